@@ -0,0 +1,124 @@
+#![no_std]
+#![no_main]
+
+use rp6::{behavior::StateMachine, *};
+
+/// Speed while driving forwards or backwards.
+const DRIVE_SPEED: i16 = 80;
+/// Speed of each motor while turning on the spot.
+const TURN_SPEED: i16 = 60;
+/// Number of the `ACS_BURSTS` bursts per side that must be reflected to detect an obstacle.
+const ACS_THRESHOLD: u8 = 4;
+/// Time to stop in front of an obstacle detected by the ACS in ms.
+const STOP_MS: u32 = 200;
+/// Time to back off from an obstacle that hit a bumper in ms.
+const BACKOFF_MS: u32 = 400;
+/// Minimum time to turn away from an obstacle in ms. The robot keeps turning afterwards until the
+/// ACS no longer detects the obstacle.
+const TURN_MS: u32 = 300;
+
+/// Events collected from the sensors in each iteration of the main loop.
+#[derive(Clone, Copy)]
+enum Event {
+    Clear,
+    /// The ACS detects an obstacle, which is `on_left` if more bursts were reflected on the left.
+    Detected {
+        on_left: bool,
+    },
+    /// A bumper hit an obstacle that the ACS missed, e.g., a dark, matte one.
+    Collision {
+        on_left: bool,
+    },
+}
+
+/// States of the obstacle avoidance behavior. The robot turns away from the side of the obstacle.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum State {
+    Forward,
+    Stop { turn_right: bool },
+    Backoff { turn_right: bool },
+    Turn { right: bool },
+}
+
+/// Transition function of the behavior: drive forwards until the ACS detects an obstacle, then stop
+/// and turn away from it until the way is clear again before resuming. As a fallback, the robot
+/// backs off before turning if a bumper hits an obstacle.
+fn transition(state: State, event: Event, time_in_state_ms: u32) -> State {
+    match (state, event) {
+        (State::Backoff { .. }, _) if time_in_state_ms < BACKOFF_MS => state,
+        (_, Event::Collision { on_left }) => State::Backoff {
+            turn_right: on_left,
+        },
+        (State::Forward, Event::Detected { on_left }) => State::Stop {
+            turn_right: on_left,
+        },
+        (State::Stop { turn_right }, _) if time_in_state_ms >= STOP_MS => {
+            State::Turn { right: turn_right }
+        }
+        (State::Backoff { turn_right }, _) => State::Turn { right: turn_right },
+        (State::Turn { .. }, Event::Clear) if time_in_state_ms >= TURN_MS => State::Forward,
+        (state, _) => state,
+    }
+}
+
+/// Collect the latest sensor event from the ACS, falling back to the bumpers.
+fn read_event() -> Event {
+    match RobotBase::bumpers() {
+        (true, _) => return Event::Collision { on_left: true },
+        (_, true) => return Event::Collision { on_left: false },
+        _ => {}
+    }
+    let acs = RobotBase::measure_acs();
+    if acs.left.max(acs.right) >= ACS_THRESHOLD {
+        Event::Detected {
+            on_left: acs.left >= acs.right,
+        }
+    } else {
+        Event::Clear
+    }
+}
+
+/// entry point for the embedded rust program
+#[entry]
+fn main() -> ! {
+    RobotBase::init();
+    RobotBase::power_on();
+    RobotBase::set_acs_power_medium();
+    // the behavior measures the time spent in each state with the system tick
+    time::init_tick(time::DEFAULT_TICK_PERIOD_US).ok();
+
+    Serial::write(
+        "\nObstacle avoidance: the robot turns away from obstacles detected by the ACS\n\n",
+    );
+
+    let mut behavior = StateMachine::new(State::Forward, transition);
+    Motors::drive(DRIVE_SPEED, DRIVE_SPEED);
+
+    // main loop: perform the actions of each newly entered state, where each iteration takes about
+    // 25ms for measuring the ACS
+    loop {
+        match behavior.step(read_event()) {
+            Some(State::Forward) => {
+                println!("forward");
+                Motors::drive(DRIVE_SPEED, DRIVE_SPEED);
+            }
+            Some(State::Stop { .. }) => {
+                println!("obstacle detected: stopping");
+                Motors::stop();
+            }
+            Some(State::Backoff { .. }) => {
+                println!("collision: backing off");
+                Motors::drive(-DRIVE_SPEED, -DRIVE_SPEED);
+            }
+            Some(State::Turn { right: true }) => {
+                println!("turning right");
+                Motors::drive(TURN_SPEED, -TURN_SPEED);
+            }
+            Some(State::Turn { right: false }) => {
+                println!("turning left");
+                Motors::drive(-TURN_SPEED, TURN_SPEED);
+            }
+            None => {}
+        }
+    }
+}
@@ -0,0 +1,128 @@
+#![no_std]
+#![no_main]
+
+use rp6::*;
+
+/// Minimal driver for the MPU-6050 IMU connected to the robot's expansion bus (I2C), built only on
+/// the public API of the `rp6` crate.
+mod imu {
+    use rp6::avr::twi::{TwiError, TwiMaster};
+
+    /// I2C address of the MPU-6050 with its pin `AD0` pulled low (`0x69` if pulled high).
+    const ADDRESS: u8 = 0x68;
+
+    /// Register addresses of the MPU-6050.
+    const ACCEL_CONFIG: u8 = 0x1C;
+    const ACCEL_XOUT_H: u8 = 0x3B;
+    const PWR_MGMT_1: u8 = 0x6B;
+    const WHO_AM_I: u8 = 0x75;
+
+    /// Raw value of 1g of the accelerometer in its default range of ±2g.
+    pub const ONE_G: i32 = 16384;
+
+    /// Errors of the IMU driver.
+    #[derive(Clone, Copy, Debug)]
+    pub enum ImuError {
+        /// The I2C transfer failed, e.g., because the IMU is not connected.
+        Bus(TwiError),
+        /// The device at `ADDRESS` is not an MPU-6050.
+        UnknownDevice(u8),
+    }
+
+    impl From<TwiError> for ImuError {
+        fn from(error: TwiError) -> Self {
+            ImuError::Bus(error)
+        }
+    }
+
+    /// Struct managing the MPU-6050. The bus must be initialized via `TwiMaster::init` first.
+    pub struct Imu;
+
+    impl Imu {
+        /// Check the identity of the IMU, wake it from sleep mode (its state after power-on) and
+        /// select the accelerometer range of ±2g.
+        pub fn init() -> Result<(), ImuError> {
+            match Self::read_register(WHO_AM_I)? {
+                0x68 => {}
+                id => return Err(ImuError::UnknownDevice(id)),
+            }
+            // clearing `SLEEP` selects the internal oscillator as clock source
+            TwiMaster::write(ADDRESS, &[PWR_MGMT_1, 0x00])?;
+            TwiMaster::write(ADDRESS, &[ACCEL_CONFIG, 0x00])?;
+            Ok(())
+        }
+
+        /// Read the acceleration along the x, y and z axes, where `ONE_G` corresponds to 1g.
+        ///
+        /// The six registers `ACCEL_XOUT_H` to `ACCEL_ZOUT_L` are read in a single burst, as the
+        /// IMU auto-increments the register address. This ensures that all axes belong to the
+        /// same sample. Each axis is a signed 16-bit value stored big-endian, i.e., high byte
+        /// first.
+        pub fn read_accel() -> Result<(i16, i16, i16), ImuError> {
+            let mut data = [0u8; 6];
+            TwiMaster::write_read(ADDRESS, &[ACCEL_XOUT_H], &mut data)?;
+            let axis = |index: usize| i16::from_be_bytes([data[index], data[index + 1]]);
+            Ok((axis(0), axis(2), axis(4)))
+        }
+
+        /// Read a single register of the IMU.
+        fn read_register(register: u8) -> Result<u8, TwiError> {
+            let mut value = [0u8];
+            TwiMaster::write_read(ADDRESS, &[register], &mut value)?;
+            Ok(value[0])
+        }
+    }
+}
+
+use imu::{Imu, ImuError, ONE_G};
+use rp6::avr::twi::{TwiError, TwiMaster, TWI_FAST_MODE_HZ};
+
+/// Convert a raw acceleration to mg.
+fn to_mg(raw: i16) -> i32 {
+    i32::from(raw) * 1000 / ONE_G
+}
+
+/// entry point for the embedded rust program
+#[entry]
+fn main() -> ! {
+    RobotBase::init();
+    TwiMaster::init(TWI_FAST_MODE_HZ);
+
+    Serial::write("\nMPU-6050 accelerometer on the I2C bus\n\n");
+    match Imu::init() {
+        Ok(()) => {}
+        Err(ImuError::Bus(TwiError::AddressNack)) => {
+            println!("IMU not found, check the wiring and the pull-up resistors");
+            RobotBase::halt();
+        }
+        Err(ImuError::Bus(_)) => {
+            println!("I2C bus error");
+            RobotBase::halt();
+        }
+        Err(ImuError::UnknownDevice(id)) => {
+            println!("Unknown device with WHO_AM_I = ", id => HEXP);
+            RobotBase::halt();
+        }
+    }
+
+    // main loop: print the acceleration of each axis in mg
+    loop {
+        match Imu::read_accel() {
+            Ok((x, y, z)) => {
+                println!(
+                    "x: ",
+                    to_mg(x),
+                    "mg | y: ",
+                    to_mg(y),
+                    "mg | z: ",
+                    to_mg(z),
+                    "mg"
+                );
+            }
+            Err(_) => {
+                println!("IMU read failed");
+            }
+        }
+        delay_ms(200);
+    }
+}
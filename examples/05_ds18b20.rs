@@ -0,0 +1,113 @@
+#![no_std]
+#![no_main]
+
+use rp6::{
+    one_wire::{crc8, OneWire, OneWireError, Rom},
+    *,
+};
+
+/// Maximum number of sensors on the bus.
+const MAX_SENSORS: usize = 4;
+
+/// Family code of the DS18B20 in the first byte of its ROM code.
+const DS18B20_FAMILY: u8 = 0x28;
+
+/// Function commands of the DS18B20.
+const CONVERT_T: u8 = 0x44;
+const READ_SCRATCHPAD: u8 = 0xBE;
+
+/// Maximum conversion time of the DS18B20 in ms at its default resolution of 12 bits.
+const CONVERSION_MS: u16 = 750;
+
+/// The bus on pin `ADC0` of the expansion port, with a 4.7kΩ pull-up resistor to `VDD`.
+type Bus = OneWire<port::ADC0>;
+
+/// Start the temperature conversion of all sensors at once and wait until it has finished, which
+/// the sensors signal by reading 1 in read slots.
+fn convert_all(bus: &Bus) -> Result<(), OneWireError> {
+    bus.reset()?;
+    bus.skip_rom();
+    bus.write_byte(CONVERT_T);
+    for _ in 0..CONVERSION_MS {
+        if bus.read_bit() {
+            break;
+        }
+        delay_ms(1);
+    }
+    Ok(())
+}
+
+/// Read the temperature of the sensor with the given `rom` code in 1/16°C, or `None` if its
+/// scratchpad is corrupted, e.g., by a loose connection.
+fn read_temperature(bus: &Bus, rom: &Rom) -> Result<Option<i16>, OneWireError> {
+    bus.reset()?;
+    bus.select(rom);
+    bus.write_byte(READ_SCRATCHPAD);
+    let mut scratchpad = [0u8; 9];
+    bus.read_bytes(&mut scratchpad);
+    if crc8(&scratchpad) != 0 {
+        return Ok(None);
+    }
+    Ok(Some(i16::from_le_bytes([scratchpad[0], scratchpad[1]])))
+}
+
+/// entry point for the embedded rust program
+#[entry]
+fn main() -> ! {
+    RobotBase::init();
+    let bus = Bus::new();
+    bus.init();
+
+    Serial::write("\nDS18B20 temperature sensors on the 1-Wire bus\n\n");
+    let mut roms: [Rom; MAX_SENSORS] = [[0; 8]; MAX_SENSORS];
+    let count = match bus.search(&mut roms) {
+        Ok(0) | Err(OneWireError::NoDevice) => {
+            println!("No sensor found, check the wiring");
+            RobotBase::halt();
+        }
+        Ok(count) => count,
+        Err(OneWireError::BusLow) => {
+            println!("Bus stuck low, check the pull-up resistor");
+            RobotBase::halt();
+        }
+        Err(_) => {
+            println!("Search failed");
+            RobotBase::halt();
+        }
+    };
+
+    // list the ROM codes of the sensors found
+    for rom in &roms[..count] {
+        print!("Found ");
+        for &byte in rom {
+            print!(byte => hex, " ");
+        }
+        if rom[0] != DS18B20_FAMILY {
+            print!("(not a DS18B20)");
+        }
+        Serial::new_line();
+    }
+
+    // main loop: print the temperature of each sensor in °C
+    loop {
+        if convert_all(&bus).is_err() {
+            println!("Sensors lost");
+        }
+        for (index, rom) in roms[..count].iter().enumerate() {
+            print!("Sensor ", index as u8, ": ");
+            match read_temperature(&bus, rom) {
+                Ok(Some(raw)) => {
+                    Serial::write_fixed(i32::from(raw) * 100 / 16, 2);
+                    println!("degC");
+                }
+                Ok(None) => {
+                    println!("CRC error");
+                }
+                Err(_) => {
+                    println!("no answer");
+                }
+            }
+        }
+        delay_ms(1000);
+    }
+}
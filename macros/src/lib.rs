@@ -32,6 +32,8 @@ mod vector {
 use syn::spanned::Spanned;
 
 /// Allows to define the entry point of the program by annotating a function with this macro.
+/// With `#[entry(resources)]`, the function receives the handle of the shared resources, see
+/// `#[resources]`.
 #[proc_macro_attribute]
 pub fn entry(
     args: proc_macro::TokenStream,
@@ -39,11 +41,16 @@ pub fn entry(
 ) -> proc_macro::TokenStream {
     let mut f = syn::parse_macro_input!(input as syn::ItemFn);
 
+    let with_resources = match parse_resources_arg(args) {
+        Err(e) => return e.to_compile_error().into(),
+        Ok(x) => x,
+    };
+
     // check the function signature
     let valid_signature = f.sig.constness.is_none()
         && f.vis == syn::Visibility::Inherited
         && f.sig.abi.is_none()
-        && f.sig.inputs.is_empty()
+        && f.sig.inputs.len() == usize::from(with_resources)
         && f.sig.generics.params.is_empty()
         && f.sig.generics.where_clause.is_none()
         && f.sig.variadic.is_none()
@@ -55,16 +62,12 @@ pub fn entry(
     if !valid_signature {
         return syn::parse::Error::new(
             f.span(),
-            "`#[entry]` function must have signature `[unsafe] fn() -> !`",
-        )
-        .to_compile_error()
-        .into();
-    }
-
-    if !args.is_empty() {
-        return syn::parse::Error::new(
-            proc_macro2::Span::call_site(),
-            "This attribute accepts no arguments",
+            if with_resources {
+                "`#[entry(resources)]` function must have signature \
+                 `[unsafe] fn(interrupt::Resources<_>) -> !`"
+            } else {
+                "`#[entry]` function must have signature `[unsafe] fn() -> !`"
+            },
         )
         .to_compile_error()
         .into();
@@ -100,7 +103,7 @@ pub fn entry(
     );
     let ident = &f.sig.ident;
 
-    let resource_args = statics
+    let mut resource_args = statics
         .iter()
         .map(|statik| {
             let (ref cfgs, ref attrs) = extract_cfgs(statik.attrs.clone());
@@ -117,6 +120,10 @@ pub fn entry(
             }
         })
         .collect::<Vec<_>>();
+    if with_resources {
+        // the handle of the shared resources is the first argument, see `#[resources]`
+        resource_args.insert(0, quote::quote!(::rp6::interrupt::Resources::new()));
+    }
 
     quote::quote! (
         #[cfg(not(any(doc, target_arch = "avr")))]
@@ -141,17 +148,19 @@ pub fn entry(
 }
 
 /// Allows to define an interrupt service routine (ISR) by annotating a function with this macro.
-/// By convention, the function must be named like the handled interrupt.
+/// By convention, the function must be named like the handled interrupt. With
+/// `#[interrupt(resources)]`, the function receives the shared resources, see `#[resources]`.
 ///
 /// For example:
 /// ```rust
 /// #[interrupt]
-/// fn INT0() {
+/// fn INT2() {
 ///     ...
 /// }
 /// ```
 ///
-/// The available interrupts on the RP6 (ATmega32) are:
+/// Note that the crate `rp6` defines some of the handlers itself, see its list of interrupt
+/// vectors. The available interrupts on the RP6 (ATmega32) are:
 /// ```
 /// RESET
 /// INT0
@@ -176,12 +185,17 @@ pub fn entry(
 /// SPM_RDY
 #[proc_macro_attribute]
 pub fn interrupt(
-    _args: proc_macro::TokenStream,
+    args: proc_macro::TokenStream,
     input: proc_macro::TokenStream,
 ) -> proc_macro::TokenStream {
     let mut f: syn::ItemFn =
         syn::parse(input).expect("`#[interrupt]` must be applied to a function");
 
+    let with_resources = match parse_resources_arg(args) {
+        Err(e) => return e.to_compile_error().into(),
+        Ok(x) => x,
+    };
+
     let fspan = f.span();
     let ident = f.sig.ident.clone();
     let ident_s = ident.to_string();
@@ -189,7 +203,7 @@ pub fn interrupt(
     let valid_signature = f.sig.constness.is_none()
         && f.vis == syn::Visibility::Inherited
         && f.sig.abi.is_none()
-        && f.sig.inputs.is_empty()
+        && f.sig.inputs.len() == usize::from(with_resources)
         && f.sig.generics.params.is_empty()
         && f.sig.generics.where_clause.is_none()
         && f.sig.variadic.is_none()
@@ -205,7 +219,12 @@ pub fn interrupt(
     if !valid_signature {
         return syn::parse::Error::new(
             fspan,
-            "`#[interrupt]` handlers must have signature `[unsafe] fn() [-> !]`",
+            if with_resources {
+                "`#[interrupt(resources)]` handlers must have signature \
+                 `[unsafe] fn(&mut _) [-> !]`"
+            } else {
+                "`#[interrupt]` handlers must have signature `[unsafe] fn() [-> !]`"
+            },
         )
         .to_compile_error()
         .into();
@@ -237,7 +256,7 @@ pub fn interrupt(
     );
     let ident = &f.sig.ident;
 
-    let resource_args = statics
+    let mut resource_args = statics
         .iter()
         .map(|statik| {
             let (ref cfgs, ref attrs) = extract_cfgs(statik.attrs.clone());
@@ -254,6 +273,10 @@ pub fn interrupt(
             }
         })
         .collect::<Vec<_>>();
+    if with_resources {
+        // handlers cannot preempt each other, so they may access the shared resources directly
+        resource_args.insert(0, quote::quote!(::rp6::interrupt::SharedResources::get()));
+    }
 
     let vect = if let Some(v) = vector::lookup_vector(&ident_s) {
         v
@@ -270,6 +293,9 @@ pub fn interrupt(
     let vector_ident_s = vector_ident.to_string();
 
     quote::quote! (
+        // the vector only exists on the AVR, the handler is still type-checked on other targets,
+        // e.g., for the unit tests on the host
+        #[cfg(target_arch = "avr")]
         #[doc(hidden)]
         #[export_name = #vector_ident_s]
         pub unsafe extern "avr-interrupt" fn #tramp_ident() {
@@ -279,11 +305,116 @@ pub fn interrupt(
         }
 
         #[doc(hidden)]
+        #[cfg_attr(not(target_arch = "avr"), allow(dead_code))]
         #f
     )
     .into()
 }
 
+/// Groups the resources shared between the entry function and interrupt handlers in a single
+/// struct, see `rp6::interrupt::shared`. Each field must be initialized with a constant expression
+/// given by an `#[init(...)]` attribute. The single instance of the struct is passed to functions
+/// annotated with `#[entry(resources)]` and `#[interrupt(resources)]`.
+///
+/// For example:
+/// ```rust
+/// #[resources]
+/// struct Shared {
+///     #[init(0)]
+///     ticks: u16,
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn resources(
+    args: proc_macro::TokenStream,
+    input: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    let mut s = syn::parse_macro_input!(input as syn::ItemStruct);
+
+    if !args.is_empty() {
+        return syn::parse::Error::new(
+            proc_macro2::Span::call_site(),
+            "This attribute accepts no arguments",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    if !s.generics.params.is_empty() {
+        return syn::parse::Error::new(
+            s.generics.span(),
+            "`#[resources]` struct must not be generic",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    let fields = match s.fields {
+        syn::Fields::Named(ref mut fields) => &mut fields.named,
+        _ => {
+            return syn::parse::Error::new(s.span(), "`#[resources]` struct must have named fields")
+                .to_compile_error()
+                .into()
+        }
+    };
+
+    // move the `#[init(...)]` attributes of the fields into the initializer of the instance
+    let mut initializers = vec![];
+    for field in fields.iter_mut() {
+        let (inits, attrs): (Vec<_>, Vec<_>) =
+            field.attrs.drain(..).partition(|attr| eq(attr, "init"));
+        field.attrs = attrs;
+        let init = match inits.as_slice() {
+            [init] => init,
+            _ => {
+                return syn::parse::Error::new(
+                    field.span(),
+                    "each field must have exactly one `#[init(...)]` attribute",
+                )
+                .to_compile_error()
+                .into()
+            }
+        };
+        let expr = match init.parse_args::<syn::Expr>() {
+            Err(e) => return e.to_compile_error().into(),
+            Ok(x) => x,
+        };
+        let ident = &field.ident;
+        initializers.push(quote::quote!(#ident: #expr));
+    }
+
+    let ident = &s.ident;
+
+    quote::quote! (
+        #s
+
+        unsafe impl ::rp6::interrupt::SharedResources for #ident {
+            unsafe fn get() -> &'static mut Self {
+                static mut RESOURCES: #ident = #ident {
+                    #(#initializers),*
+                };
+                &mut *::core::ptr::addr_of_mut!(RESOURCES)
+            }
+        }
+    )
+    .into()
+}
+
+/// Parses the arguments of `#[entry]` and `#[interrupt]`, which are either empty or `resources`,
+/// see `#[resources]`.
+fn parse_resources_arg(args: proc_macro::TokenStream) -> Result<bool, syn::parse::Error> {
+    if args.is_empty() {
+        return Ok(false);
+    }
+    match syn::parse::<syn::Ident>(args) {
+        Ok(ident) if ident == "resources" => Ok(true),
+        _ => Err(syn::parse::Error::new(
+            proc_macro2::Span::call_site(),
+            "This attribute accepts no arguments or `resources`",
+        )),
+    }
+}
+
 /// Extracts `static mut` vars from the beginning of the given statements
 fn extract_static_muts(
     stmts: impl IntoIterator<Item = syn::Stmt>,
@@ -0,0 +1,59 @@
+//! Lightweight assertions that report failures over the `Serial` connection.
+//!
+//! In contrast to `assert!`, the `rp6_assert!` macro does not invoke the generic panic machinery
+//! with its formatting, which results in much smaller code. On failure, the robot is halted in a
+//! safe state, see `RobotBase::halt`. With the feature `release-no-assert`, all assertions
+//! compile to nothing.
+
+use crate::{print, RobotBase, Serial};
+
+/// Report a failed assertion over the `Serial` connection and halt the robot. Called by the
+/// `rp6_assert!` macro.
+#[doc(hidden)]
+#[inline(never)]
+pub fn assertion_failed(message: &str, file: &str, line: u32) -> ! {
+    print!("\nASSERTION FAILED: ", message, " (", file, ":");
+    Serial::write_dec_u32(line);
+    Serial::write(")\n");
+    RobotBase::halt()
+}
+
+/// Check that the given condition holds. Otherwise, write the message (or the condition, if no
+/// message is given), file, and line over the `Serial` connection and halt the robot in a safe
+/// state, see `RobotBase::halt`.
+///
+/// Example:
+/// ```rust
+/// rp6_assert!(speed <= Motors::MAX_SPEED, "speed out of range");
+/// ```
+///
+/// With the feature `release-no-assert`, the condition is neither evaluated nor checked.
+#[cfg(not(feature = "release-no-assert"))]
+#[macro_export]
+macro_rules! rp6_assert {
+    ($condition: expr $(,)?) => {
+        $crate::rp6_assert!($condition, stringify!($condition));
+    };
+    ($condition: expr, $message: expr $(,)?) => {
+        if !$condition {
+            $crate::assertions::assertion_failed($message, file!(), line!());
+        }
+    };
+}
+
+/// Check that the given condition holds. Disabled by the feature `release-no-assert`, i.e., the
+/// condition is neither evaluated nor checked.
+#[cfg(feature = "release-no-assert")]
+#[macro_export]
+macro_rules! rp6_assert {
+    ($condition: expr $(,)?) => {
+        $crate::rp6_assert!($condition, "");
+    };
+    ($condition: expr, $message: expr $(,)?) => {
+        // type-check the arguments without evaluating them
+        if false {
+            let _: bool = $condition;
+            let _: &str = $message;
+        }
+    };
+}
@@ -0,0 +1,228 @@
+//! ADC = "Analog to Digital Converter"
+//!
+//! This module implements blocking single conversions of the ADC channels `ADC0` to `ADC7` (pins
+//! `a0` to `a7`) against the supply voltage `AVCC` as reference, as well as the estimation of the
+//! supply voltage itself from the internal bandgap reference. Conversions against the internal
+//! 2.56V reference are supported as well. Moreover, conversions can be auto-triggered by other
+//! peripherals, e.g., a timer, see `Adc::set_trigger_source`.
+
+use super::{
+    bitmasks::{ADATE, ADEN, ADIF, ADPS1, ADPS2, ADSC, ADTS, MUX, REFS, REFS0},
+    interrupt,
+    registers::{ADCH, ADCL, ADCSRA, ADMUX, SFIOR},
+    Register,
+};
+use crate::{delay_us, rp6_assert};
+
+/// Voltage of the internal bandgap reference in mV. The datasheet of the atmega32 specifies
+/// 1.15V to 1.35V, i.e., for precise measurements, calibrate this value per device by comparing
+/// `Adc::read_vcc_mv` with a multimeter.
+pub const BANDGAP_MV: u32 = 1220;
+
+/// Time in µs to wait after selecting the bandgap reference as ADC input before converting, as
+/// the bandgap needs up to 70µs to start up.
+const BANDGAP_SETTLING_US: u32 = 70;
+
+/// Time in µs to wait after switching the voltage reference, e.g., from the internal 2.56V
+/// reference to `AVCC`, until the capacitor on the `AREF` pin has settled.
+const REFERENCE_SETTLING_US: u32 = 1000;
+
+/// `MUX` selection of the internal bandgap reference as ADC input.
+const MUX_BANDGAP: u8 = 0b11110;
+
+/// Sources starting auto-triggered conversions, see `Adc::set_trigger_source`. A conversion is
+/// started by the rising edge of the interrupt flag of the source, i.e., the flag has to be cleared
+/// before the next event, e.g., by executing the source's interrupt handler.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AdcTrigger {
+    /// Each conversion is started as soon as the previous one has completed.
+    FreeRunning,
+    /// The analog comparator's output toggles.
+    AnalogComparator,
+    /// The external interrupt `INT0` occurs.
+    ExternalInterrupt0,
+    /// The counter of `Timer0` matches its output compare register, e.g., on every system tick.
+    Timer0CompareMatch,
+    /// The counter of `Timer0` overflows.
+    Timer0Overflow,
+    /// The counter of `Timer1` matches `OCR1B`.
+    Timer1CompareMatchB,
+    /// The counter of `Timer1` overflows.
+    Timer1Overflow,
+    /// `Timer1` captures an input event.
+    Timer1Capture,
+}
+
+impl AdcTrigger {
+    /// Bits of the trigger source in `SFIOR`.
+    fn bits(self) -> u8 {
+        use AdcTrigger::*;
+
+        let source = match self {
+            FreeRunning => 0b000,
+            AnalogComparator => 0b001,
+            ExternalInterrupt0 => 0b010,
+            Timer0CompareMatch => 0b011,
+            Timer0Overflow => 0b100,
+            Timer1CompareMatchB => 0b101,
+            Timer1Overflow => 0b110,
+            Timer1Capture => 0b111,
+        };
+        (source << 5) & ADTS
+    }
+}
+
+/// Struct managing all access to the ADC.
+pub struct Adc;
+
+impl Adc {
+    /// Number of steps of a conversion result, i.e., results range from 0 to `RESOLUTION - 1`.
+    pub const RESOLUTION: u32 = 1024;
+
+    /// Convert the voltage on the given ADC `channel` (0 to 7) against `AVCC` as reference.
+    /// Blocks for the duration of the conversion, i.e., about 110µs. Higher channel numbers are
+    /// truncated to 3 bits.
+    pub fn read(channel: u8) -> u16 {
+        Self::select(channel & 0x07, REFS0);
+        Self::convert()
+    }
+
+    /// Convert the voltages on the given ADC `channels` (0 to 7 each) against `AVCC` in one pass
+    /// and store the results in `out`, in the same order, e.g., for the battery voltage, the motor
+    /// currents and the light sensors in each iteration of the main loop:
+    /// ```rust
+    /// let mut results = [0; 5];
+    /// // UBAT, MCURRENT_L, MCURRENT_R, LS_L, LS_R
+    /// Adc::scan(&[7, 5, 6, 3, 2], &mut results);
+    /// ```
+    ///
+    /// After switching the multiplexer, the sample-and-hold capacitor still carries charge of the
+    /// previous channel, which falsifies the next conversion of a high-impedance source like the
+    /// light sensors. Hence, the first conversion after each change of the channel is discarded as
+    /// settling sample, i.e., each channel takes two conversions (about 220µs), whereas repeating a
+    /// channel right after itself takes a single one, e.g., to average it. The ADC stays enabled
+    /// and the reference is checked only once for the whole scan, instead of for each `Adc::read`.
+    ///
+    /// The lengths of `channels` and `out` must be equal, which fails an `rp6_assert!` otherwise.
+    pub fn scan(channels: &[u8], out: &mut [u16]) {
+        rp6_assert!(
+            channels.len() == out.len(),
+            "number of channels and results differ"
+        );
+        let Some(&first) = channels.first() else {
+            return;
+        };
+        Self::select(first & 0x07, REFS0);
+        let mut selected = None;
+        for (&channel, result) in channels.iter().zip(out) {
+            let channel = channel & 0x07;
+            if selected != Some(channel) {
+                ADMUX::write(REFS0 | channel);
+                // settling sample
+                Self::convert();
+                selected = Some(channel);
+            }
+            *result = Self::convert();
+        }
+    }
+
+    /// Convert the voltage on the given ADC `channel` (0 to 7) against the internal 2.56V
+    /// reference, e.g., for small voltages. Otherwise, behaves like `Adc::read`, plus 1ms if the
+    /// reference had to be switched. Note that `AVCC` must not be connected to the `AREF` pin
+    /// externally.
+    pub fn read_with_internal_reference(channel: u8) -> u16 {
+        Self::select(channel & 0x07, REFS);
+        Self::convert()
+    }
+
+    /// Estimate the supply voltage `AVCC` in mV by converting the internal bandgap reference
+    /// (`BANDGAP_MV`) against `AVCC`: as `result = BANDGAP_MV * RESOLUTION / AVCC`, the supply
+    /// voltage is `AVCC = BANDGAP_MV * RESOLUTION / result`. This allows to compensate other
+    /// readings for variations of the supply voltage, e.g., a sagging battery, via
+    /// `Adc::to_mv`, without additional hardware.
+    ///
+    /// Blocks for at least two conversions, as the first one after selecting the bandgap is
+    /// discarded, plus 1ms if the reference had to be switched to `AVCC`.
+    pub fn read_vcc_mv() -> u16 {
+        Self::select(MUX_BANDGAP, REFS0);
+        delay_us(BANDGAP_SETTLING_US);
+        Self::convert();
+        let result = u32::from(Self::convert()).max(1);
+        (BANDGAP_MV * Self::RESOLUTION / result).min(u16::MAX as u32) as u16
+    }
+
+    /// Convert a conversion `result` to mV, given the supply voltage `vcc_mv`, e.g., as estimated
+    /// by `Adc::read_vcc_mv`.
+    pub fn to_mv(result: u16, vcc_mv: u16) -> u16 {
+        (u32::from(result) * u32::from(vcc_mv) / Self::RESOLUTION) as u16
+    }
+
+    /// Select the source of auto-triggered conversions, see `Adc::start_auto_trigger`. Of the
+    /// shared register `SFIOR`, the ADC owns only the trigger select bits `ADTS`, whereas the
+    /// prescaler reset bits `PSR10` and `PSR2` are owned by the timers (see
+    /// `Timer0::reset_prescaler`), and the bits `ACME` (analog comparator) and `PUD` (pull-up
+    /// disable) are not used by this crate. Hence, only `ADTS` is modified.
+    pub fn set_trigger_source(trigger: AdcTrigger) {
+        interrupt::without_interrupts(|_| {
+            SFIOR::write(SFIOR::read() & !ADTS | trigger.bits());
+        });
+    }
+
+    /// Start auto-triggered conversions of the given ADC `channel` (0 to 7) against `AVCC`, each
+    /// started by an event of the source selected via `Adc::set_trigger_source`, e.g., to sample
+    /// at the exact rate of a timer without jitter. Fetch the results via `Adc::take_result`. A
+    /// free-running conversion sequence is started right away.
+    ///
+    /// Single conversions, e.g., via `Adc::read`, stop auto-triggering, so they must not be used
+    /// meanwhile.
+    pub fn start_auto_trigger(channel: u8) {
+        Self::select(channel & 0x07, REFS0);
+        let free_running = SFIOR::read() & ADTS == AdcTrigger::FreeRunning.bits();
+        // clear a stale conversion complete flag by writing a logical one
+        ADCSRA::write(ADEN | ADATE | ADIF | ADPS2 | ADPS1 | if free_running { ADSC } else { 0 });
+    }
+
+    /// Stop auto-triggered conversions, see `Adc::start_auto_trigger`. A conversion in progress is
+    /// completed.
+    pub fn stop_auto_trigger() {
+        ADCSRA::unset_mask_raw(ADATE);
+    }
+
+    /// Take the result of the latest auto-triggered conversion, or `None` if no conversion has
+    /// completed since the last call. If several conversions completed in between, only the latest
+    /// result is returned.
+    pub fn take_result() -> Option<u16> {
+        if !ADCSRA::is_mask_set_raw(ADIF) {
+            return None;
+        }
+        // `ADCL` must be read first, which locks `ADCH` until it is read
+        let low = ADCL::read();
+        let high = ADCH::read();
+        // clear the flag by writing a logical one, without starting a conversion
+        ADCSRA::write(ADCSRA::read() & !ADSC | ADIF);
+        Some(u16::from_le_bytes([low, high]))
+    }
+
+    /// Select the ADC input given by `mux` with the given `reference`, i.e., `REFS0` for `AVCC` or
+    /// `REFS` for the internal 2.56V reference. If this switches the reference, waits until the
+    /// `AREF` pin has settled.
+    fn select(mux: u8, reference: u8) {
+        let reference_changed = ADMUX::read() & REFS != reference;
+        ADMUX::write(reference | (mux & MUX));
+        if reference_changed {
+            delay_us(REFERENCE_SETTLING_US);
+        }
+    }
+
+    /// Enable the ADC with a clock of 125kHz (prescaler 64 at 8MHz), run a single conversion of
+    /// the selected input and return its result.
+    fn convert() -> u16 {
+        // clear a stale conversion complete flag by writing a logical one
+        ADCSRA::write(ADEN | ADSC | ADIF | ADPS2 | ADPS1);
+        while ADCSRA::is_mask_set_raw(ADSC) {}
+        // `ADCL` must be read first, which locks `ADCH` until it is read
+        let low = ADCL::read();
+        let high = ADCH::read();
+        u16::from_le_bytes([low, high])
+    }
+}
@@ -0,0 +1,168 @@
+//! Pulse-width measurement using the input capture unit of `Timer1` on the `ICP1` pin (`d6`).
+//!
+//! Useful for reading sensors that report their measurement as the width of a pulse, e.g.,
+//! ultrasonic range sensors (HC-SR04) or sensors with a PWM output. Note that on the RP6, `ICP1`
+//! is shared with the `ACS_Pwr` pin, so the ACS must be turned off while measuring. Furthermore,
+//! `Timer1` generates the motors' PWM signals, so the motors are stopped during a measurement.
+
+use super::{
+    bitmasks::{CS11, ICES1, ICF1, TOV1},
+    config::CPU_FREQUENCY_HZ,
+    port::d6 as ICP1,
+    registers::{TCCR1A, TCCR1B, TIFR},
+    timers::Timer1,
+    Pin, Register,
+};
+
+/// Frequency of the ticks returned by `measure_pulse`, i.e., 1MHz (1µs per tick) at 8MHz.
+pub const CAPTURE_TICK_HZ: u32 = CPU_FREQUENCY_HZ / 8;
+
+/// The level of a pulse to measure.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PulseLevel {
+    /// The pulse starts with a rising edge and ends with a falling edge.
+    High,
+    /// The pulse starts with a falling edge and ends with a rising edge.
+    Low,
+}
+
+/// Measures the width of the next pulse with the given `level` on the `ICP1` pin in ticks of
+/// `CAPTURE_TICK_HZ`. The edges are timestamped by the input capture unit, so the measurement is
+/// not affected by interrupts, as long as the pulse is longer than the handling of interrupts.
+///
+/// Returns `None` if the pulse does not start within 65536 ticks (about 65ms at 8MHz) or if it is
+/// longer than `u16::MAX` ticks. `Timer1` is temporarily reconfigured and restored afterwards.
+pub fn measure_pulse(level: PulseLevel) -> Option<u16> {
+    ICP1::set_input();
+
+    let leading_edge = match level {
+        PulseLevel::High => ICES1,
+        PulseLevel::Low => 0,
+    };
+    let captured = || TIFR::is_mask_set_raw(ICF1).then(Timer1::read_icr1);
+
+    with_timer1(leading_edge, || {
+        wait_for_edge(captured).and_then(|start| {
+            // capture the trailing edge, changing the edge may set `ICF1` which must be cleared
+            TCCR1B::write((leading_edge ^ ICES1) | CS11);
+            TIFR::write(ICF1 | TOV1);
+            measure_until_edge(start, captured)
+        })
+    })
+}
+
+/// Measures the width of the next pulse with the given `level` on an arbitrary pin `P` in ticks
+/// of `CAPTURE_TICK_HZ`, using `Timer1` as timebase while polling the pin. In contrast to
+/// `measure_pulse`, interrupts delay the detection of the edges, so it is recommended to disable
+/// interrupts for accurate measurements of short pulses.
+///
+/// Returns `None` under the same conditions as `measure_pulse`.
+pub fn measure_pulse_on<P: Pin>(level: PulseLevel) -> Option<u16> {
+    P::set_input();
+
+    let is_active = move || P::is_high() == (level == PulseLevel::High);
+
+    with_timer1(0, || {
+        // a pulse that is already in progress is skipped
+        wait_for_edge(|| (!is_active()).then_some(0))?;
+        let start = wait_for_edge(|| is_active().then(Timer1::read_counter))?;
+        measure_until_edge(start, || (!is_active()).then(Timer1::read_counter))
+    })
+}
+
+/// Runs `f` while `Timer1` counts from zero in normal mode with a prescaler of 8. The given
+/// `control_b` is additionally set in `TCCR1B`. The configuration and the counter of `Timer1` are
+/// restored afterwards, as a counter left above the top value of the motors' PWM, e.g., `ICR1`,
+/// would first run up to `0xFFFF` with the outputs frozen, see `Motors::set_pwm_mode`.
+fn with_timer1<T>(control_b: u8, f: impl FnOnce() -> T) -> T {
+    // save the configuration of Timer1 and stop it
+    let (saved_a, saved_b) = (TCCR1A::read(), TCCR1B::read());
+    TCCR1B::write(0);
+    let saved_counter = Timer1::read_counter();
+    TCCR1A::write(0);
+    Timer1::write_counter(0);
+    TCCR1B::write(control_b);
+
+    // clear stale flags (by writing a logical one) and start Timer1
+    TIFR::write(ICF1 | TOV1);
+    TCCR1B::write(control_b | CS11);
+
+    let result = f();
+
+    // restore the configuration of Timer1 while it is stopped
+    TCCR1B::write(0);
+    Timer1::write_counter(saved_counter);
+    TCCR1A::write(saved_a);
+    TCCR1B::write(saved_b);
+
+    result
+}
+
+/// Waits until `edge` returns the timestamp of the leading edge of a pulse. Returns `None` if
+/// `Timer1` overflows before, i.e., after 65536 ticks.
+fn wait_for_edge(mut edge: impl FnMut() -> Option<u16>) -> Option<u16> {
+    loop {
+        if let Some(timestamp) = edge() {
+            return Some(timestamp);
+        }
+        if TIFR::is_mask_set_raw(TOV1) {
+            return None;
+        }
+    }
+}
+
+/// Waits until `edge` returns the timestamp of the trailing edge of a pulse started at `start`
+/// and returns the width of the pulse. Timer overflows are tracked, returning `None` as soon as
+/// the pulse is longer than `u16::MAX` ticks.
+fn measure_until_edge(start: u16, mut edge: impl FnMut() -> Option<u16>) -> Option<u16> {
+    let mut overflowed = false;
+    loop {
+        if let Some(end) = edge() {
+            // an overflow at the same time only counts if it happened before the edge
+            if TIFR::is_mask_set_raw(TOV1) && end < 0x8000 {
+                overflowed = true;
+            }
+            return if !overflowed || end < start {
+                Some(end.wrapping_sub(start))
+            } else {
+                None
+            };
+        }
+        if TIFR::is_mask_set_raw(TOV1) {
+            TIFR::write(TOV1);
+            overflowed = true;
+        }
+        if overflowed && Timer1::read_counter() >= start {
+            return None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::with_timer1;
+    use crate::{
+        avr::{
+            mock,
+            registers::{TCCR1A, TCCR1B},
+            timers::Timer1,
+        },
+        Register,
+    };
+
+    #[test]
+    fn restores_the_configuration_and_counter() {
+        mock::reset();
+        // e.g., the motors' PWM with `ICR1` as top value
+        mock::preset::<TCCR1A>(0b1010_0010);
+        mock::preset::<TCCR1B>(0b0001_0001);
+        Timer1::write_counter(100);
+
+        // the measurement leaves the counter above the top value
+        with_timer1(0, || Timer1::write_counter(0xFFF0));
+
+        assert_eq!(TCCR1A::read(), 0b1010_0010);
+        assert_eq!(TCCR1B::read(), 0b0001_0001);
+        assert_eq!(Timer1::read_counter(), 100);
+    }
+}
@@ -288,3 +288,59 @@ bitmask_list!(
     /// Bitfield on register SREG
     Z,
 );
+
+// The following bitmasks of the timers are not provided by `avrd`, hence they are defined
+// manually according to the atmega32 datasheet.
+
+/// Bitfield on register TIMSK
+pub const OCIE0: u8 = 1 << 1;
+/// Bitfield on register TIMSK
+pub const OCIE2: u8 = 1 << 7;
+/// Bitfield on register TIFR
+pub const OCF0: u8 = 1 << 1;
+/// Bitfield on register TIFR
+pub const OCF2: u8 = 1 << 7;
+/// Bitfield on register TCCR1B
+pub const CS10: u8 = 1 << 0;
+/// Bitfield on register TCCR1A
+pub const COM1A1: u8 = 1 << 7;
+/// Bitfield on register TCCR1A
+pub const COM1B1: u8 = 1 << 5;
+/// Bitfield on register TCCR1A
+pub const WGM11: u8 = 1 << 1;
+/// Bitfield on register TCCR1B
+pub const WGM13: u8 = 1 << 4;
+/// Bitfield on register TCCR1B
+pub const CS11: u8 = 1 << 1;
+/// Bitfield on register GIFR
+pub const INTF0: u8 = 1 << 6;
+/// Bitfield on register GIFR
+pub const INTF1: u8 = 1 << 7;
+/// Bitfield on register TIFR
+pub const TOV0: u8 = 1 << 0;
+/// Bitfield on register TIFR
+pub const TOV2: u8 = 1 << 6;
+/// Bitfield on register ADMUX
+pub const REFS0: u8 = 1 << 6;
+/// Bitfield on register ADCSRA
+pub const ADPS1: u8 = 1 << 1;
+/// Bitfield on register ADCSRA
+pub const ADPS2: u8 = 1 << 2;
+/// Bitfield on register TCCR1B
+pub const WGM12: u8 = 1 << 3;
+/// Bitfield on register MCUCR
+pub const ISC00: u8 = 1 << 0;
+/// Bitfield on register MCUCR
+pub const ISC10: u8 = 1 << 2;
+/// Bitfield on register TIMSK
+pub const TOIE2: u8 = 1 << 6;
+/// Bitfield on register TCCR2
+pub const CS20: u8 = 1 << 0;
+/// Bitfield on register TCCR2
+pub const CS22: u8 = 1 << 2;
+/// Bitfield on register SFIOR
+pub const ADTS: u8 = 0b111 << 5;
+/// Bitfield on register SFIOR
+pub const PSR2: u8 = 1 << 1;
+/// Bitfield on register SFIOR
+pub const PSR10: u8 = 1 << 0;
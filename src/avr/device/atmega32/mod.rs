@@ -4,3 +4,4 @@ use avrd::atmega32 as avr_device;
 pub mod bitmasks;
 pub mod port;
 pub mod registers;
+pub mod timers;
@@ -4,7 +4,7 @@
 use super::{
     super::{
         pin::{pin, port},
-        Pin,
+        Pin, Register,
     },
     registers::*,
 };
@@ -13,3 +13,27 @@ port!(A);
 port!(B);
 port!(C);
 port!(D);
+
+/// The IO ports of the atmega32, allowing to select pins at runtime, see `DynPin`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Port {
+    A,
+    B,
+    C,
+    D,
+}
+
+impl Port {
+    /// All ports of the atmega32, indexed by their number as used by `DynPin::from_byte`.
+    pub const ALL: [Port; 4] = [Port::A, Port::B, Port::C, Port::D];
+
+    /// Addresses of the `DDR`, `PORT` and `PIN` registers of the port.
+    pub fn registers(self) -> (*mut u8, *mut u8, *mut u8) {
+        match self {
+            Port::A => (DDRA::ADDRESS, PORTA::ADDRESS, PINA::ADDRESS),
+            Port::B => (DDRB::ADDRESS, PORTB::ADDRESS, PINB::ADDRESS),
+            Port::C => (DDRC::ADDRESS, PORTC::ADDRESS, PINC::ADDRESS),
+            Port::D => (DDRD::ADDRESS, PORTD::ADDRESS, PIND::ADDRESS),
+        }
+    }
+}
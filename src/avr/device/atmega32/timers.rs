@@ -0,0 +1,197 @@
+//! Describes the timers available on the atmega32.
+
+use super::{
+    super::{
+        super::{
+            interrupt::{self, mutex::Mutex},
+            modules::Timer8,
+        },
+        Register, RegisterBits,
+    },
+    bitmasks::{OCF0, OCF2, OCIE0, OCIE2, PSR10, PSR2, WGM00, WGM01, WGM20, WGM21},
+    registers::*,
+};
+#[cfg(feature = "timer2-one-shot")]
+use avr_macros::interrupt;
+
+/// The 8-bit Timer/Counter0. Note that the atmega32 only provides a single control register and a
+/// single output compare unit, which are used for both `ControlA`/`ControlB` and
+/// `CompareA`/`CompareB`, respectively.
+pub struct Timer0;
+
+/// Handler of the pending one-shot of `Timer0`.
+static TIMER0_ONE_SHOT: Mutex<Option<fn()>> = Mutex::new(None);
+
+impl Timer8 for Timer0 {
+    type CompareA = OCR0;
+    type CompareB = OCR0;
+    type Counter = TCNT0;
+    type ControlA = TCCR0;
+    type ControlB = TCCR0;
+    type InterruptMask = TIMSK;
+    type InterruptFlag = TIFR;
+
+    const CS0: RegisterBits<Self::ControlB> = RegisterBits::new(1 << 0);
+    const CS1: RegisterBits<Self::ControlB> = RegisterBits::new(1 << 1);
+    const CS2: RegisterBits<Self::ControlB> = RegisterBits::new(1 << 2);
+
+    const WGM0: RegisterBits<Self::ControlA> = RegisterBits::new(WGM00);
+    const WGM1: RegisterBits<Self::ControlA> = RegisterBits::new(WGM01);
+    // The atmega32 does not provide a third waveform generation mode bit.
+    const WGM2: RegisterBits<Self::ControlB> = RegisterBits::new(0);
+
+    const OCIEA: RegisterBits<Self::InterruptMask> = RegisterBits::new(OCIE0);
+    const OCFA: RegisterBits<Self::InterruptFlag> = RegisterBits::new(OCF0);
+
+    // the output compare interrupt is handled by the system tick in `crate::time`
+    const ONE_SHOT: bool = true;
+
+    fn one_shot_handler() -> &'static Mutex<Option<fn()>> {
+        &TIMER0_ONE_SHOT
+    }
+}
+
+impl Timer0 {
+    /// Reset the prescaler shared by `Timer0` and `Timer1` via the bit `PSR10` of `SFIOR`, e.g.,
+    /// to start a timer in sync with an event: the next prescaled clock tick then occurs exactly
+    /// one prescaler period later. Note that this affects both timers. Of `SFIOR`, the timers own
+    /// only the prescaler reset bits, see `Adc::set_trigger_source`.
+    pub fn reset_prescaler() {
+        // `PSR10` clears itself, so only the other bits are preserved
+        interrupt::without_interrupts(|_| SFIOR::write(SFIOR::read() & !PSR2 | PSR10));
+    }
+}
+
+/// The 8-bit Timer/Counter2. Note that the atmega32 only provides a single control register and a
+/// single output compare unit, which are used for both `ControlA`/`ControlB` and
+/// `CompareA`/`CompareB`, respectively.
+pub struct Timer2;
+
+/// Handler of the pending one-shot of `Timer2`.
+static TIMER2_ONE_SHOT: Mutex<Option<fn()>> = Mutex::new(None);
+
+impl Timer8 for Timer2 {
+    type CompareA = OCR2;
+    type CompareB = OCR2;
+    type Counter = TCNT2;
+    type ControlA = TCCR2;
+    type ControlB = TCCR2;
+    type InterruptMask = TIMSK;
+    type InterruptFlag = TIFR;
+
+    const CS0: RegisterBits<Self::ControlB> = RegisterBits::new(1 << 0);
+    const CS1: RegisterBits<Self::ControlB> = RegisterBits::new(1 << 1);
+    const CS2: RegisterBits<Self::ControlB> = RegisterBits::new(1 << 2);
+
+    const WGM0: RegisterBits<Self::ControlA> = RegisterBits::new(WGM20);
+    const WGM1: RegisterBits<Self::ControlA> = RegisterBits::new(WGM21);
+    // The atmega32 does not provide a third waveform generation mode bit.
+    const WGM2: RegisterBits<Self::ControlB> = RegisterBits::new(0);
+
+    const OCIEA: RegisterBits<Self::InterruptMask> = RegisterBits::new(OCIE2);
+    const OCFA: RegisterBits<Self::InterruptFlag> = RegisterBits::new(OCF2);
+
+    const ONE_SHOT: bool = cfg!(feature = "timer2-one-shot");
+
+    fn one_shot_handler() -> &'static Mutex<Option<fn()>> {
+        &TIMER2_ONE_SHOT
+    }
+}
+
+impl Timer2 {
+    /// Reset the prescaler of `Timer2` via the bit `PSR2` of `SFIOR`, see
+    /// `Timer0::reset_prescaler`. In asynchronous operation, the reset takes effect in the clock
+    /// domain of the crystal, i.e., with a delay.
+    pub fn reset_prescaler() {
+        // `PSR2` clears itself, so only the other bits are preserved
+        interrupt::without_interrupts(|_| SFIOR::write(SFIOR::read() & !PSR10 | PSR2));
+    }
+}
+
+/// The 16-bit Timer/Counter1, e.g., generating the motor PWM, see `Motors`.
+///
+/// Its 16-bit registers are accessed as pairs of 8-bit registers, which share a single `TEMP`
+/// register for the high byte: reading the low byte latches the high byte into `TEMP`, and writing
+/// the low byte writes `TEMP` to the high byte. Hence, the low byte must be read first and written
+/// last. As `TEMP` is shared by all 16-bit registers of `Timer1`, an interrupt handler accessing
+/// any of them between the two byte accesses corrupts the high byte. Therefore, the accessors of
+/// `Timer1` perform both byte accesses within a `CriticalSection`. Note that writing only the low
+/// byte also writes `TEMP` to the high byte, i.e., 8-bit values must be written as full `u16`.
+pub struct Timer1;
+
+impl Timer1 {
+    /// Reset the prescaler shared by `Timer0` and `Timer1`, see `Timer0::reset_prescaler`.
+    pub fn reset_prescaler() {
+        Timer0::reset_prescaler();
+    }
+
+    /// Read the counter `TCNT1`.
+    pub fn read_counter() -> u16 {
+        read16::<TCNT1H, TCNT1L>()
+    }
+
+    /// Write the counter `TCNT1`.
+    pub fn write_counter(value: u16) {
+        write16::<TCNT1H, TCNT1L>(value);
+    }
+
+    /// Read the output compare register `OCR1A`.
+    pub fn read_ocr1a() -> u16 {
+        read16::<OCR1AH, OCR1AL>()
+    }
+
+    /// Write the output compare register `OCR1A`.
+    pub fn write_ocr1a(value: u16) {
+        write16::<OCR1AH, OCR1AL>(value);
+    }
+
+    /// Read the output compare register `OCR1B`.
+    pub fn read_ocr1b() -> u16 {
+        read16::<OCR1BH, OCR1BL>()
+    }
+
+    /// Write the output compare register `OCR1B`.
+    pub fn write_ocr1b(value: u16) {
+        write16::<OCR1BH, OCR1BL>(value);
+    }
+
+    /// Read the input capture register `ICR1`.
+    pub fn read_icr1() -> u16 {
+        read16::<ICR1H, ICR1L>()
+    }
+
+    /// Write the input capture register `ICR1`, e.g., the top value of the PWM modes.
+    pub fn write_icr1(value: u16) {
+        write16::<ICR1H, ICR1L>(value);
+    }
+}
+
+/// Read the 16-bit register consisting of `High` and `Low` without being interrupted, low byte
+/// first, see `Timer1`.
+#[inline(always)]
+fn read16<High: Register<T = u8>, Low: Register<T = u8>>() -> u16 {
+    interrupt::without_interrupts(|_| {
+        let low = Low::read();
+        let high = High::read();
+        u16::from_le_bytes([low, high])
+    })
+}
+
+/// Write the 16-bit register consisting of `High` and `Low` without being interrupted, high byte
+/// first, see `Timer1`.
+#[inline(always)]
+fn write16<High: Register<T = u8>, Low: Register<T = u8>>(value: u16) {
+    let [low, high] = value.to_le_bytes();
+    interrupt::without_interrupts(|_| {
+        High::write(high);
+        Low::write(low);
+    });
+}
+
+// Note: the `TIMER0_COMP` interrupt is defined by the system tick in `crate::time`.
+
+#[cfg(feature = "timer2-one-shot")]
+#[interrupt]
+fn TIMER2_COMP() {
+    Timer2::on_compare_match();
+}
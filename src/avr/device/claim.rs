@@ -0,0 +1,42 @@
+//! Keeps track of the pins claimed by peripherals, e.g., `RX` and `TX` by the UART. Using a
+//! claimed pin as plain `Pin` silently breaks the peripheral, so this is checked by an
+//! `rp6_assert!` in debug builds.
+use super::DynPin;
+use crate::{interrupt, interrupt::mutex::Mutex, rp6_assert};
+
+/// Pins claimed by a peripheral, one bit per pin, see `DynPin::claim_bit`.
+static CLAIMED: Mutex<u32> = Mutex::new(0);
+
+impl DynPin {
+    /// Mark the pin as claimed by a peripheral, which is done by the drivers of this crate, e.g.,
+    /// `Serial::init`. Until released again, reconfiguring or driving the pin (via `Pin` or
+    /// `DynPin`) fails an `rp6_assert!` in debug builds.
+    pub fn claim(self) {
+        let bit = self.claim_bit();
+        interrupt::without_interrupts(|cs| CLAIMED.lock(cs).update(|claimed| claimed | bit));
+    }
+
+    /// Release a claimed pin, e.g., after disabling the peripheral that used it.
+    pub fn release(self) {
+        let bit = self.claim_bit();
+        interrupt::without_interrupts(|cs| CLAIMED.lock(cs).update(|claimed| claimed & !bit));
+    }
+
+    /// Check if the pin is currently claimed by a peripheral.
+    pub fn is_claimed(self) -> bool {
+        interrupt::without_interrupts(|cs| CLAIMED.lock(cs).get() & self.claim_bit() != 0)
+    }
+
+    /// Check that the pin is not claimed by a peripheral before it is used as plain pin. Compiles
+    /// to nothing in release builds.
+    #[inline(always)]
+    pub(crate) fn assert_unclaimed(self) {
+        #[cfg(debug_assertions)]
+        rp6_assert!(!self.is_claimed(), "pin claimed by a peripheral");
+    }
+
+    /// Bit of the pin in `CLAIMED`, i.e., 8 bits per port in the order of `Port::ALL`.
+    fn claim_bit(self) -> u32 {
+        1 << (self.port() as u8 * 8 + self.offset())
+    }
+}
@@ -0,0 +1,136 @@
+use super::{current_device::port::Port, Pin, Register};
+use core::ptr::{read_volatile, write_volatile};
+
+/// An IO pin that is selected at runtime, e.g., from a configuration byte stored in the EEPROM.
+/// Holds the pin's `Port` and its bit offset, and accesses the pin's registers via their addresses.
+///
+/// Prefer the zero-sized `Pin` types whenever the pin is known at compile time: those compile to
+/// single instructions (`SBI`, `CBI`, `SBIS`, ...), which are also atomic w.r.t. interrupts. In
+/// contrast, a `DynPin` needs to look up the register addresses and performs non-atomic
+/// read-modify-write operations, i.e., interrupt handlers modifying the same port must be guarded,
+/// e.g., by `interrupt::without_interrupts`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DynPin {
+    port: Port,
+    offset: u8,
+}
+
+impl DynPin {
+    /// Select the pin with the given `offset` (0 to 7) of `port`. Returns `None` for an invalid
+    /// offset.
+    pub const fn new(port: Port, offset: u8) -> Option<Self> {
+        if offset < 8 {
+            Some(Self { port, offset })
+        } else {
+            None
+        }
+    }
+
+    /// Convert a static `Pin` type to a `DynPin`.
+    pub fn of<P: Pin>() -> Self {
+        let port = Port::ALL
+            .into_iter()
+            .find(|port| port.registers().1 == P::PORT::ADDRESS)
+            .expect("pin of an unknown port");
+        Self {
+            port,
+            offset: P::OFFSET,
+        }
+    }
+
+    /// Decode a pin from a single byte, e.g., read from the EEPROM, where the upper nibble holds the
+    /// index of the `Port` in `Port::ALL` and the lower nibble the offset. Returns `None` for an
+    /// invalid encoding.
+    pub fn from_byte(byte: u8) -> Option<Self> {
+        let port = *Port::ALL.get(usize::from(byte >> 4))?;
+        Self::new(port, byte & 0x0F)
+    }
+
+    /// Encode the pin into a single byte, see `DynPin::from_byte`.
+    pub fn to_byte(self) -> u8 {
+        (self.port as u8) << 4 | self.offset
+    }
+
+    /// The port of the pin.
+    pub fn port(self) -> Port {
+        self.port
+    }
+
+    /// The numeric offset of the pin in its port's registers.
+    pub fn offset(self) -> u8 {
+        self.offset
+    }
+
+    /// The mask of the pin used for accessing registers.
+    pub fn mask(self) -> u8 {
+        1 << self.offset
+    }
+
+    /// Sets the pin up as an input.
+    pub fn set_input(self) {
+        self.assert_unclaimed();
+        let (ddr, _, _) = self.port.registers();
+        self.modify(ddr, false);
+    }
+
+    /// Sets the pin up as an output.
+    pub fn set_output(self) {
+        self.assert_unclaimed();
+        let (ddr, _, _) = self.port.registers();
+        self.modify(ddr, true);
+    }
+
+    /// Set the pin to high.
+    ///
+    /// The pin must be configured as an output.
+    pub fn set_high(self) {
+        self.assert_unclaimed();
+        let (_, port, _) = self.port.registers();
+        self.modify(port, true);
+    }
+
+    /// Set the pin to low.
+    ///
+    /// The pin must be configured as an output.
+    pub fn set_low(self) {
+        self.assert_unclaimed();
+        let (_, port, _) = self.port.registers();
+        self.modify(port, false);
+    }
+
+    /// Toggles the pin.
+    ///
+    /// The pin must be configured as an output.
+    pub fn toggle(self) {
+        self.assert_unclaimed();
+        let (_, port, _) = self.port.registers();
+        unsafe { write_volatile(port, read_volatile(port) ^ self.mask()) }
+    }
+
+    /// Check if the pin is currently high.
+    ///
+    /// The pin must be configured as an input.
+    pub fn is_high(self) -> bool {
+        let (_, _, pin) = self.port.registers();
+        unsafe { read_volatile(pin) & self.mask() != 0 }
+    }
+
+    /// Checks if the pin is currently low.
+    ///
+    /// The pin must be configured as an input.
+    pub fn is_low(self) -> bool {
+        !self.is_high()
+    }
+
+    /// Set (`true`) or clear (`false`) the pin's bit in the register at `address`.
+    fn modify(self, address: *mut u8, set: bool) {
+        unsafe {
+            let value = read_volatile(address);
+            if set {
+                write_volatile(address, value | self.mask());
+            } else {
+                write_volatile(address, value & !self.mask());
+            }
+        }
+    }
+}
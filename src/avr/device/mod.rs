@@ -3,9 +3,15 @@
 
 // Do not export this module, as it should only be used within the device-specific `port` module.
 pub mod pin;
-pub(crate) use pin::set_pins;
 pub use pin::{DataDirection, Pin};
 
+/// Module allowing to select pins at runtime.
+pub mod dyn_pin;
+pub use dyn_pin::DynPin;
+
+/// Module keeping track of the pins claimed by peripherals.
+mod claim;
+
 // Do not export this module, as it should only be used within the device-specific `registers` module.
 pub mod register;
 pub use register::{Register, RegisterBits, RegisterValue};
@@ -14,5 +20,5 @@ pub use register::{Register, RegisterBits, RegisterValue};
 pub mod atmega32;
 pub use atmega32 as current_device;
 
-/// Re-export the device's modules for bitmasks, port and register definitions.
-pub use current_device::{bitmasks, port, registers};
+/// Re-export the device's modules for bitmasks, port, register and timer definitions.
+pub use current_device::{bitmasks, port, registers, timers};
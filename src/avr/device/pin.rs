@@ -1,4 +1,4 @@
-use super::Register;
+use super::{DynPin, Register};
 
 /// Represents whether a pin is an input or an output.
 pub enum DataDirection {
@@ -9,7 +9,11 @@ pub enum DataDirection {
 }
 
 /// An IO pin.
-pub trait Pin {
+///
+/// Pins used by a peripheral, e.g., `RX` and `TX` after `Serial::init`, are claimed by it. In
+/// debug builds, setting the direction or value of a claimed pin fails an `rp6_assert!`, see
+/// `Pin::claim`.
+pub trait Pin: Sized {
     /// The associated data direction register.
     type DDR: Register<T = u8>;
     /// The associated port register.
@@ -34,12 +38,14 @@ pub trait Pin {
     /// Sets the pin up as an input.
     #[inline(always)]
     fn set_input() {
+        Self::assert_unclaimed();
         Self::DDR::unset_mask_raw(Self::MASK);
     }
 
     /// Sets the pin up as an output.
     #[inline(always)]
     fn set_output() {
+        Self::assert_unclaimed();
         Self::DDR::set_mask_raw(Self::MASK);
     }
 
@@ -48,6 +54,7 @@ pub trait Pin {
     /// The pin must be configured as an output.
     #[inline(always)]
     fn set_high() {
+        Self::assert_unclaimed();
         Self::PORT::set_mask_raw(Self::MASK);
     }
 
@@ -56,6 +63,7 @@ pub trait Pin {
     /// The pin must be configured as an output.
     #[inline(always)]
     fn set_low() {
+        Self::assert_unclaimed();
         Self::PORT::unset_mask_raw(Self::MASK);
     }
 
@@ -64,6 +72,7 @@ pub trait Pin {
     /// The pin must be configured as an output.
     #[inline(always)]
     fn toggle() {
+        Self::assert_unclaimed();
         // FIXME: We can optimise this on post-2006 AVRs.
         // http://www.avrfreaks.net/forum/toggle-state-output-pin
         // set(Self::PIN, Self::MASK);
@@ -85,6 +94,28 @@ pub trait Pin {
     fn is_low() -> bool {
         Self::PIN::is_clear_raw(Self::MASK)
     }
+
+    /// Mark the pin as claimed by a peripheral, see `DynPin::claim`.
+    fn claim() {
+        DynPin::of::<Self>().claim();
+    }
+
+    /// Release a claimed pin, see `DynPin::release`.
+    fn release() {
+        DynPin::of::<Self>().release();
+    }
+
+    /// Check if the pin is currently claimed by a peripheral.
+    fn is_claimed() -> bool {
+        DynPin::of::<Self>().is_claimed()
+    }
+
+    /// Check that the pin is not claimed by a peripheral. Compiles to nothing in release builds.
+    #[inline(always)]
+    fn assert_unclaimed() {
+        #[cfg(debug_assertions)]
+        DynPin::of::<Self>().assert_unclaimed();
+    }
 }
 
 /// Convenience macro to define a pin struct directly from the `DDR`, `PORT` and `PIN` `Register`s.
@@ -134,54 +165,3 @@ macro_rules! port {
 }
 // export macro to the crate
 pub(crate) use port;
-
-/// Convenience setter and getter macros to set multiple pins in the same register at once.
-///
-/// Example: To set `b0`, `b1` and `b7` to `0b110`, use `set_pins!(b0, b1, b7, 0b110);`.
-macro_rules! set_pins {
-    ([$base_pin: ident, $($pin: ident),*], $value: expr $(,)?) => {
-        // check that users have really used this macro only for pins in the same PORT group
-        let mut _typecheck = <$base_pin as Pin>::DDR::default();
-        $(_typecheck = <$pin as Pin>::DDR::default();)*
-        let mut _typecheck = <$base_pin as Pin>::PORT::default();
-        $(_typecheck = <$pin as Pin>::PORT::default();)*
-        let mut _typecheck = <$base_pin as Pin>::PIN::default();
-        $(_typecheck = <$pin as Pin>::PIN::default();)*
-
-        // set pins as outputs
-        let pin_mask = $base_pin::MASK $(| $pin::MASK)*;
-        <$base_pin as Pin>::DDR::set_mask_raw(pin_mask);
-
-        // set pins' values
-        <$base_pin as Pin>::PORT::write(
-            (<$base_pin as Pin>::PORT::read() & !pin_mask)
-            | set_pins!(@reverse_for_output_mask [$($pin, )*], [$base_pin], $value)
-        );
-    };
-
-    // base case: pass reversed array of pins to @output_mask
-    (@reverse_for_output_mask [], [$($pin_rev: ident),* $(,)?], $value: expr) => {
-        set_pins!(@output_mask 0, [$($pin_rev, )*], $value)
-    };
-    // otherwise: add front element to front of the reversed array of pins
-    (@reverse_for_output_mask [$first_pin: ident, $($pin: ident),* $(,)?], [$($pin_rev: ident),* $(,)?], $value: expr) => {
-        set_pins!(@reverse_for_output_mask [$($pin, )*], [$first_pin, $($pin_rev, )*], $value)
-    };
-
-    // add correct mask for the next pin
-    (@output_mask $position: expr, [$last_pin: ident, $($pin_rev: ident),* $(,)?], $value: expr) => {
-        (
-        // 1. `>>`-shift $value by $position bits to the right and extract the least significant bit
-            (($value >> $position) & 1)
-        // 2. `<<`-shift this extracted bit to the correct mask offset of the respective pin
-            << <$last_pin as Pin>::OFFSET)
-        // 3. compute the logical `|` with the remaining pins' bitmask
-            | set_pins!(@output_mask $position, [$($pin_rev, )*], $value >> 1)
-    };
-    // end of recursion: all pins included in the mask
-    (@output_mask $position: expr, [], $value: expr) => {
-        0
-    };
-}
-// export macro to the crate
-pub(crate) use set_pins;
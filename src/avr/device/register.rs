@@ -1,3 +1,4 @@
+use super::super::interrupt;
 use core::{cmp, convert, marker, ops};
 
 /// A value that a register can store.
@@ -38,15 +39,23 @@ pub trait Register: Default + Sized {
     where
         V: Into<Self::T>,
     {
+        #[cfg(target_arch = "avr")]
         unsafe {
             core::ptr::write_volatile(Self::ADDRESS, value.into());
         }
+        #[cfg(not(target_arch = "avr"))]
+        super::super::mock::write(Self::ADDRESS, value.into());
     }
 
     /// Reads the value of the register.
     #[inline(always)]
     fn read() -> Self::T {
-        unsafe { core::ptr::read_volatile(Self::ADDRESS) }
+        #[cfg(target_arch = "avr")]
+        unsafe {
+            core::ptr::read_volatile(Self::ADDRESS)
+        }
+        #[cfg(not(target_arch = "avr"))]
+        super::super::mock::read(Self::ADDRESS)
     }
 
     /// Sets a set of bits to `1` in the register.
@@ -59,12 +68,7 @@ pub trait Register: Default + Sized {
     /// This is equivalent to `r |= mask`.
     #[inline(always)]
     fn set_mask_raw(mask: Self::T) {
-        unsafe {
-            core::ptr::write_volatile(
-                Self::ADDRESS,
-                core::ptr::read_volatile(Self::ADDRESS) | mask,
-            );
-        }
+        Self::write(Self::read() | mask);
     }
 
     /// Unsets a set of bits in the register.
@@ -79,12 +83,7 @@ pub trait Register: Default + Sized {
     /// This is equivalent to `r &= !mask`.
     #[inline(always)]
     fn unset_mask_raw(mask: Self::T) {
-        unsafe {
-            core::ptr::write_volatile(
-                Self::ADDRESS,
-                core::ptr::read_volatile(Self::ADDRESS) & !mask,
-            )
-        }
+        Self::write(Self::read() & !mask);
     }
 
     /// Toggles a set of bits within the register.
@@ -101,12 +100,7 @@ pub trait Register: Default + Sized {
     /// This is equivalent to `r ^= mask`.
     #[inline(always)]
     fn toggle_raw(mask: Self::T) {
-        unsafe {
-            core::ptr::write_volatile(
-                Self::ADDRESS,
-                core::ptr::read_volatile(Self::ADDRESS) ^ mask,
-            )
-        }
+        Self::write(Self::read() ^ mask);
     }
 
     /// Checks if a set of bits are enabled.
@@ -122,7 +116,7 @@ pub trait Register: Default + Sized {
     /// This is equivalent to `(r & mask) == mask`.
     #[inline(always)]
     fn is_mask_set_raw(mask: Self::T) -> bool {
-        unsafe { (core::ptr::read_volatile(Self::ADDRESS) & mask) == mask }
+        (Self::read() & mask) == mask
     }
 
     /// Checks if a set of bits are not set.
@@ -138,7 +132,33 @@ pub trait Register: Default + Sized {
     /// This is equivalent to `(r & mask) == 0`.
     #[inline(always)]
     fn is_clear_raw(mask: Self::T) -> bool {
-        unsafe { (core::ptr::read_volatile(Self::ADDRESS) & mask) == Self::T::from(0) }
+        (Self::read() & mask) == Self::T::from(0)
+    }
+
+    /// Atomically replaces the value of the register with `value` and returns the old value. The
+    /// read and write happen without being interrupted, so that no change of an interrupt handler
+    /// gets lost in between.
+    ///
+    /// Note that flag registers such as `TIFR` or `GIFR` are cleared by writing a logical one, see
+    /// `claim_flags_raw` for reading and clearing pending flags instead.
+    fn swap(value: Self::T) -> Self::T {
+        interrupt::without_interrupts(|_| {
+            let old = Self::read();
+            Self::write(value);
+            old
+        })
+    }
+
+    /// Atomically reads the flags in `mask` that are currently set in a flag register such as
+    /// `TIFR` or `GIFR`, clears exactly those flags and returns them. As these registers are
+    /// cleared by writing a logical one to a flag, the set flags are written back to the register,
+    /// while flags outside of `mask` or set in the meantime are not affected.
+    fn claim_flags_raw(mask: Self::T) -> Self::T {
+        interrupt::without_interrupts(|_| {
+            let flags = Self::read() & mask;
+            Self::write(flags);
+            flags
+        })
     }
 
     /// Waits until a set of bits are set in the register.
@@ -334,3 +354,41 @@ macro_rules! bitmask_list {
     };
 }
 pub(crate) use bitmask_list;
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        super::{
+            super::mock,
+            bitmasks::{INTF0, INTF1, INTF2, OCF0, TOV0},
+            registers::{GIFR, TIFR},
+        },
+        Register,
+    };
+
+    #[test]
+    fn swap_returns_the_old_value() {
+        mock::reset();
+        mock::preset::<TIFR>(0x5A);
+        assert_eq!(TIFR::swap(0xA5), 0x5A);
+        assert_eq!(TIFR::read(), 0xA5);
+        assert_eq!(mock::take_writes::<TIFR>(), [0xA5]);
+    }
+
+    #[test]
+    fn claim_flags_raw_clears_exactly_the_set_flags_in_mask() {
+        mock::reset();
+        mock::preset::<GIFR>(INTF0 | INTF2);
+        assert_eq!(GIFR::claim_flags_raw(INTF0 | INTF1), INTF0);
+        // flags are cleared by writing a logical one, so only `INTF0` must be written
+        assert_eq!(mock::take_writes::<GIFR>(), [INTF0]);
+    }
+
+    #[test]
+    fn claim_flags_raw_without_set_flags() {
+        mock::reset();
+        mock::preset::<TIFR>(TOV0);
+        assert_eq!(TIFR::claim_flags_raw(OCF0), 0);
+        assert_eq!(mock::take_writes::<TIFR>(), [0]);
+    }
+}
@@ -0,0 +1,203 @@
+use super::Eeprom;
+
+/// A `u32` counter stored in the EEPROM that spreads its writes over a span of records to avoid
+/// wearing out a single EEPROM cell, e.g., to track the boot count or odometer totals of the robot.
+///
+/// The counter occupies `slots` consecutive records of `EepromCounter::RECORD_SIZE` bytes each,
+/// starting at the EEPROM address `base`. Every `increment` writes the new value to the record
+/// following the one holding the current value, so each cell is only written once every `slots`
+/// increments. Upon creation, the current value is reconstructed by scanning all records for the
+/// highest valid one.
+///
+/// Each record consists of the value in little-endian byte order followed by its bitwise inverse,
+/// which is only valid if both copies match. If the power fails while a record is written, the
+/// torn record is ignored by the scan, as the partially written copy does not match the other one.
+/// In that case, the counter resumes from the previous value, i.e., at most the single interrupted
+/// increment is lost. Unlike a check byte, which a torn record matches by chance in 1 of 256
+/// cases, the copies are written one after another, so that a torn record can only be valid if it
+/// holds either the complete new value or the unchanged old one, and never a bogus value. An
+/// erased record (all bytes `0xFF`) is never valid.
+pub struct EepromCounter {
+    /// EEPROM address of the first record.
+    base: u16,
+    /// Number of records used by the counter.
+    slots: u16,
+    /// Index of the record holding the current value.
+    slot: u16,
+    /// The current value of the counter.
+    value: u32,
+}
+
+impl EepromCounter {
+    /// Number of EEPROM bytes occupied by a single record of the counter.
+    pub const RECORD_SIZE: u16 = 8;
+
+    /// Create a counter using `slots` records starting at the EEPROM address `base`, i.e., the
+    /// EEPROM range `base..base + slots * RECORD_SIZE`. Restores the value stored previously or
+    /// starts at `0` if no valid record is found.
+    ///
+    /// The span of records must fit into the EEPROM and must not be shared with any other data.
+    pub fn new(base: u16, slots: u16) -> Self {
+        debug_assert!(slots > 0 && base + slots * Self::RECORD_SIZE <= Eeprom::SIZE);
+
+        // without any valid record, start so that the first increment writes to record `0`
+        let mut counter = EepromCounter {
+            base,
+            slots,
+            slot: slots - 1,
+            value: 0,
+        };
+
+        let mut found = false;
+        for slot in 0..slots {
+            if let Some(value) = counter.read_record(slot) {
+                if !found || value > counter.value {
+                    counter.slot = slot;
+                    counter.value = value;
+                    found = true;
+                }
+            }
+        }
+
+        counter
+    }
+
+    /// Returns the current value of the counter.
+    pub fn get(&self) -> u32 {
+        self.value
+    }
+
+    /// Increments the counter by one and persists the new value in the EEPROM. Blocks until the
+    /// record has been written. The counter saturates at `u32::MAX`, i.e., further increments do
+    /// not change it and do not write to the EEPROM.
+    pub fn increment(&mut self) {
+        if self.value == u32::MAX {
+            return;
+        }
+        let slot = if self.slot + 1 < self.slots {
+            self.slot + 1
+        } else {
+            0
+        };
+        let value = self.value + 1;
+
+        self.write_record(slot, value);
+        self.slot = slot;
+        self.value = value;
+    }
+
+    /// Returns the EEPROM address of the record with index `slot`.
+    fn address(&self, slot: u16) -> u16 {
+        self.base + slot * Self::RECORD_SIZE
+    }
+
+    /// Reads the value stored in the record with index `slot`, or `None` if the record is invalid.
+    fn read_record(&self, slot: u16) -> Option<u32> {
+        let mut bytes = [0; 8];
+        Eeprom::read(self.address(slot), &mut bytes);
+        let value = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        let inverse = u32::from_le_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]);
+
+        (value == !inverse).then_some(value)
+    }
+
+    /// Writes `value` to the record with index `slot`.
+    fn write_record(&self, slot: u16, value: u32) {
+        let address = self.address(slot);
+
+        // While the value is written, the old inverse only matches the bytes that equal the old
+        // value, and while the inverse is written, only the complete new value matches it.
+        Eeprom::write(address, &value.to_le_bytes());
+        Eeprom::write(address + 4, &(!value).to_le_bytes());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EepromCounter;
+    use crate::avr::{eeprom::Eeprom, mock};
+
+    const BASE: u16 = 0x100;
+    const SLOTS: u16 = 4;
+
+    /// Store a valid record of `value` in the record with index `slot`.
+    fn preset_record(slot: u16, value: u32) {
+        let address = BASE + slot * EepromCounter::RECORD_SIZE;
+        mock::preset_eeprom(address, &value.to_le_bytes());
+        mock::preset_eeprom(address + 4, &(!value).to_le_bytes());
+    }
+
+    #[test]
+    fn starts_at_zero_on_an_erased_eeprom() {
+        let _guard = mock::lock_global_state();
+        mock::reset();
+        let mut counter = EepromCounter::new(BASE, SLOTS);
+        assert_eq!(counter.get(), 0);
+
+        // the first increment writes the first record
+        counter.increment();
+        assert_eq!(counter.get(), 1);
+        let mut record = [0; 8];
+        Eeprom::read(BASE, &mut record);
+        assert_eq!(record, [1, 0, 0, 0, 0xFE, 0xFF, 0xFF, 0xFF]);
+    }
+
+    #[test]
+    fn restores_the_value_after_wrapping_around() {
+        let _guard = mock::lock_global_state();
+        mock::reset();
+        let mut counter = EepromCounter::new(BASE, SLOTS);
+        for _ in 0..10 {
+            counter.increment();
+        }
+        // the records hold 9, 10, 7 and 8, i.e., the latest one is not the last slot
+        let restored = EepromCounter::new(BASE, SLOTS);
+        assert_eq!(restored.get(), 10);
+
+        // and increments continue with the oldest record
+        let mut restored = restored;
+        restored.increment();
+        assert_eq!(EepromCounter::new(BASE, SLOTS).get(), 11);
+        assert_eq!(restored.slot, 2);
+    }
+
+    #[test]
+    fn resumes_from_the_previous_value_after_a_torn_record() {
+        let _guard = mock::lock_global_state();
+        mock::reset();
+        preset_record(0, 0x0001_00FE);
+        preset_record(1, 0x0001_00FF);
+        preset_record(2, 0x0000_FFFE);
+        preset_record(3, 0x0000_FFFF);
+
+        // the power failed while writing 0x0001_0100 to the oldest record: only the low bytes of
+        // the value have been written, while the inverse is still the old one
+        mock::preset_eeprom(BASE + 2 * EepromCounter::RECORD_SIZE, &[0x00, 0x01]);
+        let mut counter = EepromCounter::new(BASE, SLOTS);
+        assert_eq!(counter.get(), 0x0001_00FF);
+
+        // the value has been written completely, but the inverse only partially
+        preset_record(2, 0x0000_FFFE);
+        mock::preset_eeprom(
+            BASE + 2 * EepromCounter::RECORD_SIZE,
+            &0x0001_0100u32.to_le_bytes(),
+        );
+        mock::preset_eeprom(BASE + 2 * EepromCounter::RECORD_SIZE + 4, &[!0x00]);
+        assert_eq!(EepromCounter::new(BASE, SLOTS).get(), 0x0001_00FF);
+
+        // the interrupted increment is repeated in the same record
+        counter.increment();
+        assert_eq!(EepromCounter::new(BASE, SLOTS).get(), 0x0001_0100);
+    }
+
+    #[test]
+    fn saturates_at_the_maximum() {
+        let _guard = mock::lock_global_state();
+        mock::reset();
+        preset_record(0, u32::MAX);
+        let mut counter = EepromCounter::new(BASE, SLOTS);
+        counter.increment();
+        assert_eq!(counter.get(), u32::MAX);
+        assert_eq!(EepromCounter::new(BASE, SLOTS).get(), u32::MAX);
+    }
+}
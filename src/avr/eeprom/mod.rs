@@ -0,0 +1,103 @@
+//! Access to the internal EEPROM of the atmega32.
+//!
+//! The EEPROM keeps its content while the robot is switched off, which makes it the place to store
+//! calibration values or counters. Note that each EEPROM cell only endures about 100.000 write
+//! cycles, so values that change often should be spread over multiple cells, e.g., by using an
+//! `EepromCounter`.
+
+use crate::{
+    avr::{
+        bitmasks::{EEMWE, EERE, EEWE},
+        registers::{EEARH, EEARL, EECR, EEDR},
+    },
+    interrupt, Register,
+};
+
+/// Module implementing a wear-leveled counter stored in the EEPROM.
+mod counter;
+pub use counter::EepromCounter;
+
+/// Module implementing writes that complete in the background via the `EE_RDY` interrupt.
+mod queue;
+pub use queue::EEPROM_QUEUE_SIZE;
+
+/// Struct managing all access to the internal EEPROM.
+pub struct Eeprom;
+
+impl Eeprom {
+    /// Size of the internal EEPROM in bytes.
+    pub const SIZE: u16 = 1024;
+
+    /// Check if the EEPROM is ready for the next access, i.e., no write is in progress. Note that
+    /// further writes may still be queued, see `Eeprom::is_busy`.
+    #[inline(always)]
+    pub fn is_ready() -> bool {
+        EECR::is_clear_raw(EEWE)
+    }
+
+    /// Blocks until a previous write to the EEPROM has been completed, including all writes queued
+    /// by `Eeprom::write_byte_async`. Also works with interrupts disabled, as the queued writes are
+    /// then started here instead of by the `EE_RDY` interrupt.
+    #[inline(always)]
+    pub fn wait_until_ready() {
+        while !interrupt::without_interrupts(Self::write_next_queued) {}
+    }
+
+    /// Reads a single byte from the EEPROM at the given `address`.
+    pub fn read_byte(address: u16) -> u8 {
+        Self::wait_until_ready();
+        Self::set_address(address);
+        EECR::set_mask_raw(EERE);
+        EEDR::read()
+    }
+
+    /// Writes a single byte to the EEPROM at the given `address`. Blocks until any previous write
+    /// has been completed, a single write takes about 8.5ms.
+    pub fn write_byte(address: u16, value: u8) {
+        Self::wait_until_ready();
+        interrupt::without_interrupts(|_| Self::start_write(address, value));
+    }
+
+    /// Writes a single byte to the EEPROM at the given `address` only if it differs from the
+    /// currently stored value. This avoids unnecessary wear on the EEPROM cell.
+    pub fn update_byte(address: u16, value: u8) {
+        if Self::read_byte(address) != value {
+            Self::write_byte(address, value);
+        }
+    }
+
+    /// Fills `buffer` with the bytes stored in the EEPROM starting at `address`.
+    pub fn read(address: u16, buffer: &mut [u8]) {
+        for (offset, byte) in buffer.iter_mut().enumerate() {
+            *byte = Self::read_byte(address + offset as u16);
+        }
+    }
+
+    /// Stores `data` in the EEPROM starting at `address`. Only bytes that differ from the stored
+    /// ones are actually written.
+    pub fn write(address: u16, data: &[u8]) {
+        for (offset, byte) in data.iter().enumerate() {
+            Self::update_byte(address + offset as u16, *byte);
+        }
+    }
+
+    /// Starts writing `value` to the given `address`, which requires that no write is in progress.
+    /// Must be called with interrupts disabled.
+    #[inline(always)]
+    fn start_write(address: u16, value: u8) {
+        Self::set_address(address);
+        EEDR::write(value);
+
+        // `EEWE` must be set within four clock cycles after setting `EEMWE`.
+        let control = EECR::read() | EEMWE;
+        EECR::write(control);
+        EECR::write(control | EEWE);
+    }
+
+    /// Sets the EEPROM address register. The high byte must be written first.
+    #[inline(always)]
+    fn set_address(address: u16) {
+        EEARH::write((address >> 8) as u8);
+        EEARL::write(address as u8);
+    }
+}
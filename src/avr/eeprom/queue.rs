@@ -0,0 +1,74 @@
+use super::Eeprom;
+use crate::{
+    avr::{
+        bitmasks::{EERIE, EEWE},
+        registers::EECR,
+    },
+    interrupt::{self, mutex::DynamicMutex, CriticalSection},
+    Register,
+};
+use avr_macros::interrupt;
+use heapless::Deque;
+
+/// Maximum number of writes that can be queued by `Eeprom::write_byte_async` at the same time.
+pub const EEPROM_QUEUE_SIZE: usize = 16;
+
+/// Writes queued by `Eeprom::write_byte_async` as `(address, value)` that have not been started
+/// yet.
+static QUEUE: DynamicMutex<Deque<(u16, u8), EEPROM_QUEUE_SIZE>> = DynamicMutex::new(Deque::new());
+
+/// Triggered while the EEPROM is ready, i.e., after each completed write: starts the next queued
+/// write or disables itself once the queue is empty.
+#[interrupt]
+fn EE_RDY() {
+    interrupt::without_interrupts(Eeprom::write_next_queued);
+}
+
+impl Eeprom {
+    /// Queues writing `value` to the given `address` and returns immediately, so that the robot
+    /// keeps running while a block of data, e.g., calibration values, is persisted. The queued
+    /// writes are executed one after the other in the background, started by the `EE_RDY`
+    /// interrupt each time the previous write has been completed. A write that is queued while
+    /// another one is in progress is simply started after it, in the order of the calls.
+    ///
+    /// Returns `false` if already `EEPROM_QUEUE_SIZE` writes are queued, in which case `value` is
+    /// not written. Requires global interrupts to be enabled, otherwise the queued writes are only
+    /// executed by the next blocking access, e.g., `Eeprom::read_byte` or `wait_until_ready`.
+    pub fn write_byte_async(address: u16, value: u8) -> bool {
+        interrupt::without_interrupts(|cs| {
+            let queued = QUEUE
+                .lock(cs)
+                .borrow_mut()
+                .push_back((address, value))
+                .is_ok();
+            // the interrupt triggers immediately if the EEPROM is ready
+            EECR::set_mask_raw(EERIE);
+            queued
+        })
+    }
+
+    /// Check if a write is in progress or queued, see `Eeprom::write_byte_async`.
+    pub fn is_busy() -> bool {
+        interrupt::without_interrupts(|cs| {
+            EECR::is_mask_set_raw(EEWE) || !QUEUE.lock(cs).borrow().is_empty()
+        })
+    }
+
+    /// Starts the next queued write if the EEPROM is ready. Returns `true` if the EEPROM is ready
+    /// and no further write is queued, in which case the `EE_RDY` interrupt is disabled.
+    pub(super) fn write_next_queued(cs: &mut CriticalSection) -> bool {
+        if !Self::is_ready() {
+            return false;
+        }
+        match QUEUE.lock(cs).borrow_mut().pop_front() {
+            Some((address, value)) => {
+                Self::start_write(address, value);
+                false
+            }
+            None => {
+                EECR::unset_mask_raw(EERIE);
+                true
+            }
+        }
+    }
+}
@@ -0,0 +1,147 @@
+//! Self-programming of the application section of the flash memory via the `SPM` instruction,
+//! e.g., for firmware that updates itself or stores large tables.
+//!
+//! # Requirements
+//!
+//! The atmega32 only executes `SPM` from its boot loader section (BLS) at the top of the flash,
+//! and ignores it anywhere else. Therefore, `program_page` is placed in the linker section
+//! `.bootloader`, which must be located at the start of the BLS:
+//! - The size of the BLS is selected by the fuses `BOOTSZ1:0`, e.g., `00` selects 2048 words,
+//!   i.e., the BLS starts at the byte address `0x7000`. Place the section accordingly, e.g., by
+//!   passing `-C link-arg=-Wl,--section-start=.bootloader=0x7000` to `rustc`. The code in the
+//!   section must not exceed the size of the BLS.
+//! - The boot lock bits `BLB02:01` must allow `SPM` to write to the application section, i.e.,
+//!   must both be unprogrammed (`1`), which is their default.
+//! - Note that the RP6 ships with its own boot loader in the BLS, which is used by the
+//!   RobotLoader to flash new programs via the serial connection. Replacing it requires an ISP
+//!   programmer, and the RobotLoader can no longer be used afterwards.
+//!
+//! # Read-While-Write
+//!
+//! The flash consists of the Read-While-Write (RWW) section from `0x0000` to `RWW_END`, which
+//! contains the application, and the No-Read-While-Write (NRWW) section above, which contains the
+//! BLS. Only pages of the RWW section can be programmed by this module. While a page is erased or
+//! written, the RWW section cannot be read, i.e., no code of the application and no interrupt
+//! vector (unless moved to the BLS via `IVSEL`) may be executed. Hence, `program_page` runs with
+//! interrupts disabled and executes the whole sequence of erasing, filling and writing the page
+//! and re-enabling the RWW section in a single block of inline assembly, so that no code outside
+//! of the BLS is called in between, e.g., a function that the compiler did not inline.
+
+use super::{
+    bitmasks::{PGERS, PGWRT, RWWSB, RWWSRE, SPMEN},
+    eeprom::Eeprom,
+    interrupt::CriticalSection,
+    registers::SPMCR,
+    Register,
+};
+use core::{arch::asm, mem::transmute};
+
+/// Size of a flash page in bytes, i.e., 64 words.
+pub const PAGE_SIZE: u16 = 128;
+
+/// Byte address of the end of the RWW section, i.e., of the start of the NRWW section, which is
+/// independent of the fuses `BOOTSZ1:0`.
+pub const RWW_END: u16 = 0x7000;
+
+/// Errors that prevent programming a page.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FlashError {
+    /// The address is not the start of a page, i.e., not a multiple of `PAGE_SIZE`.
+    Misaligned,
+    /// The page is not located in the RWW section, i.e., above `RWW_END`.
+    OutsideRww,
+}
+
+/// Erase the page starting at the byte `address` and program it with `data`, then re-enable the
+/// RWW section. Blocks with interrupts disabled for about 8ms, i.e., 4ms each for erasing and
+/// writing the page. A pending EEPROM write is completed first, as `SPM` must not be executed
+/// while the EEPROM is written.
+///
+/// # Safety
+/// See the module documentation: this function must be placed in the BLS and the page must not
+/// contain code or data that is still needed, e.g., the currently running application.
+#[inline(never)]
+#[link_section = ".bootloader"]
+pub unsafe fn program_page(
+    address: u16,
+    data: &[u8; PAGE_SIZE as usize],
+) -> Result<(), FlashError> {
+    if !address.is_multiple_of(PAGE_SIZE) {
+        return Err(FlashError::Misaligned);
+    }
+    if address >= RWW_END {
+        return Err(FlashError::OutsideRww);
+    }
+
+    // the RWW section is still readable until the first `SPM`, so the EEPROM may be waited for by
+    // code outside of the BLS, while interrupts are disabled to prevent queued EEPROM writes from
+    // being started by the `EE_RDY` interrupt
+    let critical_section = CriticalSection::new();
+    Eeprom::wait_until_ready();
+
+    // `SPM` must be executed within four cycles after writing `SPMCR`, so both instructions
+    // directly follow each other. While filling the page buffer, `SPM` takes the word from
+    // `r1:r0`, where `r1` is the zero register of the compiler and must be cleared afterwards.
+    asm!(
+        // erase the page
+        "ldi {command}, {erase}",
+        "out {spmcr}, {command}",
+        "spm",
+        "1:",
+        "in {command}, {spmcr}",
+        "sbrc {command}, {spmen_bit}",
+        "rjmp 1b",
+        // fill the temporary page buffer word by word from `X`, little endian
+        "ldi {words}, {page_words}",
+        "2:",
+        "ld r0, X+",
+        "ld r1, X+",
+        "ldi {command}, {fill}",
+        "out {spmcr}, {command}",
+        "spm",
+        "adiw r30, 2",
+        "dec {words}",
+        "brne 2b",
+        "clr r1",
+        // `Z` points to the next page now, move it back to the start of the page
+        "subi r30, {page_size}",
+        "sbci r31, 0",
+        // write the page
+        "ldi {command}, {write}",
+        "out {spmcr}, {command}",
+        "spm",
+        "3:",
+        "in {command}, {spmcr}",
+        "sbrc {command}, {spmen_bit}",
+        "rjmp 3b",
+        // re-enable the RWW section until it is no longer busy, which also clears the page buffer
+        "4:",
+        "ldi {command}, {rww_enable}",
+        "out {spmcr}, {command}",
+        "spm",
+        "5:",
+        "in {command}, {spmcr}",
+        "sbrc {command}, {spmen_bit}",
+        "rjmp 5b",
+        "sbrc {command}, {rwwsb_bit}",
+        "rjmp 4b",
+        // I/O address of `SPMCR`, i.e., its memory address minus 0x20
+        spmcr = const { transmute::<*mut u8, usize>(SPMCR::ADDRESS) - 0x20 },
+        erase = const PGERS | SPMEN,
+        fill = const SPMEN,
+        write = const PGWRT | SPMEN,
+        rww_enable = const RWWSRE | SPMEN,
+        spmen_bit = const SPMEN.trailing_zeros(),
+        rwwsb_bit = const RWWSB.trailing_zeros(),
+        page_words = const PAGE_SIZE / 2,
+        page_size = const PAGE_SIZE,
+        command = out(reg_upper) _,
+        words = out(reg_upper) _,
+        inout("X") data.as_ptr() => _,
+        inout("Z") address => _,
+        options(nostack),
+    );
+
+    drop(critical_section);
+    Ok(())
+}
@@ -0,0 +1,39 @@
+//! Module to defer work from interrupt handlers to the main loop ("bottom halves").
+//!
+//! Interrupt handlers should be as short as possible. If an interrupt handler detects that some
+//! expensive work needs to be done, it can instead `defer` a task that is later executed outside of
+//! interrupt context when the main loop calls `run_deferred`.
+
+use super::{mutex::DynamicMutex, without_interrupts};
+use heapless::Deque;
+
+/// Maximum number of tasks that can be pending at the same time.
+pub const DEFERRED_QUEUE_SIZE: usize = 8;
+
+/// Queue of tasks deferred via `defer` that have not yet been executed by `run_deferred`.
+static DEFERRED_QUEUE: DynamicMutex<Deque<fn(), DEFERRED_QUEUE_SIZE>> =
+    DynamicMutex::new(Deque::new());
+
+/// Enqueue `task` to be executed by the next call to `run_deferred`. Can be called from interrupt
+/// handlers as well as from the main loop.
+///
+/// If already `DEFERRED_QUEUE_SIZE` tasks are pending, `task` is dropped and `false` is returned.
+/// Already pending tasks are never overwritten.
+pub fn defer(task: fn()) -> bool {
+    without_interrupts(|cs| DEFERRED_QUEUE.lock(cs).borrow_mut().push_back(task).is_ok())
+}
+
+/// Execute all pending tasks in the order they were deferred, with interrupts enabled. Tasks
+/// deferred while `run_deferred` is executing are executed as well. Intended to be called
+/// regularly from the main loop.
+pub fn run_deferred() {
+    while let Some(task) = without_interrupts(|cs| DEFERRED_QUEUE.lock(cs).borrow_mut().pop_front())
+    {
+        task();
+    }
+}
+
+/// Number of tasks that are currently pending.
+pub fn pending_deferred() -> usize {
+    without_interrupts(|cs| DEFERRED_QUEUE.lock(cs).borrow().len())
+}
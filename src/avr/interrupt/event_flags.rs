@@ -0,0 +1,89 @@
+//! Module to signal events from interrupt handlers to the main loop via a register of flags.
+
+use super::{mutex::Mutex, without_interrupts, CriticalSection};
+
+/// Register of up to 8 event flags, through which interrupt handlers signal events to the main
+/// loop without a dedicated `Mutex` per event. Intended to be placed in a `static`, with a named
+/// constant per flag, i.e., per bit.
+///
+/// Flags are set by `set`, e.g., from an interrupt handler, and stay set until they are consumed
+/// by `take` or `take_mask` (clear-on-read): an event signalled several times before being taken
+/// is reported only once, while an event signalled right after a `take` is reported by the next
+/// one, i.e., no event is lost.
+///
+/// Example:
+/// ```rust
+/// const BYTE_RECEIVED: u8 = 1 << 0;
+/// const BUMPER_HIT: u8 = 1 << 1;
+/// static EVENTS: EventFlags = EventFlags::new();
+///
+/// #[interrupt]
+/// fn INT2() {
+///     EVENTS.set(BUMPER_HIT);
+/// }
+///
+/// loop {
+///     let events = EVENTS.take();
+///     if events & BUMPER_HIT != 0 {
+///         // react to the bumper
+///     }
+/// }
+/// ```
+pub struct EventFlags {
+    /// The flags that have been set and not yet taken.
+    flags: Mutex<u8>,
+}
+
+impl EventFlags {
+    /// Create a new register with all flags cleared.
+    pub const fn new() -> Self {
+        Self {
+            flags: Mutex::new(0),
+        }
+    }
+
+    /// Set the given `flags` (a bitmask), keeping the other flags. Costs only a read-modify-write
+    /// of a single byte, so that it can be called from short interrupt handlers.
+    #[inline(always)]
+    pub fn set(&self, flags: u8) {
+        without_interrupts(|cs| self.set_in(cs, flags));
+    }
+
+    /// Set the given `flags` within an existing `CriticalSection`, see `EventFlags::set`.
+    #[inline(always)]
+    pub fn set_in(&self, cs: &CriticalSection, flags: u8) {
+        let pending = self.flags.lock(cs);
+        pending.set(pending.get() | flags);
+    }
+
+    /// Return all flags that have been set and clear them atomically.
+    pub fn take(&self) -> u8 {
+        without_interrupts(|cs| self.flags.lock(cs).take())
+    }
+
+    /// Return the flags of `mask` that have been set and clear them atomically, while the other
+    /// flags are kept, e.g., for other consumers.
+    pub fn take_mask(&self, mask: u8) -> u8 {
+        without_interrupts(|cs| self.take_mask_in(cs, mask))
+    }
+
+    /// Return and clear the flags of `mask` within an existing `CriticalSection`, see
+    /// `EventFlags::take_mask`.
+    pub fn take_mask_in(&self, cs: &CriticalSection, mask: u8) -> u8 {
+        let pending = self.flags.lock(cs);
+        let flags = pending.get() & mask;
+        pending.set(pending.get() & !flags);
+        flags
+    }
+
+    /// Return the flags that have been set without clearing them.
+    pub fn peek(&self) -> u8 {
+        without_interrupts(|cs| self.flags.lock(cs).get())
+    }
+}
+
+impl Default for EventFlags {
+    fn default() -> Self {
+        Self::new()
+    }
+}
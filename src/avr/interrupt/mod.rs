@@ -3,75 +3,114 @@
 //! - <https://github.com/avr-rust/ruduino/blob/master/src/interrupt.rs>
 //! - <https://docs.rs/bare-metal/0.2.5/src/bare_metal/lib.rs.html>
 
-use core::{arch::asm, marker::PhantomData};
+#[cfg(target_arch = "avr")]
+use super::bitmasks::I;
+#[cfg(target_arch = "avr")]
+use core::arch::asm;
+
+pub mod deferred;
+pub use deferred::{defer, run_deferred};
+
+pub mod event_flags;
+pub use event_flags::EventFlags;
 
 pub mod mutex;
-use mutex::Mutex;
 
-/// Atomic counter of critical sections to avoid problems when `without_interrupts` is used in
-/// nested function calls.
-#[cfg(not(feature = "unsafe-no-critical-section-count"))]
-static CRITICAL_SECTION_COUNTER: Mutex<usize> = Mutex::new(0);
+pub mod once;
+pub use once::{AlreadyCalled, Once};
+
+pub mod pending;
+pub use pending::*;
 
-/// Helper struct that automatically restores interrupts on drop. The wrapped `PhantomData` creates
-/// a private field to ensure that this struct cannot be initialized from outside of this module
-/// without using its `unsafe` initializer function `new`. The recommended use to enter a
-/// `CriticalSection` is to pass a closure to `without_interrupts`.
+pub mod shared;
+pub use shared::{Resources, SharedResources};
+
+/// Helper struct that restores interrupts on drop. The wrapped flag stores whether interrupts were
+/// enabled when the `CriticalSection` was entered, and is a private field to ensure that this
+/// struct cannot be initialized from outside of this module without using its `unsafe`
+/// initializer function `new`. The recommended use to enter a `CriticalSection` is to pass a
+/// closure to `without_interrupts`.
 ///
-/// When the feature `unsafe-no-critical-section-count` is disabled, this implementation is also
-/// safe w.r.t. nested calls of `without_interrupts`. This is achieved by counting how many
-/// `CriticalSection`s were entered, and only enabling device interrupts once the last
-/// `CriticalSection` is exited. However, as these checks incur a small runtime overhead, they can
-/// be disabled with the feature `unsafe-no-critical-section-count`. Note that, for execution
-/// consistency, a user must then ensure that `without_interrupts` will never be nested!
-pub struct CriticalSection(PhantomData<()>);
+/// Dropping a `CriticalSection` restores the previous state of the flag `I` in `SREG` instead of
+/// unconditionally enabling interrupts. Thus, nested calls of `without_interrupts` only enable
+/// interrupts again once the outermost `CriticalSection` is exited, and a `CriticalSection`
+/// entered within an interrupt handler never enables interrupts, so that handlers cannot be
+/// preempted by other handlers. The feature `unsafe-no-critical-section-count`, which used to
+/// disable the counting of nested `CriticalSection`s, no longer has any effect.
+pub struct CriticalSection(bool);
 
 impl CriticalSection {
     /// Upon entering any `CriticalSection`, disable global device interrupts.
     ///
     /// # Safety
-    /// When the feature `unsafe-no-critical-section-count` is disabled, this implementation is also
-    /// safe w.r.t. nested `CriticalSection`s, e.g., by nesting calls to `without_interrupts`. This
-    /// is achieved by counting how many `CriticalSection`s were entered, and only enabling device
-    /// interrupts once the last `CriticalSection` is exited. However, as these checks incur a small
-    /// runtime overhead, they can be disabled with the feature `unsafe-no-critical-section-count`.
-    /// Note that, for execution consistency, a user must then ensure that `CriticalSection`s will
-    /// never be nested!
+    /// The `CriticalSection` must be dropped in the reverse order of entering, which
+    /// `without_interrupts` ensures. Otherwise, interrupts may be enabled while an inner
+    /// `CriticalSection` is still alive.
     #[inline(always)]
     pub unsafe fn new() -> Self {
-        // first, deactivate interrupts
-        asm!("CLI");
-
-        // next, create the new `CriticalSection`
-        let cs = CriticalSection(PhantomData);
-
-        // now, in guaranteed single-threaded mode, increase number of `CriticalSection`s
-        #[cfg(not(feature = "unsafe-no-critical-section-count"))]
-        CRITICAL_SECTION_COUNTER.lock(&cs).update(|x| x + 1);
-
-        cs
+        // an interrupt between reading `SREG` and `CLI` returns via `RETI`, which restores the flag
+        let enabled = interrupts_enabled();
+        cli();
+        CriticalSection(enabled)
     }
 }
 
 impl Drop for CriticalSection {
-    /// Upon dropping the last `CriticalSection`, enable global device interrupts.
+    /// Upon dropping a `CriticalSection`, enable global device interrupts if they were enabled
+    /// when it was entered.
     #[inline(always)]
     fn drop(&mut self) {
-        #[cfg(not(feature = "unsafe-no-critical-section-count"))]
-        CRITICAL_SECTION_COUNTER.lock(self).update(|x| {
-            if x == 1 {
-                unsafe { asm!("SEI") }
-            }
-            x - 1
-        });
-
-        #[cfg(feature = "unsafe-no-critical-section-count")]
-        unsafe {
-            asm!("SEI")
+        if self.0 {
+            sei();
         }
     }
 }
 
+/// Check whether global interrupts are enabled, i.e., whether the flag `I` is set in `SREG`.
+#[inline(always)]
+fn interrupts_enabled() -> bool {
+    #[cfg(target_arch = "avr")]
+    {
+        let sreg: u8;
+        // I/O address of `SREG`, which is deliberately not defined as a `Register`
+        unsafe { asm!("in {}, 0x3F", out(reg) sreg, options(nomem, nostack, preserves_flags)) };
+        sreg & I != 0
+    }
+    #[cfg(not(target_arch = "avr"))]
+    super::mock::interrupts_enabled()
+}
+
+/// Disable global interrupts, i.e., clear the flag `I` in `SREG`.
+#[inline(always)]
+fn cli() {
+    #[cfg(target_arch = "avr")]
+    unsafe {
+        asm!("CLI")
+    }
+    #[cfg(not(target_arch = "avr"))]
+    super::mock::set_interrupts_enabled(false);
+}
+
+/// Enable global interrupts, i.e., set the flag `I` in `SREG`.
+#[inline(always)]
+fn sei() {
+    #[cfg(target_arch = "avr")]
+    unsafe {
+        asm!("SEI")
+    }
+    #[cfg(not(target_arch = "avr"))]
+    super::mock::set_interrupts_enabled(true);
+}
+
+/// Enable global interrupts, i.e., set the flag `I` in `SREG`, which is cleared after a reset.
+/// Called by `RobotBase::init`, as the handlers of this crate, e.g., of the system tick or the
+/// receive ringbuffer of `Serial`, do not run otherwise. Note that a `CriticalSection` that is
+/// alive meanwhile disables interrupts again when it is dropped.
+#[inline(always)]
+pub fn enable() {
+    sei();
+}
+
 /// Executes a closure, disabling interrupts until its completion. Introduces a `CriticalSection`
 /// that allows to access shared data structures via the guards provided in the `mutex` module.
 ///
@@ -94,3 +133,35 @@ where
     // return whatever the closure yielded
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{super::mock, without_interrupts};
+
+    #[test]
+    fn restores_enabled_interrupts() {
+        mock::reset();
+        mock::set_interrupts_enabled(true);
+        without_interrupts(|_| assert!(!mock::interrupts_enabled()));
+        assert!(mock::interrupts_enabled());
+    }
+
+    #[test]
+    fn nested_sections_keep_interrupts_disabled() {
+        mock::reset();
+        mock::set_interrupts_enabled(true);
+        without_interrupts(|_| {
+            without_interrupts(|_| {});
+            assert!(!mock::interrupts_enabled());
+        });
+        assert!(mock::interrupts_enabled());
+    }
+
+    #[test]
+    fn keeps_disabled_interrupts_disabled() {
+        // e.g., within an interrupt handler, where the hardware clears the flag `I`
+        mock::reset();
+        without_interrupts(|_| {});
+        assert!(!mock::interrupts_enabled());
+    }
+}
@@ -0,0 +1,61 @@
+//! Module to guard initializations that must run exactly once, e.g., of a peripheral.
+
+use super::{mutex::Mutex, without_interrupts};
+
+/// Error returned by `Once::call_once` if the guarded closure has been called before.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AlreadyCalled;
+
+/// Guard that lets only the first call of `call_once` run its closure, which makes a repeated
+/// initialization detectable. Intended to be placed in a `static`.
+///
+/// Example:
+/// ```rust
+/// static SENSOR_INIT: Once = Once::new();
+///
+/// fn init_sensor() {
+///     if SENSOR_INIT.call_once(configure_sensor).is_err() {
+///         log_warn!("sensor initialized twice");
+///     }
+/// }
+/// ```
+pub struct Once {
+    /// Whether `call_once` has been called before.
+    called: Mutex<bool>,
+}
+
+impl Once {
+    /// Create a new `Once` whose closure has not been called yet.
+    pub const fn new() -> Self {
+        Self {
+            called: Mutex::new(false),
+        }
+    }
+
+    /// Run `f` and return its result if this is the first call of `call_once` on this guard.
+    /// Otherwise, `f` is not run and `AlreadyCalled` is returned.
+    ///
+    /// The guard is marked as called in a `CriticalSection` *before* `f` runs, while `f` itself
+    /// runs with interrupts enabled (unless called from within `without_interrupts`). Hence, a
+    /// call from an interrupt handler that occurs while `f` is still running returns
+    /// `AlreadyCalled` without waiting for `f` to complete, i.e., `AlreadyCalled` does not imply
+    /// that the initialization has finished. If `f` panics, the guard stays marked as called.
+    pub fn call_once<T>(&self, f: impl FnOnce() -> T) -> Result<T, AlreadyCalled> {
+        if without_interrupts(|cs| self.called.lock(cs).replace(true)) {
+            Err(AlreadyCalled)
+        } else {
+            Ok(f())
+        }
+    }
+
+    /// Whether `call_once` has been called on this guard before.
+    pub fn is_called(&self) -> bool {
+        without_interrupts(|cs| self.called.lock(cs).get())
+    }
+}
+
+impl Default for Once {
+    fn default() -> Self {
+        Self::new()
+    }
+}
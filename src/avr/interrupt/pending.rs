@@ -0,0 +1,55 @@
+//! Accessors of the interrupt flags, which allow to poll for events without enabling (or even
+//! defining) the corresponding interrupts.
+//!
+//! A flag is set by the hardware when its event occurs, regardless of whether the interrupt is
+//! enabled. If the interrupt is enabled, the flag is cleared automatically when its handler is
+//! executed, so polling the flags is only meaningful while the interrupt is disabled.
+//!
+//! **ATTENTION:** the flags are cleared by writing a logical ONE to them, NOT a zero! Writing a
+//! zero has no effect. Consequently, read-modify-write operations such as `TIFR::set_mask_raw(..)`
+//! clear ALL flags that are currently set, not only the given ones. The `clear_*_pending`
+//! functions therefore write only the flag of the respective interrupt.
+
+use super::super::{
+    bitmasks::{ICF1, INTF0, INTF1, INTF2, OCF0, OCF1A, OCF1B, OCF2, TOV0, TOV1, TOV2},
+    registers::{GIFR, TIFR},
+    Register,
+};
+
+/// Define the functions `is_<name>_pending` and `clear_<name>_pending` for the flag `$flag` of
+/// the interrupt `$vector` in register `$register`.
+macro_rules! pending_flag {
+    ($name: ident, $vector: ident, $register: ident, $flag: ident) => {
+        paste::paste! {
+            #[doc = concat!(
+                "Check if the `", stringify!($vector), "` interrupt is pending, i.e., its flag `",
+                stringify!($flag), "` is set in `", stringify!($register), "`."
+            )]
+            #[inline(always)]
+            pub fn [<is_ $name _pending>]() -> bool {
+                $register::is_mask_set_raw($flag)
+            }
+
+            #[doc = concat!(
+                "Clear the pending `", stringify!($vector), "` interrupt by writing a logical one ",
+                "to its flag `", stringify!($flag), "` in `", stringify!($register), "`."
+            )]
+            #[inline(always)]
+            pub fn [<clear_ $name _pending>]() {
+                $register::write($flag);
+            }
+        }
+    };
+}
+
+pending_flag!(int0, INT0, GIFR, INTF0);
+pending_flag!(int1, INT1, GIFR, INTF1);
+pending_flag!(int2, INT2, GIFR, INTF2);
+pending_flag!(timer0_comp, TIMER0_COMP, TIFR, OCF0);
+pending_flag!(timer0_ovf, TIMER0_OVF, TIFR, TOV0);
+pending_flag!(timer1_capt, TIMER1_CAPT, TIFR, ICF1);
+pending_flag!(timer1_compa, TIMER1_COMPA, TIFR, OCF1A);
+pending_flag!(timer1_compb, TIMER1_COMPB, TIFR, OCF1B);
+pending_flag!(timer1_ovf, TIMER1_OVF, TIFR, TOV1);
+pending_flag!(timer2_comp, TIMER2_COMP, TIFR, OCF2);
+pending_flag!(timer2_ovf, TIMER2_OVF, TIFR, TOV2);
@@ -0,0 +1,76 @@
+//! Resources shared between the entry function and interrupt handlers, RTIC-style.
+//!
+//! Instead of many individual `static` variables wrapped in `Mutex`es, a single struct annotated
+//! with `#[resources]` groups all shared state, where each field is initialized by an
+//! `#[init(...)]` attribute holding a constant expression:
+//! ```rust
+//! #[resources]
+//! struct Shared {
+//!     #[init(0)]
+//!     ticks: u16,
+//!     #[init(false)]
+//!     blink: bool,
+//! }
+//!
+//! #[entry(resources)]
+//! fn main(mut shared: interrupt::Resources<Shared>) -> ! {
+//!     loop {
+//!         let ticks = shared.lock(|shared, _cs| shared.ticks);
+//!         ...
+//!     }
+//! }
+//!
+//! #[interrupt(resources)]
+//! fn INT2(shared: &mut Shared) {
+//!     shared.ticks += 1;
+//! }
+//! ```
+//!
+//! Shared means that there is exactly one instance of the struct, which is accessed by the entry
+//! function and the interrupt handlers. On the single-core AVR, the only concurrency is an
+//! interrupt handler preempting the entry function, as handlers do not preempt each other (the
+//! hardware disables interrupts while a handler executes). Therefore, handlers receive the
+//! resources directly as `&mut`, while the entry function has to `lock` them, i.e., to access them
+//! within a `CriticalSection`. Handlers must not re-enable interrupts, as a nested handler would
+//! then alias the resources.
+
+use super::{without_interrupts, CriticalSection};
+use core::marker::PhantomData;
+
+/// Struct holding the resources shared between the entry function and interrupt handlers.
+/// Implemented by the `#[resources]` attribute, which also defines the single instance.
+///
+/// # Safety
+/// `get` must always return a reference to the same instance, and must only be called by the code
+/// generated by `#[entry(resources)]` and `#[interrupt(resources)]`.
+pub unsafe trait SharedResources: 'static {
+    /// Reference to the single instance of the resources.
+    #[doc(hidden)]
+    unsafe fn get() -> &'static mut Self;
+}
+
+/// Handle of the entry function granting access to the shared resources `R` within critical
+/// sections, see `Resources::lock`. It is passed to the entry function by `#[entry(resources)]`
+/// and cannot be cloned, i.e., there is exactly one handle.
+pub struct Resources<R: SharedResources> {
+    _resources: PhantomData<R>,
+}
+
+impl<R: SharedResources> Resources<R> {
+    /// Create the handle of the shared resources.
+    ///
+    /// # Safety
+    /// Only called once by the code generated by `#[entry(resources)]`.
+    #[doc(hidden)]
+    pub unsafe fn new() -> Self {
+        Self {
+            _resources: PhantomData,
+        }
+    }
+
+    /// Access the shared resources within a `CriticalSection`, i.e., without being preempted by
+    /// an interrupt handler. As this borrows the handle mutably, locks cannot be nested.
+    pub fn lock<T>(&mut self, f: impl FnOnce(&mut R, &CriticalSection) -> T) -> T {
+        without_interrupts(|cs| f(unsafe { R::get() }, cs))
+    }
+}
@@ -0,0 +1,205 @@
+//! Stand-ins for the hardware when compiling for a target other than AVR, e.g., to run the unit
+//! tests on the host via `make test`.
+//!
+//! The memory-mapped registers are backed by a plain array per thread, so that tests running in
+//! parallel do not interfere, and all writes are logged, e.g., to check the bytes written to `UDR`
+//! or the sequence of writes to a register sharing its address with another one, where `UBRRH`
+//! and `UCSRC` behave like on the atmega32, see `read` and `write`. Likewise, the internal EEPROM
+//! is backed by an erased array per thread, which is read and written via `EECR` like on the
+//! atmega32, where writes complete immediately. Global interrupts are modeled by a flag per
+//! thread, which is cleared initially, as no interrupt handler is ever executed on the host.
+//! Delays and sleeping return immediately.
+
+extern crate std;
+
+#[cfg(test)]
+use super::{
+    bitmasks::UDRE,
+    registers::{UCSRA, UDR},
+};
+use super::{
+    bitmasks::{EEMWE, EERE, EEWE, URSEL},
+    registers::{EEARH, EEARL, EECR, EEDR},
+    Register, RegisterValue,
+};
+use core::{
+    cell::{Cell, RefCell},
+    mem::size_of,
+};
+#[cfg(test)]
+use std::sync::{Mutex, MutexGuard};
+use std::vec::Vec;
+
+/// Size of the data memory backing the registers, i.e., the register file and the I/O space.
+const MEMORY_SIZE: usize = 0x60;
+
+/// Size of the internal EEPROM.
+const EEPROM_SIZE: usize = 1024;
+
+std::thread_local! {
+    /// Contents of the registers.
+    static MEMORY: RefCell<[u8; MEMORY_SIZE]> = const { RefCell::new([0; MEMORY_SIZE]) };
+    /// Log of all bytes written to registers as `(address, value)`.
+    static WRITES: RefCell<Vec<(usize, u8)>> = const { RefCell::new(Vec::new()) };
+    /// Whether global interrupts are enabled, i.e., the flag `I` in `SREG`.
+    static INTERRUPTS_ENABLED: RefCell<bool> = const { RefCell::new(false) };
+    /// Contents of `UCSRC`, which shares its address with `UBRRH`, see `write`.
+    static UCSRC: Cell<u8> = const { Cell::new(0) };
+    /// Whether the previous access was a read of the address of `UCSRC`, see `read`.
+    static UCSRC_SELECTED: Cell<bool> = const { Cell::new(false) };
+    /// Contents of the internal EEPROM, see `access_eeprom`.
+    static EEPROM: RefCell<[u8; EEPROM_SIZE]> = const { RefCell::new([0xFF; EEPROM_SIZE]) };
+}
+
+/// Address shared by `UBRRH` and `UCSRC` on the atmega32.
+const UCSRC_ADDRESS: usize = 0x40;
+
+/// Serializes tests that use global state besides the registers, e.g., the ringbuffers of
+/// `Serial`, see `capture_serial`.
+#[cfg(test)]
+static GLOBAL_STATE: Mutex<()> = Mutex::new(());
+
+/// Read the register at `address`, which is little endian for 16-bit registers like on the AVR.
+///
+/// Like on the atmega32, a read of the address shared by `UBRRH` and `UCSRC` returns `UBRRH`,
+/// unless it directly follows another read of that address, which returns `UCSRC`.
+pub(crate) fn read<T: RegisterValue>(address: *mut T) -> T {
+    let address = address as usize;
+    let mut bytes = [0; 2];
+    MEMORY.with_borrow(|memory| {
+        bytes[..size_of::<T>()].copy_from_slice(&memory[address..address + size_of::<T>()])
+    });
+    let previous_read_shared = UCSRC_SELECTED.replace(address == UCSRC_ADDRESS);
+    if address == UCSRC_ADDRESS && previous_read_shared {
+        UCSRC_SELECTED.set(false);
+        bytes[0] = UCSRC.get();
+    }
+    // SAFETY: `RegisterValue` is only implemented for `u8` and `u16`, i.e., plain integers
+    unsafe { core::ptr::read_unaligned(bytes.as_ptr() as *const T) }
+}
+
+/// Write `value` to the register at `address` and log the written bytes.
+///
+/// Like on the atmega32, a write to the address shared by `UBRRH` and `UCSRC` goes to `UCSRC` if
+/// the bit `URSEL` is set, and to `UBRRH` otherwise.
+pub(crate) fn write<T: RegisterValue>(address: *mut T, value: T) {
+    let address = address as usize;
+    let mut bytes = [0; 2];
+    // SAFETY: see `read`
+    unsafe { core::ptr::write_unaligned(bytes.as_mut_ptr() as *mut T, value) };
+    let bytes = &bytes[..size_of::<T>()];
+    UCSRC_SELECTED.set(false);
+    if address == UCSRC_ADDRESS && bytes[0] & URSEL != 0 {
+        UCSRC.set(bytes[0]);
+    } else {
+        MEMORY.with_borrow_mut(|memory| {
+            memory[address..address + bytes.len()].copy_from_slice(bytes)
+        });
+    }
+    WRITES.with_borrow_mut(|writes| {
+        writes.extend(bytes.iter().enumerate().map(|(i, &b)| (address + i, b)))
+    });
+    if address == EECR::ADDRESS as usize {
+        access_eeprom();
+    }
+}
+
+/// Perform the EEPROM access started by the last write to `EECR`: setting `EERE` reads the byte at
+/// the address in `EEAR` into `EEDR`, and setting `EEWE` together with `EEMWE` writes `EEDR` to
+/// that address. Both bits clear themselves immediately, i.e., the EEPROM is always ready.
+fn access_eeprom() {
+    MEMORY.with_borrow_mut(|memory| {
+        let control = &mut memory[EECR::ADDRESS as usize];
+        let (read, write) = (
+            *control & EERE != 0,
+            *control & (EEWE | EEMWE) == EEWE | EEMWE,
+        );
+        *control &= !(EERE | EEWE | EEMWE);
+
+        let address = usize::from(memory[EEARH::ADDRESS as usize] & 0x03) << 8
+            | usize::from(memory[EEARL::ADDRESS as usize]);
+        EEPROM.with_borrow_mut(|eeprom| {
+            if read {
+                memory[EEDR::ADDRESS as usize] = eeprom[address];
+            }
+            if write {
+                eeprom[address] = memory[EEDR::ADDRESS as usize];
+            }
+        });
+    });
+}
+
+/// Set the register `R` to `value` without logging the write, e.g., to simulate a flag set by the
+/// hardware.
+#[cfg(test)]
+pub(crate) fn preset<R: Register>(value: R::T) {
+    write(R::ADDRESS, value);
+    WRITES.with_borrow_mut(|writes| {
+        let len = writes.len() - size_of::<R::T>();
+        writes.truncate(len);
+    });
+}
+
+/// Take the log of the bytes written to the register `R` since the last call.
+#[cfg(test)]
+pub(crate) fn take_writes<R: Register<T = u8>>() -> Vec<u8> {
+    let address = R::ADDRESS as usize;
+    WRITES.with_borrow_mut(|writes| {
+        let (taken, kept) = writes.drain(..).partition(|&(a, _)| a == address);
+        *writes = kept;
+        taken.into_iter().map(|(_, value)| value).collect()
+    })
+}
+
+/// Reset all registers to zero, clear the log, erase the EEPROM and disable interrupts.
+#[cfg(test)]
+pub(crate) fn reset() {
+    MEMORY.with_borrow_mut(|memory| memory.fill(0));
+    WRITES.with_borrow_mut(|writes| writes.clear());
+    UCSRC.set(0);
+    UCSRC_SELECTED.set(false);
+    EEPROM.with_borrow_mut(|eeprom| eeprom.fill(0xFF));
+    set_interrupts_enabled(false);
+}
+
+/// Store `bytes` in the EEPROM starting at `address`, e.g., to simulate data written before.
+#[cfg(test)]
+pub(crate) fn preset_eeprom(address: u16, bytes: &[u8]) {
+    let address = usize::from(address);
+    EEPROM.with_borrow_mut(|eeprom| eeprom[address..address + bytes.len()].copy_from_slice(bytes));
+}
+
+/// Check whether global interrupts are enabled.
+pub(crate) fn interrupts_enabled() -> bool {
+    INTERRUPTS_ENABLED.with_borrow(|enabled| *enabled)
+}
+
+/// Enable or disable global interrupts.
+pub(crate) fn set_interrupts_enabled(enabled: bool) {
+    INTERRUPTS_ENABLED.with_borrow_mut(|flag| *flag = enabled);
+}
+
+/// Lock the global state of the crate for the calling test, see `GLOBAL_STATE`.
+#[cfg(test)]
+pub(crate) fn lock_global_state() -> MutexGuard<'static, ()> {
+    GLOBAL_STATE
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Run `f` with fresh registers and a transmitter that is always ready, and return the bytes it
+/// wrote to the `Serial` connection.
+#[cfg(test)]
+pub(crate) fn capture_serial(f: impl FnOnce()) -> Vec<u8> {
+    let _guard = lock_global_state();
+    reset();
+    preset::<UCSRA>(UDRE);
+    f();
+    take_writes::<UDR>()
+}
+
+/// Stand-in for `avr_delay::delay_ms`.
+pub fn delay_ms(_ms: u32) {}
+
+/// Stand-in for `avr_delay::delay_us`.
+pub fn delay_us(_us: u32) {}
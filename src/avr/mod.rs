@@ -4,8 +4,26 @@
 pub mod device;
 pub use device::*;
 
+pub mod adc;
+
+pub mod capture;
+
+pub mod eeprom;
+
+#[cfg(target_arch = "avr")]
+pub mod flash;
+
 pub mod interrupt;
 
+#[cfg(not(target_arch = "avr"))]
+pub(crate) mod mock;
+
+pub mod power;
+
+pub mod reset;
+
+pub mod twi;
+
 #[allow(unused)]
 pub mod legacy;
 #[allow(unused)]
@@ -16,9 +34,9 @@ pub use avr_config as config;
 
 /// Convenience module grouping functions that might be worth re-exporting to other crates.
 pub mod prelude {
-    pub(crate) use super::device::set_pins;
     pub use super::{
-        device::{DataDirection, Pin, Register, RegisterBits, RegisterValue},
+        device::{DataDirection, DynPin, Pin, Register, RegisterBits, RegisterValue},
         interrupt,
+        modules::Timer8,
     };
 }
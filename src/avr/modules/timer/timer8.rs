@@ -1,4 +1,7 @@
-use crate::{Register, RegisterBits};
+use crate::{
+    interrupt::{self, mutex::Mutex},
+    Register, RegisterBits,
+};
 use core::marker;
 
 /// A 8-bit timer.
@@ -52,6 +55,105 @@ pub trait Timer8: Sized {
 
     /// Output compare interrupt enable flag.
     const OCIEA: RegisterBits<Self::InterruptMask>;
+
+    /// Output compare flag.
+    const OCFA: RegisterBits<Self::InterruptFlag>;
+
+    /// Whether the timer's output compare interrupt fires its one-shots, i.e., whether
+    /// `Timer8::after` is available.
+    const ONE_SHOT: bool;
+
+    /// Storage of the handler of the timer's pending one-shot, see `Timer8::after`.
+    fn one_shot_handler() -> &'static Mutex<Option<fn()>>;
+
+    /// Arms a one-shot that calls `handler` once after `ticks` timer ticks, triggered by the
+    /// timer's output compare interrupt, e.g., `TIMER0_COMP`. Afterwards, the output compare
+    /// interrupt is disabled again. This is useful for timeouts, e.g., for debouncing or IR frames.
+    ///
+    /// The timer is restarted in CTC mode. If the timer was stopped, it is started with
+    /// `ClockSource::Prescale1024`, i.e., a tick lasts 128µs at 8MHz. Otherwise, the duration of a
+    /// tick depends on the configured clock source.
+    ///
+    /// Only a single one-shot per timer is supported: arming a new one-shot replaces a pending one.
+    /// The timer should not be used for any other purpose while a one-shot is pending.
+    ///
+    /// The one-shots of `Timer2` require the feature `timer2-one-shot`, which claims the
+    /// `TIMER2_COMP` interrupt, and fail to compile otherwise.
+    fn after(ticks: u8, handler: fn()) {
+        const {
+            assert!(
+                Self::ONE_SHOT,
+                "the one-shots of `Timer2` require the feature `timer2-one-shot`"
+            )
+        };
+        interrupt::without_interrupts(|cs| {
+            Self::one_shot_handler().lock(cs).set(Some(handler));
+
+            // stop the timer while it is reconfigured
+            let clock_select = Self::ControlB::read() & u8::from(!ClockSource::mask::<Self>());
+            Self::ControlB::unset(!ClockSource::mask::<Self>());
+
+            // restart counting from zero in CTC mode
+            let (a, b) = WaveformGenerationMode::mask::<Self>();
+            Self::ControlA::unset(!a);
+            Self::ControlB::unset(!b);
+            let (a, b) = WaveformGenerationMode::ClearOnTimerMatchOutputCompare.bits::<Self>();
+            Self::ControlA::set(a);
+            Self::ControlB::set(b);
+            Self::Counter::write(0);
+            Self::CompareA::write(ticks);
+
+            // clear a stale compare match (by writing a logical one) and enable the interrupt
+            Self::InterruptFlag::write(Self::OCFA);
+            Self::InterruptMask::set(Self::OCIEA);
+
+            // (re-)start the timer
+            if clock_select == 0 {
+                Self::ControlB::set(ClockSource::Prescale1024.bits::<Self>());
+            } else {
+                Self::ControlB::set_mask_raw(clock_select);
+            }
+        });
+    }
+
+    /// Disarms the timer's pending one-shot, if any.
+    fn cancel_one_shot() {
+        interrupt::without_interrupts(|cs| {
+            Self::InterruptMask::unset(Self::OCIEA);
+            Self::one_shot_handler().lock(cs).set(None);
+        });
+    }
+
+    /// Fires the timer's pending one-shot, if any, and disarms it. Must be called from the timer's
+    /// output compare interrupt.
+    fn on_compare_match() {
+        let handler = interrupt::without_interrupts(|cs| {
+            let handler = Self::one_shot_handler().lock(cs).take();
+            if handler.is_some() {
+                Self::InterruptMask::unset(Self::OCIEA);
+            }
+            handler
+        });
+
+        if let Some(handler) = handler {
+            handler();
+        }
+    }
+
+    /// Selects the clock source of the running timer, e.g., `TCCR0`'s `CS0` bits, while keeping
+    /// its waveform generation mode, counter and compare values. `ClockSource::None` stops the
+    /// timer, e.g., to save power.
+    ///
+    /// Note that the system tick of the `time` module selects the clock source of `Timer0` itself
+    /// in `time::init_tick`, so that `millis` advances at the configured tick period. Changing the
+    /// clock source of `Timer0` while the tick is running lets `millis` run faster, slower or stop.
+    /// Stop the tick via `time::stop_tick` before repurposing `Timer0`.
+    fn set_clock_source(source: ClockSource) {
+        interrupt::without_interrupts(|_| {
+            Self::ControlB::unset(!ClockSource::mask::<Self>());
+            Self::ControlB::set(source.bits::<Self>());
+        });
+    }
 }
 
 pub enum ClockSource {
@@ -0,0 +1,61 @@
+use super::{
+    super::{
+        bitmasks::SE,
+        interrupt::{self, EventFlags},
+        registers::MCUCR,
+        Register,
+    },
+    set_sleep_mode, SleepMode,
+};
+#[cfg(target_arch = "avr")]
+use core::arch::asm;
+
+/// Event signalled by the `USART_RXC` interrupt whenever a byte was received via `Serial`.
+pub const EVENT_SERIAL_RECEIVED: u8 = 1 << 0;
+/// Event signalled by the `TIMER0_COMP` interrupt on every system tick, see `rp6::time`.
+pub const EVENT_TICK: u8 = 1 << 1;
+
+/// Events signalled by interrupts that have not yet been consumed by `sleep_until`.
+static EVENTS: EventFlags = EventFlags::new();
+
+/// Record the given `events` (a bitmask), e.g., from an interrupt handler, to wake a pending
+/// `sleep_until`. Bits 0 and 1 are used by the interrupts of this crate, see
+/// `EVENT_SERIAL_RECEIVED` and `EVENT_TICK`, while the remaining bits are free to be used by
+/// custom interrupt handlers.
+pub fn signal(events: u8) {
+    EVENTS.set(events);
+}
+
+/// Enter the idle sleep mode until at least one of the events in `mask` has been signalled, see
+/// `signal`. Returns the signalled events of `mask` and clears them, while other events are kept.
+/// Returns immediately if one of the events has already been signalled before.
+///
+/// The interrupts signalling the events must be enabled, e.g., the `USART_RXC` interrupt for
+/// `EVENT_SERIAL_RECEIVED` or the system tick (`rp6::time::init_tick`) for `EVENT_TICK`, as this
+/// would sleep forever otherwise. Any other interrupt wakes the device as well, but it goes back
+/// to sleep unless one of the events in `mask` was signalled. Must not be called within a
+/// `CriticalSection`, as interrupts are enabled while sleeping.
+pub fn sleep_until(mask: u8) -> u8 {
+    set_sleep_mode(SleepMode::Idle);
+    loop {
+        let events = interrupt::without_interrupts(|cs| {
+            let events = EVENTS.take_mask_in(cs, mask);
+            if events == 0 {
+                // `SEI` delays interrupts until after the next instruction, so an event signalled
+                // after the check above will always wake the device from `SLEEP`
+                MCUCR::set_mask_raw(SE);
+                #[cfg(target_arch = "avr")]
+                unsafe {
+                    asm!("SEI", "SLEEP")
+                }
+                #[cfg(not(target_arch = "avr"))]
+                crate::avr::mock::set_interrupts_enabled(true);
+                MCUCR::unset_mask_raw(SE);
+            }
+            events
+        });
+        if events != 0 {
+            return events;
+        }
+    }
+}
@@ -0,0 +1,67 @@
+//! Routines for putting the device to sleep to save power.
+
+use super::{
+    bitmasks::{SE, SM},
+    registers::MCUCR,
+    Register,
+};
+#[cfg(target_arch = "avr")]
+use core::arch::asm;
+
+/// Module allowing to sleep until one of several events is signalled by an interrupt.
+mod events;
+pub use events::*;
+
+/// Sleep modes of the atmega32, ordered from the lightest to the deepest sleep. Note that only a
+/// few interrupts (e.g., external interrupts or the TWI address match) can wake the device from
+/// the deeper modes, see the datasheet.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SleepMode {
+    /// Stops the CPU, but all peripherals (timers, USART, ADC, ...) keep running.
+    Idle,
+    /// Stops the CPU and most clocks except for the ADC to reduce noise during conversions.
+    AdcNoiseReduction,
+    /// Stops all clocks, only asynchronous interrupts can wake the device.
+    PowerDown,
+    /// Like `PowerDown`, but `Timer2` keeps running if clocked asynchronously.
+    PowerSave,
+    /// Like `PowerDown`, but the oscillator keeps running for a fast wake-up.
+    Standby,
+    /// Like `PowerSave`, but the oscillator keeps running for a fast wake-up.
+    ExtendedStandby,
+}
+
+impl SleepMode {
+    /// Bits of the sleep mode in `MCUCR`.
+    fn bits(self) -> u8 {
+        use SleepMode::*;
+
+        let mode = match self {
+            Idle => 0b000,
+            AdcNoiseReduction => 0b001,
+            PowerDown => 0b010,
+            PowerSave => 0b011,
+            Standby => 0b110,
+            ExtendedStandby => 0b111,
+        };
+        (mode << 4) & SM
+    }
+}
+
+/// Select the sleep mode entered by the next call to `sleep` or `sleep_until`.
+pub fn set_sleep_mode(mode: SleepMode) {
+    MCUCR::write((MCUCR::read() & !SM) | mode.bits());
+}
+
+/// Enter the given sleep mode until an (enabled) interrupt wakes the device. Make sure that
+/// global interrupts are enabled and an interrupt able to wake the device from `mode` is enabled,
+/// as it would sleep forever otherwise.
+pub fn sleep(mode: SleepMode) {
+    set_sleep_mode(mode);
+    MCUCR::set_mask_raw(SE);
+    #[cfg(target_arch = "avr")]
+    unsafe {
+        asm!("SLEEP")
+    }
+    MCUCR::unset_mask_raw(SE);
+}
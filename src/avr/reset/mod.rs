@@ -0,0 +1,54 @@
+//! Detection of the cause of the last reset of the device.
+
+use super::{
+    bitmasks::{BORF, EXTRF, JTRF, PORF, WDRF},
+    registers::MCUCSR,
+    Register,
+};
+
+/// Causes of a reset, as indicated by the reset flags in `MCUCSR`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ResetCause {
+    /// The supply voltage was turned on.
+    PowerOn,
+    /// The reset pin was pulled low, e.g., by the reset button.
+    External,
+    /// The supply voltage dropped below the brown-out detection level, e.g., due to a low battery.
+    BrownOut,
+    /// The watchdog timer expired.
+    Watchdog,
+    /// A reset was requested via the JTAG interface.
+    Jtag,
+    /// No reset flag is set, e.g., after jumping to the reset vector in software or if the flags
+    /// have already been cleared.
+    Unknown,
+}
+
+/// All reset flags in `MCUCSR`.
+const RESET_FLAGS: u8 = PORF | EXTRF | BORF | WDRF | JTRF;
+
+/// Determine the cause of the last reset from the reset flags. As the flags accumulate until they
+/// are cleared, e.g., the brown-out flag is usually set along with the power-on flag, the most
+/// fundamental cause takes precedence. Call `clear_reset_flags` afterwards to correctly detect
+/// the cause of the next reset.
+pub fn reset_cause() -> ResetCause {
+    let flags = MCUCSR::read();
+    if flags & PORF != 0 {
+        ResetCause::PowerOn
+    } else if flags & BORF != 0 {
+        ResetCause::BrownOut
+    } else if flags & WDRF != 0 {
+        ResetCause::Watchdog
+    } else if flags & EXTRF != 0 {
+        ResetCause::External
+    } else if flags & JTRF != 0 {
+        ResetCause::Jtag
+    } else {
+        ResetCause::Unknown
+    }
+}
+
+/// Clear all reset flags, keeping the other bits of `MCUCSR`.
+pub fn clear_reset_flags() {
+    MCUCSR::unset_mask_raw(RESET_FLAGS);
+}
@@ -0,0 +1,320 @@
+//! TWI = "Two-Wire Interface", i.e., the I2C bus.
+//!
+//! This module implements a blocking TWI master, e.g., to access sensors or memories connected to
+//! the robot's expansion bus via the pins `SCL` (`c0`) and `SDA` (`c1`).
+
+use super::{
+    bitmasks::{TWEA, TWEN, TWINT, TWS, TWSTA, TWSTO},
+    config::CPU_FREQUENCY_HZ,
+    port::{c0 as SCL, c1 as SDA},
+    registers::{TWBR, TWCR, TWDR, TWSR},
+    Pin, Register,
+};
+use crate::delay_us;
+
+/// Standard clock frequency of the bus in Hz.
+pub const TWI_STANDARD_MODE_HZ: u32 = 100_000;
+/// Clock frequency of the bus in fast mode in Hz.
+pub const TWI_FAST_MODE_HZ: u32 = 400_000;
+
+/// Number of polling iterations after which a TWI operation is considered to be stuck, e.g., due
+/// to a slave holding `SCL` low.
+const TIMEOUT_ITERATIONS: u16 = 10_000;
+
+/// Delay in µs between two attempts of `TwiMaster::with_retries`, giving a busy slave time to
+/// become ready, e.g., an EEPROM completing a write cycle.
+const RETRY_DELAY_US: u32 = 500;
+
+/// Half of the period in µs of the clock pulses generated by `TwiMaster::bus_recover`, i.e., a
+/// clock of 100kHz that every slave supports.
+const RECOVERY_HALF_PERIOD_US: u32 = 5;
+
+/// Maximum number of clock pulses generated by `TwiMaster::bus_recover`: a slave holding `SDA` low
+/// in the middle of a byte releases it after at most 8 data bits and the acknowledge bit.
+const RECOVERY_CLOCK_PULSES: u8 = 9;
+
+/// Status codes of `TWSR`, see the atmega32 datasheet.
+mod status {
+    pub const START: u8 = 0x08;
+    pub const REPEATED_START: u8 = 0x10;
+    pub const TRANSMIT_ADDRESS_ACK: u8 = 0x18;
+    pub const TRANSMIT_ADDRESS_NACK: u8 = 0x20;
+    pub const TRANSMIT_DATA_ACK: u8 = 0x28;
+    pub const TRANSMIT_DATA_NACK: u8 = 0x30;
+    pub const ARBITRATION_LOST: u8 = 0x38;
+    pub const RECEIVE_ADDRESS_ACK: u8 = 0x40;
+    pub const RECEIVE_ADDRESS_NACK: u8 = 0x48;
+    pub const RECEIVE_DATA_ACK: u8 = 0x50;
+    pub const RECEIVE_DATA_NACK: u8 = 0x58;
+}
+
+/// Errors that may occur during a TWI transfer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TwiError {
+    /// No slave acknowledged its address, e.g., because it is busy or not connected.
+    AddressNack,
+    /// The slave did not acknowledge a data byte.
+    DataNack,
+    /// Another master took over the bus.
+    ArbitrationLost,
+    /// The bus did not respond in time.
+    Timeout,
+    /// The TWI hardware reported an unexpected status code.
+    Unexpected(u8),
+}
+
+impl TwiError {
+    /// Check if retrying the transfer may succeed, i.e., for a NACK, e.g., of a busy slave, or a
+    /// lost arbitration, see `TwiMaster::with_retries`. A timeout indicates a hung bus instead,
+    /// see `TwiMaster::bus_recover`.
+    pub fn is_recoverable(self) -> bool {
+        matches!(
+            self,
+            TwiError::AddressNack | TwiError::DataNack | TwiError::ArbitrationLost
+        )
+    }
+}
+
+/// Struct managing the TWI hardware as bus master. All transfers are blocking. Slave addresses
+/// are given as 7-bit addresses, i.e., without the read/write bit.
+pub struct TwiMaster;
+
+impl TwiMaster {
+    /// Enable the TWI hardware as master with the given bus clock frequency in Hz, e.g.,
+    /// `TWI_STANDARD_MODE_HZ`. Note that the bus requires pull-up resistors on `SCL` and `SDA`.
+    /// Both pins are claimed by the TWI hardware until `TwiMaster::disable`, see `Pin::claim`.
+    pub fn init(frequency_hz: u32) {
+        // prescaler 1: SCL = CPU_FREQUENCY_HZ / (16 + 2 * TWBR)
+        TWSR::write(0);
+        let bit_rate = (CPU_FREQUENCY_HZ / frequency_hz).saturating_sub(16) / 2;
+        TWBR::write(bit_rate.min(u8::MAX as u32) as u8);
+        TWCR::write(TWEN);
+        SCL::claim();
+        SDA::claim();
+    }
+
+    /// Disable the TWI hardware, releasing the pins `SCL` and `SDA`.
+    pub fn disable() {
+        TWCR::write(0);
+        SCL::release();
+        SDA::release();
+    }
+
+    /// Write `data` to the slave with the given `address`.
+    pub fn write(address: u8, data: &[u8]) -> Result<(), TwiError> {
+        Self::transfer(|| {
+            Self::start(address, false)?;
+            data.iter().try_for_each(|&byte| Self::write_byte(byte))
+        })
+    }
+
+    /// Write `prefix` followed by `data` to the slave with the given `address` in a single transfer,
+    /// e.g., a register or memory address followed by the data to store there.
+    pub fn write_prefixed(address: u8, prefix: &[u8], data: &[u8]) -> Result<(), TwiError> {
+        Self::transfer(|| {
+            Self::start(address, false)?;
+            prefix
+                .iter()
+                .chain(data)
+                .try_for_each(|&byte| Self::write_byte(byte))
+        })
+    }
+
+    /// Read `buffer.len()` bytes from the slave with the given `address` into `buffer`.
+    pub fn read(address: u8, buffer: &mut [u8]) -> Result<(), TwiError> {
+        Self::transfer(|| {
+            Self::start(address, true)?;
+            Self::read_bytes(buffer)
+        })
+    }
+
+    /// Write `data` to the slave with the given `address` and then, after a repeated start, read
+    /// `buffer.len()` bytes from the same slave into `buffer`, e.g., to read the registers of a
+    /// sensor starting at a given register address.
+    pub fn write_read(address: u8, data: &[u8], buffer: &mut [u8]) -> Result<(), TwiError> {
+        Self::transfer(|| {
+            Self::start(address, false)?;
+            data.iter().try_for_each(|&byte| Self::write_byte(byte))?;
+            Self::start(address, true)?;
+            Self::read_bytes(buffer)
+        })
+    }
+
+    /// Check if the slave with the given `address` acknowledges its address.
+    pub fn probe(address: u8) -> bool {
+        Self::transfer(|| Self::start(address, false)).is_ok()
+    }
+
+    /// Execute `op`, e.g., a closure calling `TwiMaster::write_read`, and retry it up to `retries`
+    /// times as long as it fails with a recoverable error (see `TwiError::is_recoverable`), waiting
+    /// 0.5ms before each retry. Returns the result of the first successful attempt, or the error of
+    /// the last attempt, which is returned right away if it is not recoverable.
+    ///
+    /// Example:
+    /// ```rust
+    /// let mut data = [0u8; 6];
+    /// TwiMaster::with_retries(3, || TwiMaster::write_read(ADDRESS, &[REGISTER], &mut data))?;
+    /// ```
+    pub fn with_retries<T>(
+        retries: u8,
+        mut op: impl FnMut() -> Result<T, TwiError>,
+    ) -> Result<T, TwiError> {
+        let mut remaining = retries;
+        loop {
+            match op() {
+                Err(error) if error.is_recoverable() && remaining > 0 => {
+                    remaining -= 1;
+                    delay_us(RETRY_DELAY_US);
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Free a bus on which a slave holds `SDA` low, e.g., because it was interrupted by a reset of
+    /// the robot in the middle of sending a byte, so that it waits for further clock pulses.
+    ///
+    /// The TWI hardware is disabled, and up to 9 clock pulses are bit-banged on `SCL` at 100kHz
+    /// until the slave releases `SDA`, after which a stop condition resets the state machines of
+    /// all slaves. Both pins are driven as open-drain outputs, i.e., only pulled low or released,
+    /// and the clock respects clock stretching by slaves. Afterwards, the TWI hardware is
+    /// re-enabled with the previous clock frequency if it was enabled before. Returns
+    /// `TwiError::Timeout` if `SDA` or `SCL` is still held low, e.g., due to a short circuit.
+    pub fn bus_recover() -> Result<(), TwiError> {
+        let enabled = TWCR::is_mask_set_raw(TWEN);
+        Self::disable();
+        SCL::set_low();
+        SDA::set_low();
+        SDA::set_input();
+        Self::release_scl();
+
+        let mut pulses = 0;
+        while SDA::is_low() && pulses < RECOVERY_CLOCK_PULSES {
+            SCL::set_output();
+            delay_us(RECOVERY_HALF_PERIOD_US);
+            Self::release_scl();
+            pulses += 1;
+        }
+
+        // stop condition: `SDA` rises while `SCL` is high
+        SCL::set_output();
+        SDA::set_output();
+        delay_us(RECOVERY_HALF_PERIOD_US);
+        let scl_released = Self::release_scl();
+        SDA::set_input();
+        delay_us(RECOVERY_HALF_PERIOD_US);
+        let sda_released = SDA::is_high();
+
+        if enabled {
+            TWCR::write(TWEN);
+            SCL::claim();
+            SDA::claim();
+        }
+        if scl_released && sda_released {
+            Ok(())
+        } else {
+            Err(TwiError::Timeout)
+        }
+    }
+
+    /// Release `SCL` and wait for half of a clock period, plus while a slave stretches the clock
+    /// by holding `SCL` low, for at most 1ms. Returns whether `SCL` is high.
+    fn release_scl() -> bool {
+        SCL::set_input();
+        delay_us(RECOVERY_HALF_PERIOD_US);
+        for _ in 0..1000 {
+            if SCL::is_high() {
+                return true;
+            }
+            delay_us(1);
+        }
+        false
+    }
+
+    /// Execute the transfer `f` and release the bus with a stop condition afterwards, also if the
+    /// transfer failed.
+    fn transfer(f: impl FnOnce() -> Result<(), TwiError>) -> Result<(), TwiError> {
+        let result = f();
+        if result != Err(TwiError::ArbitrationLost) {
+            // a master that lost arbitration must not generate a stop condition
+            Self::stop();
+        }
+        result
+    }
+
+    /// Generate a stop condition and wait until it has been executed on the bus, i.e., until the
+    /// hardware clears `TWSTO`, as a start condition requested before would be lost otherwise,
+    /// e.g., when polling a device via `TwiMaster::probe`.
+    fn stop() {
+        TWCR::write(TWINT | TWEN | TWSTO);
+        let mut iterations = 0;
+        while TWCR::is_mask_set_raw(TWSTO) && iterations < TIMEOUT_ITERATIONS {
+            iterations += 1;
+        }
+    }
+
+    /// Generate a (repeated) start condition and address the slave for reading or writing.
+    fn start(address: u8, read: bool) -> Result<(), TwiError> {
+        match Self::execute(TWINT | TWEN | TWSTA)? {
+            status::START | status::REPEATED_START => {}
+            status => return Err(Self::error(status)),
+        }
+
+        TWDR::write(address << 1 | read as u8);
+        match Self::execute(TWINT | TWEN)? {
+            status::TRANSMIT_ADDRESS_ACK | status::RECEIVE_ADDRESS_ACK => Ok(()),
+            status => Err(Self::error(status)),
+        }
+    }
+
+    /// Transmit a single data byte.
+    fn write_byte(byte: u8) -> Result<(), TwiError> {
+        TWDR::write(byte);
+        match Self::execute(TWINT | TWEN)? {
+            status::TRANSMIT_DATA_ACK => Ok(()),
+            status => Err(Self::error(status)),
+        }
+    }
+
+    /// Receive bytes into `buffer`, acknowledging all but the last byte.
+    fn read_bytes(buffer: &mut [u8]) -> Result<(), TwiError> {
+        let count = buffer.len();
+        for (index, byte) in buffer.iter_mut().enumerate() {
+            let last = index + 1 == count;
+            let control = if last {
+                TWINT | TWEN
+            } else {
+                TWINT | TWEN | TWEA
+            };
+            match Self::execute(control)? {
+                status::RECEIVE_DATA_ACK | status::RECEIVE_DATA_NACK => *byte = TWDR::read(),
+                status => return Err(Self::error(status)),
+            }
+        }
+        Ok(())
+    }
+
+    /// Write `control` to `TWCR` to start the next step of a transfer, wait until it is finished
+    /// and return the resulting status code.
+    fn execute(control: u8) -> Result<u8, TwiError> {
+        TWCR::write(control);
+        let mut iterations = 0;
+        while !TWCR::is_mask_set_raw(TWINT) {
+            iterations += 1;
+            if iterations >= TIMEOUT_ITERATIONS {
+                return Err(TwiError::Timeout);
+            }
+        }
+        Ok(TWSR::read() & TWS)
+    }
+
+    /// Map an unexpected status code to the corresponding error.
+    fn error(status: u8) -> TwiError {
+        match status {
+            status::TRANSMIT_ADDRESS_NACK | status::RECEIVE_ADDRESS_NACK => TwiError::AddressNack,
+            status::TRANSMIT_DATA_NACK => TwiError::DataNack,
+            status::ARBITRATION_LOST => TwiError::ArbitrationLost,
+            status => TwiError::Unexpected(status),
+        }
+    }
+}
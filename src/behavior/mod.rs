@@ -0,0 +1,91 @@
+//! State machines for robot behaviors, e.g., driving around while avoiding obstacles.
+//!
+//! The states of a behavior are an `enum`, and a user-provided transition function determines the
+//! next state from the current state, an event collected from the sensors (e.g., the bumpers, the
+//! ACS or the encoders) and the time spent in the current state. The state machine is stepped from
+//! the main loop and reports state changes, so that the main loop can perform the corresponding
+//! actions, e.g., set the motors. See `examples/03_obstacle_avoidance.rs` for a complete behavior.
+//!
+//! Example:
+//! ```rust
+//! #[derive(Clone, Copy, PartialEq, Eq)]
+//! enum State { Forward, Turn }
+//!
+//! fn transition(state: State, bumper_hit: bool, time_in_state_ms: u32) -> State {
+//!     match state {
+//!         State::Forward if bumper_hit => State::Turn,
+//!         State::Turn if time_in_state_ms >= 500 => State::Forward,
+//!         state => state,
+//!     }
+//! }
+//!
+//! let mut behavior = StateMachine::new(State::Forward, transition);
+//! loop {
+//!     match behavior.step(bumper_hit()) {
+//!         Some(State::Forward) => Motors::drive(100, 100),
+//!         Some(State::Turn) => Motors::drive(-80, 80),
+//!         None => {}
+//!     }
+//! }
+//! ```
+
+use crate::time;
+
+/// Transition function of a `StateMachine`: determines the next state from the current state, an
+/// event and the time in ms spent in the current state. Returning the current state stays in it.
+pub type Transition<S, E> = fn(S, E, u32) -> S;
+
+/// State machine with states `S`, driven by events `E`. Does not allocate, as the states are
+/// stored by value and the transitions are given by a plain function, see `Transition`.
+pub struct StateMachine<S: Copy + PartialEq, E> {
+    /// The current state.
+    state: S,
+    /// Time (see `time::millis`) at which the current state was entered.
+    entered_ms: u32,
+    /// Function determining the next state.
+    transition: Transition<S, E>,
+}
+
+impl<S: Copy + PartialEq, E> StateMachine<S, E> {
+    /// Create a new state machine starting in the `initial` state.
+    ///
+    /// Requires the system tick to be started with `time::init_tick` for the time spent in a state
+    /// to advance.
+    pub fn new(initial: S, transition: Transition<S, E>) -> Self {
+        Self {
+            state: initial,
+            entered_ms: time::millis(),
+            transition,
+        }
+    }
+
+    /// The current state.
+    pub fn state(&self) -> S {
+        self.state
+    }
+
+    /// Time in ms spent in the current state.
+    pub fn time_in_state_ms(&self) -> u32 {
+        time::millis().wrapping_sub(self.entered_ms)
+    }
+
+    /// Apply the transition function to the current state and `event`. Returns the new state if the
+    /// state changed, so that the caller can perform the actions of entering it, or `None`
+    /// otherwise. Call this regularly from the main loop, e.g., with the latest sensor event.
+    pub fn step(&mut self, event: E) -> Option<S> {
+        let next = (self.transition)(self.state, event, self.time_in_state_ms());
+        if next == self.state {
+            None
+        } else {
+            self.enter(next);
+            Some(next)
+        }
+    }
+
+    /// Enter `state` immediately, e.g., to abort a behavior. Resets the time spent in the state,
+    /// even if the state machine already is in `state`.
+    pub fn enter(&mut self, state: S) {
+        self.state = state;
+        self.entered_ms = time::millis();
+    }
+}
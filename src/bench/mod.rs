@@ -0,0 +1,137 @@
+//! Cycle-accurate benchmarking of code blocks using `Timer1` as free-running counter. Only
+//! available with the feature `bench`, as the benchmark claims the `TIMER1_OVF` interrupt for
+//! itself to count overflows of `Timer1` during long blocks.
+//!
+//! As `Timer1` generates the motors' PWM signals, it is temporarily reconfigured for each
+//! measurement and restored afterwards, so the motors should be stopped while benchmarking.
+//! Benchmarks must not be nested.
+
+use crate::{
+    avr::{
+        bitmasks::{CS10, TOIE1, TOV1},
+        registers::{TCCR1A, TCCR1B, TIFR, TIMSK},
+        timers::Timer1,
+    },
+    interrupt::{self, mutex::Mutex},
+    print, Register, Serial,
+};
+use avr_macros::interrupt;
+
+/// Number of overflows of `Timer1` since the start of the running benchmark.
+static OVERFLOWS: Mutex<u16> = Mutex::new(0);
+
+/// Number of cycles measured for an empty block, i.e., the overhead of `Bench::start` and
+/// `Bench::stop` themselves. Measured on first use.
+static CALIBRATION: Mutex<Option<u32>> = Mutex::new(None);
+
+#[interrupt]
+fn TIMER1_OVF() {
+    interrupt::without_interrupts(|cs| OVERFLOWS.lock(cs).update(|n| n.wrapping_add(1)));
+}
+
+/// A running benchmark, holding the configuration of `Timer1` to restore when it is stopped.
+#[must_use = "a benchmark must be stopped to restore the configuration of Timer1"]
+pub struct Bench {
+    control_a: u8,
+    control_b: u8,
+    mask: u8,
+}
+
+impl Bench {
+    /// Start counting CPU cycles with `Timer1`. On first use, the overhead of the measurement
+    /// itself is calibrated beforehand.
+    #[inline(always)]
+    pub fn start() -> Self {
+        if interrupt::without_interrupts(|cs| CALIBRATION.lock(cs).get()).is_none() {
+            let overhead = Self::start_raw().stop_raw();
+            interrupt::without_interrupts(|cs| CALIBRATION.lock(cs).set(Some(overhead)));
+        }
+        Self::start_raw()
+    }
+
+    /// Stop counting and return the number of CPU cycles since `Bench::start`, excluding the
+    /// calibrated overhead of the measurement.
+    #[inline(always)]
+    pub fn stop(self) -> u32 {
+        let cycles = self.stop_raw();
+        let overhead = interrupt::without_interrupts(|cs| CALIBRATION.lock(cs).get());
+        cycles.saturating_sub(overhead.unwrap_or(0))
+    }
+
+    /// Save the configuration of `Timer1` and let it count from zero in normal mode without
+    /// prescaler, i.e., counting CPU cycles.
+    #[inline(always)]
+    fn start_raw() -> Self {
+        let bench = interrupt::without_interrupts(|cs| {
+            let bench = Self {
+                control_a: TCCR1A::read(),
+                control_b: TCCR1B::read(),
+                mask: TIMSK::read(),
+            };
+
+            // stop Timer1 and clear a stale overflow (by writing a logical one)
+            TCCR1B::write(0);
+            TCCR1A::write(0);
+            Timer1::write_counter(0);
+            OVERFLOWS.lock(cs).set(0);
+            TIFR::write(TOV1);
+            TIMSK::set_mask_raw(TOIE1);
+            bench
+        });
+
+        // start Timer1 as the very last step
+        TCCR1B::write(CS10);
+        bench
+    }
+
+    /// Stop `Timer1` as the very first step, read the elapsed cycles and restore its
+    /// configuration.
+    #[inline(always)]
+    fn stop_raw(self) -> u32 {
+        TCCR1B::write(0);
+
+        interrupt::without_interrupts(|cs| {
+            let counter = Timer1::read_counter();
+            let mut overflows = OVERFLOWS.lock(cs).get();
+            // an overflow right before stopping may not have been handled yet
+            if TIFR::is_mask_set_raw(TOV1) {
+                TIFR::write(TOV1);
+                overflows = overflows.wrapping_add(1);
+            }
+
+            // restore the configuration of Timer1
+            TIMSK::write(self.mask);
+            TCCR1A::write(self.control_a);
+            TCCR1B::write(self.control_b);
+
+            (u32::from(overflows) << 16) | u32::from(counter)
+        })
+    }
+}
+
+/// Write the result of a benchmark as `"<name>: <cycles> cycles"` over the `Serial` connection.
+/// Called by the `bench!` macro.
+#[doc(hidden)]
+#[inline(never)]
+pub fn report(name: &str, cycles: u32) {
+    print!(name, ": ", cycles, " cycles\n");
+}
+
+/// Measure the number of CPU cycles spent executing a block and write it over the `Serial`
+/// connection. Evaluates to the value of the block. The overhead of the measurement is calibrated
+/// and subtracted, and overflows of `Timer1` are counted, so blocks may take up to `u32::MAX`
+/// cycles. Interrupts occurring during the block are included in the measurement.
+///
+/// Example:
+/// ```rust
+/// let speed = bench!("pid update", { controller.update(error) }); // e.g., "pid update: 412 cycles"
+/// ```
+#[macro_export]
+macro_rules! bench {
+    ($name: expr, $body: block $(,)?) => {{
+        let bench = $crate::bench::Bench::start();
+        let result = $body;
+        $crate::bench::report($name, bench.stop());
+        result
+    }};
+}
@@ -0,0 +1,175 @@
+//! CLI = "Command Line Interface"
+//!
+//! This module allows to control the robot interactively via its serial connection by registering
+//! commands that are parsed from the received lines of text and dispatched to handler closures.
+//!
+//! Example:
+//! ```rust
+//! let mut set_leds = |args: &[i32]| RobotBase::set_leds(args[0] as u8);
+//! let mut cli = CommandInterpreter::<4>::new();
+//! cli.register("leds", 1, &mut set_leds);
+//! cli.run(); // e.g., receiving "leds 63" turns on all LEDs
+//! ```
+
+use crate::{print, println, Serial, SerialWritable};
+use heapless::Vec;
+
+/// Maximum number of integer arguments of a command.
+pub const MAX_ARGS: usize = 4;
+
+/// Maximum length of a command line, longer lines are truncated.
+pub const MAX_LINE_LENGTH: usize = 64;
+
+/// Errors that may occur when executing a command line.
+pub enum CommandError<'l> {
+    /// No command with the given name is registered.
+    UnknownCommand(&'l str),
+    /// The command expects a different number of arguments.
+    WrongArgumentCount { expected: usize, given: usize },
+    /// The given argument is not a valid `i32` number.
+    InvalidArgument(&'l str),
+}
+
+impl SerialWritable for CommandError<'_> {
+    /// Write a human-readable description of the error to the `Serial` connection.
+    fn write_to_serial(&self) {
+        match *self {
+            CommandError::UnknownCommand(name) => {
+                print!("unknown command '", name, "'");
+            }
+            CommandError::WrongArgumentCount { expected, given } => {
+                print!("expected ", expected, " argument(s), got ", given);
+            }
+            CommandError::InvalidArgument(argument) => {
+                print!("invalid argument '", argument, "'");
+            }
+        }
+    }
+}
+
+/// A command registered with a `CommandInterpreter`.
+struct Command<'a> {
+    /// Name of the command, i.e., the first word of a command line.
+    name: &'a str,
+    /// Number of integer arguments expected by the command.
+    args: usize,
+    /// Handler called with the parsed arguments.
+    handler: &'a mut dyn FnMut(&[i32]),
+}
+
+/// Interpreter for lines of the form `<name> [<arg> ...]` that dispatches them to the handler
+/// registered for `<name>`, passing up to `MAX_ARGS` whitespace-separated arguments parsed as
+/// `i32`. Supports up to `N` registered commands.
+pub struct CommandInterpreter<'a, const N: usize> {
+    commands: Vec<Command<'a>, N>,
+}
+
+impl<'a, const N: usize> CommandInterpreter<'a, N> {
+    /// Create a new `CommandInterpreter` without any commands.
+    pub fn new() -> Self {
+        Self {
+            commands: Vec::new(),
+        }
+    }
+
+    /// Register `handler` to be called for lines starting with `name`, expecting exactly `args`
+    /// integer arguments (at most `MAX_ARGS`). Returns `false` if already `N` commands are
+    /// registered.
+    pub fn register(
+        &mut self,
+        name: &'a str,
+        args: usize,
+        handler: &'a mut dyn FnMut(&[i32]),
+    ) -> bool {
+        debug_assert!(args <= MAX_ARGS);
+        self.commands
+            .push(Command {
+                name,
+                args,
+                handler,
+            })
+            .is_ok()
+    }
+
+    /// Parse the given `line` and call the handler of the corresponding command. Empty lines are
+    /// ignored.
+    pub fn execute<'l>(&mut self, line: &'l str) -> Result<(), CommandError<'l>> {
+        let mut words = line.split_ascii_whitespace();
+        let name = match words.next() {
+            Some(name) => name,
+            None => return Ok(()),
+        };
+
+        let command = self
+            .commands
+            .iter_mut()
+            .find(|command| command.name == name)
+            .ok_or(CommandError::UnknownCommand(name))?;
+
+        let mut args = [0; MAX_ARGS];
+        let mut given = 0;
+        for word in words {
+            if given < MAX_ARGS {
+                args[given] = word
+                    .parse()
+                    .map_err(|_| CommandError::InvalidArgument(word))?;
+            }
+            given += 1;
+        }
+
+        if given != command.args {
+            return Err(CommandError::WrongArgumentCount {
+                expected: command.args,
+                given,
+            });
+        }
+
+        (command.handler)(&args[..given]);
+        Ok(())
+    }
+
+    /// Read a single line from the `Serial` connection and execute it. Errors are replied as a
+    /// line starting with `"ERR "`.
+    pub fn process_line(&mut self) {
+        let mut buffer = [0; MAX_LINE_LENGTH];
+        let line = Serial::read_line(&mut buffer);
+        if let Err(error) = self.execute(line) {
+            println!("ERR ", error);
+        }
+    }
+
+    /// Process received lines forever.
+    pub fn run(&mut self) -> ! {
+        loop {
+            self.process_line();
+        }
+    }
+}
+
+/// Run an interactive shell on the `Serial` connection: write `prompt`, read a line with echo and
+/// backspace handling, see `Serial::read_line_interactive`, and pass it to `handler`, forever.
+/// Lines longer than `MAX_LINE_LENGTH` are truncated.
+///
+/// Example:
+/// ```rust
+/// let mut cli = CommandInterpreter::<4>::new();
+/// // ... register commands
+/// repl("rp6> ", |line| {
+///     if let Err(error) = cli.execute(line) {
+///         println!("ERR ", error);
+///     }
+/// });
+/// ```
+pub fn repl(prompt: &str, mut handler: impl FnMut(&str)) -> ! {
+    let mut buffer = [0; MAX_LINE_LENGTH];
+    loop {
+        Serial::write(prompt);
+        handler(Serial::read_line_interactive(&mut buffer));
+    }
+}
+
+impl<const N: usize> Default for CommandInterpreter<'_, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,6 @@
+//! Fixed-capacity collections that do not require an allocator, e.g., to buffer data between
+//! interrupt handlers and the main program.
+
+/// Module implementing a ringbuffer of arbitrary elements.
+mod ring_buffer;
+pub use ring_buffer::{Iter, RingBuffer};
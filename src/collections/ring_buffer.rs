@@ -0,0 +1,284 @@
+use core::mem::MaybeUninit;
+
+/// Ringbuffer storing up to `N - 1` elements of type `T`, e.g., bytes received via the `Serial`
+/// connection until they are read, encoder events or a command history. The buffer consists of
+/// the data array as well as `head` (next write position) and `tail` (next read position)
+/// indices. One slot always stays unused to distinguish between a full and an empty buffer.
+///
+/// The buffer can be used standalone, or shared with interrupt handlers by wrapping it in a
+/// `DynamicMutex`:
+/// ```rust
+/// static EVENTS: DynamicMutex<RingBuffer<u16, 16>> = DynamicMutex::new(RingBuffer::new());
+///
+/// interrupt::without_interrupts(|cs| EVENTS.lock(cs).borrow_mut().push(event));
+/// ```
+pub struct RingBuffer<T: Copy, const N: usize> {
+    data: [MaybeUninit<T>; N],
+    head: usize,
+    tail: usize,
+}
+
+impl<T: Copy, const N: usize> RingBuffer<T, N> {
+    /// Create a new, empty `RingBuffer`. A size `N` below 2 is rejected at compile time, as the
+    /// buffer could not store any element.
+    pub const fn new() -> Self {
+        const { assert!(N >= 2, "a RingBuffer needs at least 2 slots") };
+        Self {
+            data: [const { MaybeUninit::uninit() }; N],
+            head: 0,
+            tail: 0,
+        }
+    }
+
+    /// Maximum number of elements that can be stored in the buffer, i.e., `N - 1`.
+    pub const fn capacity(&self) -> usize {
+        N - 1
+    }
+
+    /// Number of elements currently stored in the buffer. If `head` has wrapped around to the
+    /// beginning of the data array while `tail` has not, the elements from `tail` to the end of
+    /// the array and from its beginning up to `head` are counted.
+    pub fn len(&self) -> usize {
+        if self.head >= self.tail {
+            self.head - self.tail
+        } else {
+            N - self.tail + self.head
+        }
+    }
+
+    /// Check if the buffer is empty.
+    pub fn is_empty(&self) -> bool {
+        self.head == self.tail
+    }
+
+    /// Check if the buffer is full.
+    pub fn is_full(&self) -> bool {
+        Self::next(self.head) == self.tail
+    }
+
+    /// Append an element to the buffer. Returns `false` if the buffer is full, i.e., the element is
+    /// lost.
+    pub fn push(&mut self, element: T) -> bool {
+        if self.is_full() {
+            return false;
+        }
+        self.data[self.head] = MaybeUninit::new(element);
+        self.head = Self::next(self.head);
+        true
+    }
+
+    /// Remove the oldest element from the buffer and return it.
+    pub fn pop(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+        // SAFETY: all elements between `tail` and `head` have been initialized by `push`
+        let element = unsafe { self.data[self.tail].assume_init() };
+        self.tail = Self::next(self.tail);
+        Some(element)
+    }
+
+    /// Discard all elements stored in the buffer.
+    pub fn clear(&mut self) {
+        self.head = 0;
+        self.tail = 0;
+    }
+
+    /// Iterate over the stored elements, oldest first, without removing them.
+    pub fn iter(&self) -> Iter<'_, T, N> {
+        Iter {
+            buffer: self,
+            index: self.tail,
+        }
+    }
+
+    /// Rearrange the stored elements to be contiguous in memory, starting at the beginning of the
+    /// data array, and return them as a slice in the order they were pushed.
+    pub fn make_contiguous(&mut self) -> &[T] {
+        let len = self.len();
+        if self.tail != 0 {
+            self.data.rotate_left(self.tail);
+            self.tail = 0;
+            self.head = len;
+        }
+        // SAFETY: the first `len` elements have been initialized by `push`, and `MaybeUninit<T>`
+        // has the same layout as `T`
+        unsafe { core::slice::from_raw_parts(self.data.as_ptr() as *const T, len) }
+    }
+
+    /// Compute the index following `index`, wrapping around at the end of the buffer.
+    #[inline(always)]
+    fn next(index: usize) -> usize {
+        if index + 1 < N {
+            index + 1
+        } else {
+            0
+        }
+    }
+}
+
+impl<T: Copy, const N: usize> Default for RingBuffer<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Iterator over the elements of a `RingBuffer`, oldest first, see `RingBuffer::iter`.
+pub struct Iter<'a, T: Copy, const N: usize> {
+    buffer: &'a RingBuffer<T, N>,
+    index: usize,
+}
+
+impl<T: Copy, const N: usize> Iterator for Iter<'_, T, N> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.index == self.buffer.head {
+            return None;
+        }
+        // SAFETY: all elements between `tail` and `head` have been initialized by `push`
+        let element = unsafe { self.buffer.data[self.index].assume_init() };
+        self.index = RingBuffer::<T, N>::next(self.index);
+        Some(element)
+    }
+}
+
+impl<'a, T: Copy, const N: usize> IntoIterator for &'a RingBuffer<T, N> {
+    type Item = T;
+    type IntoIter = Iter<'a, T, N>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RingBuffer;
+
+    #[test]
+    fn new_buffer_is_empty() {
+        let mut buffer = RingBuffer::<u8, 4>::new();
+        assert!(buffer.is_empty());
+        assert!(!buffer.is_full());
+        assert_eq!(buffer.len(), 0);
+        assert_eq!(buffer.capacity(), 3);
+        assert_eq!(buffer.pop(), None);
+        assert_eq!(buffer.iter().next(), None);
+    }
+
+    #[test]
+    fn keeps_the_order_of_elements() {
+        let mut buffer = RingBuffer::<u8, 4>::new();
+        assert!(buffer.push(1));
+        assert!(buffer.push(2));
+        assert_eq!(buffer.len(), 2);
+        assert_eq!(buffer.pop(), Some(1));
+        assert_eq!(buffer.pop(), Some(2));
+        assert_eq!(buffer.pop(), None);
+    }
+
+    #[test]
+    fn rejects_elements_when_full() {
+        let mut buffer = RingBuffer::<u8, 4>::new();
+        for element in 1..=3 {
+            assert!(buffer.push(element));
+        }
+        assert!(buffer.is_full());
+        assert!(!buffer.push(4));
+        assert_eq!(buffer.len(), 3);
+        assert_eq!(buffer.iter().collect::<Vec<_>>(), [1, 2, 3]);
+    }
+
+    #[test]
+    fn smallest_buffer_stores_a_single_element() {
+        let mut buffer = RingBuffer::<u8, 2>::new();
+        assert!(buffer.push(1));
+        assert!(buffer.is_full());
+        assert!(!buffer.push(2));
+        assert_eq!(buffer.pop(), Some(1));
+        assert!(buffer.push(3));
+        assert_eq!(buffer.pop(), Some(3));
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn wraps_around_the_end_of_the_array() {
+        let mut buffer = RingBuffer::<u16, 4>::new();
+        for round in 0..10 {
+            assert!(buffer.push(2 * round));
+            assert!(buffer.push(2 * round + 1));
+            assert_eq!(buffer.len(), 2);
+            assert_eq!(buffer.pop(), Some(2 * round));
+            assert_eq!(buffer.pop(), Some(2 * round + 1));
+            assert!(buffer.is_empty());
+        }
+    }
+
+    #[test]
+    fn len_counts_wrapped_elements() {
+        let mut buffer = RingBuffer::<u8, 4>::new();
+        buffer.push(1);
+        buffer.push(2);
+        buffer.push(3);
+        buffer.pop();
+        buffer.pop();
+        // `head` wraps around to the beginning of the array, while `tail` stays at its end
+        buffer.push(4);
+        buffer.push(5);
+        assert_eq!(buffer.len(), 3);
+        assert!(buffer.is_full());
+        assert_eq!(buffer.iter().collect::<Vec<_>>(), [3, 4, 5]);
+    }
+
+    #[test]
+    fn clear_discards_all_elements() {
+        let mut buffer = RingBuffer::<u8, 4>::new();
+        buffer.push(1);
+        buffer.push(2);
+        buffer.clear();
+        assert!(buffer.is_empty());
+        assert_eq!(buffer.pop(), None);
+        assert!(buffer.push(3));
+        assert_eq!(buffer.pop(), Some(3));
+    }
+
+    #[test]
+    fn make_contiguous_keeps_the_order_of_wrapped_elements() {
+        let mut buffer = RingBuffer::<u8, 5>::new();
+        for element in 1..=4 {
+            buffer.push(element);
+        }
+        buffer.pop();
+        buffer.pop();
+        buffer.push(5);
+        buffer.push(6);
+        assert_eq!(buffer.make_contiguous(), [3, 4, 5, 6]);
+        // the buffer stays usable afterwards
+        assert_eq!(buffer.pop(), Some(3));
+        assert!(buffer.push(7));
+        assert_eq!(buffer.iter().collect::<Vec<_>>(), [4, 5, 6, 7]);
+    }
+
+    #[test]
+    fn make_contiguous_of_empty_buffer() {
+        let mut buffer = RingBuffer::<u8, 4>::new();
+        buffer.push(1);
+        buffer.pop();
+        assert!(buffer.make_contiguous().is_empty());
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn iter_does_not_remove_elements() {
+        let mut buffer = RingBuffer::<u8, 4>::new();
+        buffer.push(1);
+        buffer.push(2);
+        let mut sum = 0;
+        for element in &buffer {
+            sum += element;
+        }
+        assert_eq!(sum, 3);
+        assert_eq!(buffer.len(), 2);
+    }
+}
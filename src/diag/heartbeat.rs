@@ -0,0 +1,93 @@
+use crate::{
+    interrupt::{self, mutex::Mutex},
+    port::*,
+    time, Pin,
+};
+
+/// State of the heartbeat LED, see `start_heartbeat`.
+#[derive(Clone, Copy)]
+struct Heartbeat {
+    /// Turns the heartbeat LED on or off.
+    set_led: fn(bool),
+    /// Whether the LED is currently on.
+    on: bool,
+    /// Time in ms between two toggles, i.e., half the blink period.
+    toggle_ms: u16,
+    /// Time (see `time::millis`) at which the LED is toggled next.
+    next_ms: u32,
+}
+
+/// The running heartbeat, if any.
+static HEARTBEAT: Mutex<Option<Heartbeat>> = Mutex::new(None);
+
+/// Turn the LED pin `P` on or off. Only the pin's bits in the `DDR` and `PORT` registers are
+/// modified, each by a single instruction, so that other users of the port are not affected.
+fn set_led<P: Pin>(on: bool) {
+    P::set_output();
+    if on {
+        P::set_high();
+    } else {
+        P::set_low();
+    }
+}
+
+/// Blink the LED with the given `index` (0 for SL1 to 5 for SL6, others are ignored) from the
+/// `TIMER0_COMP` interrupt of the system tick, with a period of `period_ms` ms, i.e., the LED is
+/// on for half of the period. This replaces a running heartbeat, whose LED is left as is.
+///
+/// As the LED is blinked by an interrupt independently of the main loop, it indicates that the
+/// firmware is alive: if the main loop hangs in a logic stall, the LED keeps blinking, whereas it
+/// stops if the CPU locks up, e.g., within a `CriticalSection` that is never left. Requires the
+/// system tick to be started with `time::init_tick`.
+///
+/// The interrupt only modifies the LED's bit of its port. However, other code writing the LEDs,
+/// e.g., `RobotBase::set_leds`, may override the heartbeat LED, so it should be left alone while
+/// the heartbeat is running.
+pub fn start_heartbeat(led_index: u8, period_ms: u16) {
+    let set_led: fn(bool) = match led_index {
+        0 => set_led::<Led1>,
+        1 => set_led::<Led2>,
+        2 => set_led::<Led3>,
+        3 => set_led::<Led4>,
+        4 => set_led::<Led5>,
+        5 => set_led::<Led6>,
+        _ => return,
+    };
+    let toggle_ms = (period_ms / 2).max(1);
+
+    interrupt::without_interrupts(|cs| {
+        set_led(true);
+        HEARTBEAT.lock(cs).set(Some(Heartbeat {
+            set_led,
+            on: true,
+            toggle_ms,
+            next_ms: time::millis().wrapping_add(u32::from(toggle_ms)),
+        }));
+    });
+}
+
+/// Stop the heartbeat started by `start_heartbeat`, if any, and turn its LED off.
+pub fn stop_heartbeat() {
+    interrupt::without_interrupts(|cs| {
+        if let Some(heartbeat) = HEARTBEAT.lock(cs).take() {
+            (heartbeat.set_led)(false);
+        }
+    });
+}
+
+/// Toggle the heartbeat LED if it is due, called on every system tick by the `TIMER0_COMP`
+/// interrupt.
+pub(crate) fn on_tick() {
+    interrupt::without_interrupts(|cs| {
+        let heartbeat = HEARTBEAT.lock(cs);
+        if let Some(mut state) = heartbeat.get() {
+            let now_ms = time::millis();
+            if now_ms.wrapping_sub(state.next_ms) as i32 >= 0 {
+                state.on = !state.on;
+                (state.set_led)(state.on);
+                state.next_ms = now_ms.wrapping_add(u32::from(state.toggle_ms));
+                heartbeat.set(Some(state));
+            }
+        }
+    });
+}
@@ -0,0 +1,90 @@
+use crate::{
+    avr::{
+        bitmasks::{CS10, OCF1A, OCIE1A},
+        registers::{OCR1AH, OCR1AL, TCCR1A, TCCR1B, TCNT1H, TCNT1L, TIFR, TIMSK},
+    },
+    interrupt::{self, mutex::Mutex},
+    print, Register, Serial,
+};
+use avr_macros::interrupt;
+
+/// Counter value of Timer1 at which the compare match interrupt is triggered.
+const TRIGGER_AT: u16 = 256;
+
+/// Counter value of Timer1 captured at the beginning of the `TIMER1_COMPA` interrupt handler.
+static CAPTURED: Mutex<Option<u16>> = Mutex::new(None);
+
+/// Reads the counter of Timer1. The low byte must be read first, which latches the high byte.
+#[inline(always)]
+fn read_counter() -> u16 {
+    let low = TCNT1L::read();
+    let high = TCNT1H::read();
+    u16::from_le_bytes([low, high])
+}
+
+#[interrupt]
+fn TIMER1_COMPA() {
+    // capture the counter first to measure the latency until the handler body executes
+    let counter = read_counter();
+    interrupt::without_interrupts(|cs| CAPTURED.lock(cs).set(Some(counter)));
+}
+
+/// Measures the number of CPU cycles between triggering an interrupt and the execution of the
+/// first statement of its handler, and reports it over the `Serial` connection. This includes
+/// finishing the current instruction, jumping to the interrupt vector and the handler's prologue
+/// saving registers. Returns `None` if the interrupt did not fire, e.g., because global
+/// interrupts are disabled when called inside `without_interrupts`.
+///
+/// The measurement runs Timer1 without prescaler and triggers its `TIMER1_COMPA` interrupt at a
+/// known counter value. Timer1 is temporarily reconfigured for this purpose and restored
+/// afterwards. As Timer1 generates the motors' PWM signals, the motors should be stopped while
+/// measuring.
+pub fn measure_isr_latency() -> Option<u16> {
+    // save the configuration of Timer1
+    let (control_a, control_b, mask) = (TCCR1A::read(), TCCR1B::read(), TIMSK::read());
+    let (compare_low, compare_high) = (OCR1AL::read(), OCR1AH::read());
+
+    interrupt::without_interrupts(|cs| {
+        CAPTURED.lock(cs).set(None);
+
+        // stop Timer1 and set up a compare match at `TRIGGER_AT` in normal mode
+        TCCR1B::write(0);
+        TCCR1A::write(0);
+        OCR1AH::write((TRIGGER_AT >> 8) as u8);
+        OCR1AL::write(TRIGGER_AT as u8);
+        TCNT1H::write(0);
+        TCNT1L::write(0);
+
+        // clear a stale compare match (by writing a logical one) and enable the interrupt
+        TIFR::write(OCF1A);
+        TIMSK::set_mask_raw(OCIE1A);
+
+        // start Timer1 without prescaler, i.e., counting CPU cycles
+        TCCR1B::write(CS10);
+    });
+
+    // wait for the interrupt, but give up once the counter has passed the trigger by far
+    let mut captured = None;
+    while captured.is_none() && read_counter() < 2 * TRIGGER_AT {
+        captured = interrupt::without_interrupts(|cs| CAPTURED.lock(cs).get());
+    }
+
+    // restore the configuration of Timer1
+    TCCR1B::write(0);
+    TIMSK::write(mask);
+    OCR1AH::write(compare_high);
+    OCR1AL::write(compare_low);
+    TCCR1A::write(control_a);
+    TCCR1B::write(control_b);
+
+    let latency = captured.map(|counter| counter - TRIGGER_AT);
+    match latency {
+        Some(cycles) => {
+            print!("ISR latency: ", cycles, " cycles\n");
+        }
+        None => {
+            print!("ISR latency: interrupt did not fire\n");
+        }
+    }
+    latency
+}
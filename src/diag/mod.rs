@@ -0,0 +1,20 @@
+//! Diagnostic utilities that help to analyze the runtime behavior of the robot's firmware. Only
+//! available with the feature `diag`, as these utilities increase code size and may claim
+//! interrupts for themselves.
+
+/// Module blinking an LED from the system tick as a sign of life.
+mod heartbeat;
+pub(crate) use heartbeat::on_tick;
+pub use heartbeat::{start_heartbeat, stop_heartbeat};
+
+/// Module measuring the latency of interrupt service routines.
+mod latency;
+pub use latency::measure_isr_latency;
+
+/// Module dumping the values of the device's registers.
+mod registers;
+pub use registers::dump_registers;
+
+/// Module collecting a snapshot of the state of the robot's peripherals.
+mod status;
+pub use status::{status, SystemStatus};
@@ -0,0 +1,54 @@
+use crate::{avr::registers::*, Register, Serial};
+
+/// Dumps each of the given registers via `dump_register`, using its identifier as name.
+macro_rules! dump {
+    ($($reg_name: ident),* $(,)?) => {
+        $(dump_register::<$reg_name>(stringify!($reg_name));)*
+    };
+}
+
+/// Writes the current value of register `R` as `NAME = 0xHH` line to the `Serial` connection.
+fn dump_register<R: Register<T = u8>>(name: &str) {
+    let value = R::read();
+    Serial::write(name);
+    Serial::write(" = 0x");
+    if value < 0x10 {
+        Serial::write('0');
+    }
+    Serial::write_hex(value);
+    Serial::new_line();
+}
+
+/// Prints the current value of every I/O register of the atmega32 as `NAME = 0xHH` line over the
+/// `Serial` connection, e.g., for post-mortem debugging of a peripheral's configuration.
+///
+/// Registers whose read access has side effects are skipped: `UDR` (pops a received byte),
+/// `SPDR` (clears `SPIF` in `SPSR`) and `UCSRC` (shares its address with `UBRRH` and needs a
+/// timed double read). The 16-bit registers of Timer1 are dumped low byte first, so that the
+/// high byte is latched correctly.
+pub fn dump_registers() {
+    dump! {
+        // analog comparator and ADC
+        ACSR, ADCL, ADCH, ADCSRA, ADMUX,
+        // ports
+        DDRA, PORTA, PINA, DDRB, PORTB, PINB, DDRC, PORTC, PINC, DDRD, PORTD, PIND,
+        // EEPROM
+        EEARL, EEARH, EECR, EEDR,
+        // external interrupts, MCU control and stack pointer
+        GICR, GIFR, MCUCR, MCUCSR, OCDR, OSCCAL, SFIOR, SPL, SPH, SPMCR, WDTCR,
+        // SPI
+        SPCR, SPSR,
+        // Timer0
+        TCCR0, TCNT0, OCR0,
+        // Timer1
+        TCCR1A, TCCR1B, TCNT1L, TCNT1H, OCR1AL, OCR1AH, OCR1BL, OCR1BH, ICR1L, ICR1H,
+        // Timer2
+        TCCR2, TCNT2, OCR2, ASSR,
+        // shared timer interrupt registers
+        TIFR, TIMSK,
+        // TWI
+        TWAR, TWBR, TWCR, TWDR, TWSR,
+        // USART
+        UBRRL, UBRRH, UCSRA, UCSRB,
+    }
+}
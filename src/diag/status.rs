@@ -0,0 +1,111 @@
+#[cfg(feature = "encoders")]
+use crate::robot_base::Encoders;
+use crate::{
+    avr::reset::ResetCause, robot_base::acs::AcsPower, time, RobotBase, Serial, SerialWritable,
+    RX_BUFFER_SIZE, TX_BUFFER_SIZE,
+};
+
+/// Snapshot of the state of the robot's peripherals, see `status`. Subsystems that are not in use
+/// are reported as `None`, so that the snapshot works with any combination of them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SystemStatus {
+    /// Cause of the last reset, see `RobotBase::reset_cause`.
+    pub reset_cause: ResetCause,
+    /// Time since the system tick was started, see `time::millis`, or `None` if it is not running.
+    pub uptime_ms: Option<u32>,
+    /// Raw ADC reading of the battery voltage, see `RobotBase::read_ubat`.
+    pub ubat: u16,
+    /// Ticks of the left and right encoder, or `None` if `Encoders::init` was not called or the
+    /// feature `encoders` is disabled.
+    pub encoder_counts: Option<(u32, u32)>,
+    /// Power level of the ACS.
+    pub acs_power: AcsPower,
+    /// Number of received bytes waiting to be read, see `Serial::available`.
+    pub rx_pending: usize,
+    /// Number of bytes waiting to be sent, see `Serial::tx_pending`.
+    pub tx_pending: usize,
+}
+
+/// Collect a snapshot of the state of the robot's peripherals, e.g., to print a health dashboard
+/// over the `Serial` connection via `println!(diag::status())`. Blocks for a few ADC conversions
+/// of the battery voltage, i.e., for about 0.5ms. Note that the values are read one after
+/// another, i.e., they are not captured at exactly the same time.
+pub fn status() -> SystemStatus {
+    SystemStatus {
+        reset_cause: RobotBase::reset_cause(),
+        uptime_ms: time::tick_period_us().map(|_| time::millis()),
+        ubat: RobotBase::read_ubat(),
+        #[cfg(feature = "encoders")]
+        encoder_counts: Encoders::is_enabled().then(Encoders::counts),
+        #[cfg(not(feature = "encoders"))]
+        encoder_counts: None,
+        acs_power: RobotBase::acs_power(),
+        rx_pending: Serial::available(),
+        tx_pending: Serial::tx_pending(),
+    }
+}
+
+impl SerialWritable for SystemStatus {
+    /// Write the snapshot with one labeled line per value, e.g.,
+    /// ```text
+    /// reset cause: power-on
+    /// uptime:      12345ms
+    /// battery:     712 (raw UBAT)
+    /// encoders:    1234 / 1201
+    /// ACS power:   low
+    /// UART RX:     0 / 31 bytes
+    /// UART TX:     5 / 63 bytes
+    /// ```
+    fn write_to_serial(&self) {
+        Serial::write("reset cause: ");
+        Serial::write(match self.reset_cause {
+            ResetCause::PowerOn => "power-on",
+            ResetCause::External => "external",
+            ResetCause::BrownOut => "brown-out",
+            ResetCause::Watchdog => "watchdog",
+            ResetCause::Jtag => "JTAG",
+            ResetCause::Unknown => "unknown",
+        });
+
+        Serial::write("\nuptime:      ");
+        match self.uptime_ms {
+            Some(uptime_ms) => {
+                Serial::write(uptime_ms);
+                Serial::write("ms");
+            }
+            None => Serial::write("tick not running"),
+        }
+
+        Serial::write("\nbattery:     ");
+        Serial::write(self.ubat);
+        Serial::write(" (raw UBAT)");
+
+        Serial::write("\nencoders:    ");
+        match self.encoder_counts {
+            Some((left, right)) => {
+                Serial::write(left);
+                Serial::write(" / ");
+                Serial::write(right);
+            }
+            None => Serial::write("disabled"),
+        }
+
+        Serial::write("\nACS power:   ");
+        Serial::write(match self.acs_power {
+            AcsPower::Off => "off",
+            AcsPower::Low => "low",
+            AcsPower::Medium => "medium",
+            AcsPower::High => "high",
+        });
+
+        Serial::write("\nUART RX:     ");
+        Serial::write(self.rx_pending);
+        Serial::write(" / ");
+        Serial::write(RX_BUFFER_SIZE - 1);
+        Serial::write(" bytes\nUART TX:     ");
+        Serial::write(self.tx_pending);
+        Serial::write(" / ");
+        Serial::write(TX_BUFFER_SIZE - 1);
+        Serial::write(" bytes");
+    }
+}
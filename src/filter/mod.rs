@@ -0,0 +1,179 @@
+//! Integer-only filters smoothing streams of sensor samples, e.g., of the battery voltage, the
+//! light sensors or the motor currents read via the ADC.
+
+/// Moving average over the last `N` samples, stored in a fixed ring. The sum of the samples is
+/// kept as `u32`, so that it cannot overflow for any `N` up to `u16::MAX` (65535) of `u16`
+/// samples, and in particular not for 10-bit ADC results.
+///
+/// Example:
+/// ```rust
+/// let mut battery = MovingAverage::<8>::new();
+/// loop {
+///     let smoothed = battery.push(Adc::read(7));
+///     // ...
+/// }
+/// ```
+pub struct MovingAverage<const N: usize> {
+    /// The last `N` samples, where `next` indexes the oldest one.
+    samples: [u16; N],
+    /// Index of the slot that is overwritten by the next sample.
+    next: usize,
+    /// Number of samples pushed so far, saturating at `N`.
+    len: usize,
+    /// Sum of the stored samples.
+    sum: u32,
+}
+
+impl<const N: usize> MovingAverage<N> {
+    /// Create a new `MovingAverage` without any samples.
+    pub const fn new() -> Self {
+        const {
+            assert!(
+                N > 0 && N <= u16::MAX as usize,
+                "the window must hold 1 to 65535 samples"
+            )
+        };
+        Self {
+            samples: [0; N],
+            next: 0,
+            len: 0,
+            sum: 0,
+        }
+    }
+
+    /// Add a `sample`, replacing the oldest one once `N` samples have been pushed, and return the
+    /// new average. Until `N` samples have been pushed, the average of the pushed samples is
+    /// returned, i.e., the filter does not ramp up from zero. The average is rounded down.
+    pub fn push(&mut self, sample: u16) -> u16 {
+        if self.len == N {
+            self.sum -= u32::from(self.samples[self.next]);
+        } else {
+            self.len += 1;
+        }
+        self.samples[self.next] = sample;
+        self.sum += u32::from(sample);
+        self.next = (self.next + 1) % N;
+        self.average()
+    }
+
+    /// The average of the stored samples, or 0 if no sample has been pushed yet.
+    pub fn average(&self) -> u16 {
+        match self.len {
+            0 => 0,
+            len => (self.sum / len as u32) as u16,
+        }
+    }
+
+    /// Remove all samples.
+    pub fn clear(&mut self) {
+        self.next = 0;
+        self.len = 0;
+        self.sum = 0;
+    }
+}
+
+impl<const N: usize> Default for MovingAverage<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Exponential moving average `y += alpha * (x - y)` with `alpha = 1 / 2^shift`, which only needs
+/// a single `u32` of state instead of a window of samples. The larger `shift`, the smoother (and
+/// slower) the output, e.g., a `shift` of 3 (`alpha = 1/8`) roughly corresponds to a moving
+/// average over 15 samples.
+///
+/// The state is kept as fixed-point value with `shift` fractional bits, i.e., `y * 2^shift`, so
+/// that small changes of the input are not lost to rounding. As `shift` is at most 16, the state of
+/// `u16` samples fits into a `u32` without overflow.
+pub struct ExponentialFilter {
+    /// `log2(1 / alpha)`.
+    shift: u8,
+    /// The filtered value scaled by `2^shift`, `None` until the first sample is pushed.
+    state: Option<u32>,
+}
+
+impl ExponentialFilter {
+    /// Create a new `ExponentialFilter` with `alpha = 1 / 2^shift`, where `shift` is clamped to
+    /// 16.
+    pub const fn new(shift: u8) -> Self {
+        Self {
+            shift: if shift < 16 { shift } else { 16 },
+            state: None,
+        }
+    }
+
+    /// Add a `sample` and return the new filtered value. The first sample initializes the filter,
+    /// so that it does not ramp up from zero. The filtered value is rounded down.
+    pub fn push(&mut self, sample: u16) -> u16 {
+        let sample = u32::from(sample);
+        let state = match self.state {
+            // y * 2^shift += x - y, i.e., without ever subtracting more than the state holds
+            Some(state) => state - (state >> self.shift) + sample,
+            None => sample << self.shift,
+        };
+        self.state = Some(state);
+        self.value()
+    }
+
+    /// The filtered value, or 0 if no sample has been pushed yet.
+    pub fn value(&self) -> u16 {
+        self.state.map_or(0, |state| (state >> self.shift) as u16)
+    }
+
+    /// Forget all samples, so that the next sample initializes the filter again.
+    pub fn clear(&mut self) {
+        self.state = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MovingAverage;
+
+    #[test]
+    fn averages_the_last_samples() {
+        let mut filter = MovingAverage::<4>::new();
+        let averages = [10, 20, 30, 40, 50, 60, 70, 80].map(|sample| filter.push(sample));
+        assert_eq!(averages, [10, 15, 20, 25, 35, 45, 55, 65]);
+    }
+
+    #[test]
+    fn rounds_down() {
+        let mut filter = MovingAverage::<3>::new();
+        assert_eq!(filter.push(1), 1);
+        assert_eq!(filter.push(2), 1);
+        assert_eq!(filter.push(2), 1);
+        assert_eq!(filter.push(3), 2);
+    }
+
+    #[test]
+    fn single_sample_window() {
+        let mut filter = MovingAverage::<1>::new();
+        assert_eq!(filter.push(7), 7);
+        assert_eq!(filter.push(3), 3);
+    }
+
+    #[test]
+    fn empty_and_cleared_average_is_zero() {
+        let mut filter = MovingAverage::<4>::new();
+        assert_eq!(filter.average(), 0);
+        filter.push(100);
+        filter.push(200);
+        filter.clear();
+        assert_eq!(filter.average(), 0);
+        // the samples before `clear` no longer count
+        assert_eq!(filter.push(8), 8);
+        assert_eq!(filter.push(4), 6);
+    }
+
+    #[test]
+    fn sum_does_not_overflow_for_the_largest_window() {
+        let mut filter = MovingAverage::<{ u16::MAX as usize }>::new();
+        for _ in 0..u16::MAX {
+            filter.push(u16::MAX);
+        }
+        assert_eq!(filter.average(), u16::MAX);
+        assert_eq!(filter.push(u16::MAX), u16::MAX);
+    }
+}
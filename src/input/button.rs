@@ -0,0 +1,150 @@
+use crate::{time::millis, Pin};
+use core::marker::PhantomData;
+
+/// Default time in ms that the level of a `Button` must be stable to be accepted.
+pub const DEFAULT_DEBOUNCE_MS: u16 = 20;
+
+/// The level of the input pin while a `Button` is pressed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ActiveLevel {
+    /// The pin is high while the button is pressed, e.g., a button connecting the pin to VCC.
+    High,
+    /// The pin is low while the button is pressed, e.g., a button connecting the pin to GND in
+    /// combination with a pull-up resistor.
+    Low,
+}
+
+/// A debounced edge of a `Button`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ButtonEvent {
+    /// The button has been pressed.
+    Pressed,
+    /// The button has been released.
+    Released,
+}
+
+/// A button (or switch) on the input pin `P`, debounced using the system tick, see
+/// `crate::time::init_tick`. A change of the pin's level is only accepted once the level has been
+/// stable for the debounce time. Without a running system tick, `millis` does not advance, so that
+/// no change is ever accepted.
+///
+/// The button must be polled regularly via `update`, `was_pressed` or `is_held`, at least once per
+/// debounce time to detect short presses.
+///
+/// Example:
+/// ```rust
+/// let mut button: Button<port::a4> = Button::new(ActiveLevel::Low).with_pull_up(true);
+/// button.init();
+/// loop {
+///     if button.was_pressed() {
+///         println!("pressed");
+///     }
+/// }
+/// ```
+pub struct Button<P: Pin> {
+    /// The level of the pin while the button is pressed.
+    active: ActiveLevel,
+    /// Whether the internal pull-up resistor of the pin is enabled by `init`.
+    pull_up: bool,
+    /// Time in ms that a level must be stable to be accepted.
+    debounce_ms: u16,
+    /// The debounced state, `true` while pressed.
+    pressed: bool,
+    /// The raw state at the last sample, `true` while pressed.
+    raw: bool,
+    /// Value of `millis` when the raw state changed last.
+    raw_since_ms: u32,
+    /// Whether a press has been detected that was not yet returned by `was_pressed`.
+    press_latched: bool,
+    _pin: PhantomData<fn() -> P>,
+}
+
+impl<P: Pin> Button<P> {
+    /// Create a new button that is pressed while its pin has the `active` level, without pull-up
+    /// and with a debounce time of `DEFAULT_DEBOUNCE_MS`. Call `init` before polling it.
+    pub const fn new(active: ActiveLevel) -> Self {
+        Self {
+            active,
+            pull_up: false,
+            debounce_ms: DEFAULT_DEBOUNCE_MS,
+            pressed: false,
+            raw: false,
+            raw_since_ms: 0,
+            press_latched: false,
+            _pin: PhantomData,
+        }
+    }
+
+    /// Enable or disable the internal pull-up resistor of the pin, e.g., for active low buttons
+    /// without an external pull-up.
+    pub const fn with_pull_up(mut self, pull_up: bool) -> Self {
+        self.pull_up = pull_up;
+        self
+    }
+
+    /// Set the time in ms that a level must be stable to be accepted.
+    pub const fn with_debounce_ms(mut self, debounce_ms: u16) -> Self {
+        self.debounce_ms = debounce_ms;
+        self
+    }
+
+    /// Configure the pin as input (with the pull-up, if enabled) and sample its initial state.
+    ///
+    /// The initial state is accepted without debouncing and without an edge, i.e., a button that
+    /// is already held at power-on is reported by `is_held`, but not by `was_pressed` until it
+    /// has been released and pressed again.
+    pub fn init(&mut self) {
+        P::set_input();
+        if self.pull_up {
+            P::set_high();
+        } else {
+            P::set_low();
+        }
+        self.raw = self.sample();
+        self.raw_since_ms = millis();
+        self.pressed = self.raw;
+        self.press_latched = false;
+    }
+
+    /// Sample the pin and return the debounced edge, if the state of the button has changed.
+    pub fn update(&mut self) -> Option<ButtonEvent> {
+        let raw = self.sample();
+        let now = millis();
+        if raw != self.raw {
+            // the level changed (again), restart the debounce time
+            self.raw = raw;
+            self.raw_since_ms = now;
+            return None;
+        }
+        if raw == self.pressed || now.wrapping_sub(self.raw_since_ms) < self.debounce_ms.into() {
+            return None;
+        }
+
+        self.pressed = raw;
+        if raw {
+            self.press_latched = true;
+            Some(ButtonEvent::Pressed)
+        } else {
+            Some(ButtonEvent::Released)
+        }
+    }
+
+    /// Poll the button and return whether it has been pressed since the last call, i.e., each
+    /// press is reported exactly once.
+    pub fn was_pressed(&mut self) -> bool {
+        self.update();
+        core::mem::take(&mut self.press_latched)
+    }
+
+    /// Poll the button and return whether it is currently held, i.e., its debounced state.
+    pub fn is_held(&mut self) -> bool {
+        self.update();
+        self.pressed
+    }
+
+    /// Read the raw state of the pin, `true` if it has the active level.
+    #[inline(always)]
+    fn sample(&self) -> bool {
+        P::is_high() == (self.active == ActiveLevel::High)
+    }
+}
@@ -0,0 +1,6 @@
+//! Debounced buttons and switches on arbitrary input pins, e.g., the bumpers, the reset button or
+//! buttons of expansion modules.
+
+/// Module implementing a debounced button bound to an input pin.
+mod button;
+pub use button::{ActiveLevel, Button, ButtonEvent, DEFAULT_DEBOUNCE_MS};
@@ -1,23 +1,53 @@
+#![feature(abi_avr_interrupt)]
 #![feature(asm_experimental_arch)]
 #![feature(associated_type_defaults)]
 #![feature(cell_update)]
-#![no_std]
+#![cfg_attr(not(test), no_std)]
 //! Rust adaptation of the RP6Lib provided with the original Robby RP6 robot.
 //!
 //! Example usage (see [`examples/`](https://github.com/Pr0gm4n/rust-rp6lib/tree/main/examples) directory for more):
 //! ```no_run
 #![doc = include_str!("../examples/01_led_runner.rs")]
 //! ```
+//!
+//! # Interrupt vectors
+//!
+//! The crate defines the handlers of the following interrupts itself. Defining one of them again
+//! via `#[interrupt]` in an application fails to link with a duplicate symbol `__vector_N`:
+//! - `USART_RXC` with the default feature `serial-rx-interrupt`: stores the bytes received via
+//!   `Serial` in a ringbuffer, see `Serial::read`. Applications that define this handler
+//!   themselves have to disable the default features, and may call `Serial::on_receive` from it
+//!   to keep using the ringbuffer.
+//! - `USART_UDRE`: sends the bytes queued by `Serial::write_nonblocking`.
+//! - `USART_TXC`: invokes the hook set via `Serial::on_transmit_complete`.
+//! - `TIMER0_COMP`: the system tick of `rp6::time`.
+//! - `EE_RDY`: the EEPROM writes queued by `Eeprom::write_byte_async`.
+//! - `INT0` and `INT1` with the feature `encoders`: the wheel encoders, see `Encoders`.
+//! - `TIMER2_COMP` with the feature `timer2-one-shot`: the one-shots of `Timer2`, see
+//!   `Timer8::after`, which also drive the `Servo`s.
+//! - `TIMER2_OVF` with the feature `rtc`: the real-time clock of `rp6::rtc`.
+//! - `TIMER1_COMPA` with the feature `diag` and `TIMER1_OVF` with the feature `bench`.
 
 // Contains simple implementations of required language items that libstd normally defines on other
-// targets.
+// targets. Its panic handler is replaced by `panic` with the feature `panic-handler`.
+#[cfg(all(target_arch = "avr", not(feature = "panic-handler")))]
 extern crate avr_std_stub;
 
+// Panic handler stopping the motors, see the feature `panic-handler`.
+#[cfg(all(target_arch = "avr", feature = "panic-handler"))]
+mod panic;
+
 // reexporting delay routines for convenience
+#[cfg(target_arch = "avr")]
 extern crate avr_delay;
 /// Blocking procedure that allows to
+#[cfg(target_arch = "avr")]
 pub use avr_delay::{delay_ms, delay_us};
 
+// stand-ins returning immediately on other targets, e.g., for the unit tests on the host
+#[cfg(not(target_arch = "avr"))]
+pub use avr::mock::{delay_ms, delay_us};
+
 // Generic AVR API based on the ruduino project.
 pub mod avr;
 pub use avr::prelude::*;
@@ -27,10 +57,40 @@ pub use avr_macros::*;
 
 // RP6-specific API based on the RP6Lib.
 pub mod robot_base;
-pub use robot_base::{port, RobotBase};
+pub use robot_base::{port, Drive, InitError, Motors, PowerOnCheck, RobotBase};
+pub mod assertions;
+pub mod behavior;
+pub mod cli;
+pub mod collections;
+pub mod filter;
+pub mod input;
+pub mod log;
+pub mod math;
+pub mod odometry;
+pub mod one_wire;
+pub mod outputs;
+pub mod pinout;
+pub mod rand;
+pub mod sensors;
+pub mod storage;
+pub mod task;
+pub mod telemetry;
+pub mod time;
 pub mod uart;
 pub use uart::*;
 
+// Diagnostic utilities, see the feature `diag`.
+#[cfg(feature = "diag")]
+pub mod diag;
+
+// Real-time clock driven by a watch crystal, see the feature `rtc`.
+#[cfg(feature = "rtc")]
+pub mod rtc;
+
+// Benchmarking of code blocks, see the feature `bench`.
+#[cfg(feature = "bench")]
+pub mod bench;
+
 /// Re-exports commonly-used API that can be imported at once.
 pub mod prelude {
     pub use super::{delay_ms, delay_us, interrupt, port, RobotBase};
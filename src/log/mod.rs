@@ -0,0 +1,124 @@
+//! Leveled logging over the `Serial` connection.
+//!
+//! The macros `log_error!`, `log_warn!`, `log_info!` and `log_debug!` accept the same arguments
+//! as `println!` and prefix the message with a level tag, e.g., `[WARN] battery low`. With the
+//! feature `log-timestamp`, the tag is preceded by the milliseconds since the system tick was
+//! started, see `crate::time::millis`, e.g., `[1234ms][WARN] battery low`.
+//!
+//! Messages above `MAX_LEVEL` are compiled out, i.e., neither their arguments nor their
+//! formatting cost any bytes. By default, `MAX_LEVEL` is `Level::Debug` for builds with debug
+//! assertions and `Level::Info` otherwise. It can be lowered with one of the features
+//! `log-max-level-off`, `log-max-level-error`, `log-max-level-warn`, `log-max-level-info` or
+//! `log-max-level-debug`, where the most restrictive enabled feature takes precedence.
+
+use crate::Serial;
+
+/// The severity of a log message, ordered from most to least severe.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[repr(u8)]
+pub enum Level {
+    /// An error that the firmware cannot recover from by itself.
+    Error = 1,
+    /// An unexpected condition that the firmware can handle.
+    Warn = 2,
+    /// Information on the normal operation, e.g., state changes.
+    Info = 3,
+    /// Detailed information for debugging.
+    Debug = 4,
+}
+
+impl Level {
+    /// The tag written in front of messages of this level.
+    pub const fn tag(self) -> &'static str {
+        match self {
+            Level::Error => "[ERROR] ",
+            Level::Warn => "[WARN] ",
+            Level::Info => "[INFO] ",
+            Level::Debug => "[DEBUG] ",
+        }
+    }
+}
+
+/// The least severe level that is logged, `None` if logging is disabled completely. Selected at
+/// compile time, see the module documentation.
+pub const MAX_LEVEL: Option<Level> = if cfg!(feature = "log-max-level-off") {
+    None
+} else if cfg!(feature = "log-max-level-error") {
+    Some(Level::Error)
+} else if cfg!(feature = "log-max-level-warn") {
+    Some(Level::Warn)
+} else if cfg!(feature = "log-max-level-info") {
+    Some(Level::Info)
+} else if cfg!(any(feature = "log-max-level-debug", debug_assertions)) {
+    Some(Level::Debug)
+} else {
+    Some(Level::Info)
+};
+
+/// Whether messages of the given `level` are logged. Evaluated at compile time by the logging
+/// macros, so that disabled messages are removed entirely.
+pub const fn is_enabled(level: Level) -> bool {
+    match MAX_LEVEL {
+        Some(max_level) => level as u8 <= max_level as u8,
+        None => false,
+    }
+}
+
+/// Write the prefix of a log message of the given `level`. Called by the logging macros.
+#[doc(hidden)]
+#[inline(never)]
+pub fn write_prefix(level: Level) {
+    #[cfg(feature = "log-timestamp")]
+    crate::print!("[", crate::time::millis(), "ms]");
+    Serial::write(level.tag());
+}
+
+/// Log a message with the given `Level` if it is enabled by `MAX_LEVEL`. The message accepts the
+/// same arguments as `println!`.
+#[macro_export]
+macro_rules! log {
+    ($level: expr, $($writable: expr $(=> $format: tt)?),* $(,)?) => {
+        if const { $crate::log::is_enabled($level) } {
+            $crate::log::write_prefix($level);
+            $crate::println!($($writable $(=> $format)?, )*);
+        }
+    };
+}
+
+/// Log an error, see `log!`.
+///
+/// Example:
+/// ```rust
+/// log_error!("TWI bus error: ", status => HEXP);
+/// ```
+#[macro_export]
+macro_rules! log_error {
+    ($($arguments: tt)*) => {
+        $crate::log!($crate::log::Level::Error, $($arguments)*)
+    };
+}
+
+/// Log a warning, see `log!`.
+#[macro_export]
+macro_rules! log_warn {
+    ($($arguments: tt)*) => {
+        $crate::log!($crate::log::Level::Warn, $($arguments)*)
+    };
+}
+
+/// Log an information on the normal operation, see `log!`.
+#[macro_export]
+macro_rules! log_info {
+    ($($arguments: tt)*) => {
+        $crate::log!($crate::log::Level::Info, $($arguments)*)
+    };
+}
+
+/// Log a debugging message, see `log!`. Compiled out in builds without debug assertions by
+/// default.
+#[macro_export]
+macro_rules! log_debug {
+    ($($arguments: tt)*) => {
+        $crate::log!($crate::log::Level::Debug, $($arguments)*)
+    };
+}
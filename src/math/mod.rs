@@ -0,0 +1,4 @@
+//! Integer math, e.g., for the odometry, that avoids the costly software emulation of floats on
+//! the AVR.
+
+pub mod trig;
@@ -0,0 +1,102 @@
+//! Sine and cosine of angles in degrees as Q15 fixed-point numbers, i.e., as `i16` scaled by
+//! `2^15`, so that `Q15_ONE` represents (almost) 1.0.
+//!
+//! The values are linearly interpolated from a table of the sine of the first quadrant in steps of
+//! 5°, which takes 38 bytes of flash. The error versus the true sine is at most 31 LSB, i.e., below
+//! 0.001. It is largest between the entries close to 90°, i.e., at about 82° to 88°, as the
+//! curvature of the sine grows with its magnitude, while it vanishes towards 0°. Multiples of 5°
+//! are exact up to rounding to 1 LSB.
+//!
+//! Example rotating a distance by the robot's heading:
+//! ```rust
+//! let dx_um = (i32::from(distance_um) * i32::from(cos_q15(heading_deg))) >> 15;
+//! let dy_um = (i32::from(distance_um) * i32::from(sin_q15(heading_deg))) >> 15;
+//! ```
+
+/// Largest Q15 value, representing 1.0 minus 1 LSB, as 1.0 itself does not fit into an `i16`.
+pub const Q15_ONE: i16 = i16::MAX;
+
+/// Distance in degrees between two entries of `SINE_TABLE`.
+const STEP_DEG: u16 = 5;
+
+/// `round(Q15_ONE * sin(index * STEP_DEG))` for the first quadrant, i.e., from 0° to 90°.
+const SINE_TABLE: [i16; 19] = [
+    0, 2856, 5690, 8481, 11207, 13848, 16383, 18794, 21062, 23170, 25101, 26841, 28377, 29697,
+    30791, 31650, 32269, 32642, 32767,
+];
+
+const _: () = assert!(SINE_TABLE.len() == (90 / STEP_DEG) as usize + 1);
+const _: () = assert!(SINE_TABLE[SINE_TABLE.len() - 1] == Q15_ONE);
+
+/// Sine of `angle_deg` in degrees as Q15 fixed-point number, see the module documentation for its
+/// accuracy. Any angle is accepted, e.g., `sin_q15(-90) == -Q15_ONE`.
+pub fn sin_q15(angle_deg: i16) -> i16 {
+    sin_normalized(i32::from(angle_deg).rem_euclid(360) as u16)
+}
+
+/// Cosine of `angle_deg` in degrees as Q15 fixed-point number, see `sin_q15`.
+pub fn cos_q15(angle_deg: i16) -> i16 {
+    sin_normalized((i32::from(angle_deg) + 90).rem_euclid(360) as u16)
+}
+
+/// Sine of `angle_deg` in the range `0..360`, mirrored from the first quadrant.
+fn sin_normalized(angle_deg: u16) -> i16 {
+    match angle_deg {
+        0..=90 => sin_first_quadrant(angle_deg),
+        91..=180 => sin_first_quadrant(180 - angle_deg),
+        181..=270 => -sin_first_quadrant(angle_deg - 180),
+        _ => -sin_first_quadrant(360 - angle_deg),
+    }
+}
+
+/// Sine of `angle_deg` in the range `0..=90`, linearly interpolated between the two neighboring
+/// entries of `SINE_TABLE` with rounding.
+fn sin_first_quadrant(angle_deg: u16) -> i16 {
+    let index = usize::from(angle_deg / STEP_DEG);
+    let offset = angle_deg % STEP_DEG;
+    let low = SINE_TABLE[index];
+    if offset == 0 {
+        return low;
+    }
+    // the sine is increasing in the first quadrant, i.e., the difference is positive
+    let difference = (SINE_TABLE[index + 1] - low) as u16;
+    let interpolated =
+        (u32::from(difference) * u32::from(offset) + u32::from(STEP_DEG / 2)) / u32::from(STEP_DEG);
+    low + interpolated as i16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The true sine of `angle_deg` in Q15.
+    fn exact(angle_deg: i16) -> f64 {
+        f64::from(Q15_ONE) * f64::from(angle_deg).to_radians().sin()
+    }
+
+    #[test]
+    fn error_is_at_most_31_lsb() {
+        for angle_deg in -360..=360 {
+            let error = (f64::from(sin_q15(angle_deg)) - exact(angle_deg)).abs();
+            assert!(error <= 31.0, "sin({angle_deg}°) is off by {error}");
+        }
+    }
+
+    #[test]
+    fn error_is_largest_close_to_90_degrees() {
+        let error = |angle_deg| (f64::from(sin_q15(angle_deg)) - exact(angle_deg)).abs();
+        let worst = (0..=90).max_by(|&a, &b| error(a).total_cmp(&error(b)));
+        assert!(matches!(worst, Some(82..=88)));
+        assert!(error(2) < 5.0);
+    }
+
+    #[test]
+    fn quadrants_are_mirrored() {
+        assert_eq!(sin_q15(90), Q15_ONE);
+        assert_eq!(sin_q15(-90), -Q15_ONE);
+        assert_eq!(sin_q15(180), 0);
+        assert_eq!(cos_q15(0), Q15_ONE);
+        assert_eq!(cos_q15(60), sin_q15(30));
+        assert_eq!(sin_q15(200), -sin_q15(20));
+    }
+}
@@ -0,0 +1,58 @@
+//! Dead reckoning of the robot's position and heading.
+//!
+//! The `Pose` is kept in integer fixed-point units, so that neither its integration nor its output
+//! via the `Serial` connection pulls in floats.
+//!
+//! Example:
+//! ```rust
+//! let pose = Pose::new(-12_345, 250_000, 9_000);
+//! println!("Pose: ", pose); // writes "Pose: (-12.345mm, 250.000mm, 90.00deg)"
+//! ```
+
+use crate::{Serial, SerialWritable};
+
+/// Position and heading of the robot relative to its starting point.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Pose {
+    /// Position along the initial heading in µm.
+    pub x_um: i32,
+    /// Position to the left of the initial heading in µm.
+    pub y_um: i32,
+    /// Heading relative to the initial heading in 1/100°, counterclockwise, in the range
+    /// `-18000..18000`.
+    pub heading_cdeg: i16,
+}
+
+impl Pose {
+    /// Create a new pose from its coordinates in µm and its heading in 1/100°, which is normalized
+    /// to the range `-18000..18000`.
+    pub fn new(x_um: i32, y_um: i32, heading_cdeg: i32) -> Self {
+        Self {
+            x_um,
+            y_um,
+            heading_cdeg: normalize_heading(heading_cdeg),
+        }
+    }
+}
+
+/// Normalize a heading in 1/100° to the range `-18000..18000`.
+fn normalize_heading(heading_cdeg: i32) -> i16 {
+    match heading_cdeg.rem_euclid(36000) {
+        heading if heading >= 18000 => (heading - 36000) as i16,
+        heading => heading as i16,
+    }
+}
+
+impl SerialWritable for Pose {
+    /// Write the pose as `(x, y, heading)`, with the coordinates in mm and the heading in degrees,
+    /// e.g., as `"(-12.345mm, 250.000mm, 90.00deg)"`.
+    fn write_to_serial(&self) {
+        Serial::write('(');
+        Serial::write_fixed(self.x_um, 3);
+        Serial::write("mm, ");
+        Serial::write_fixed(self.y_um, 3);
+        Serial::write("mm, ");
+        Serial::write_fixed(self.heading_cdeg.into(), 2);
+        Serial::write("deg)");
+    }
+}
@@ -0,0 +1,289 @@
+//! Bus master for the 1-Wire bus of Maxim (formerly Dallas), e.g., for DS18B20 temperature sensors.
+//!
+//! The bus is bit-banged on an arbitrary pin, see `OneWire`, with the standard-speed timings of
+//! Maxim's application note 126. The bus needs an external pull-up resistor of about 4.7kΩ to
+//! `VDD`, as the pin only ever drives it low or releases it. Devices in parasite power mode, which
+//! need a strong pull-up during conversions, are not supported, so power them via their `VDD` pin.
+//!
+//! Example reading the temperature of a single DS18B20:
+//! ```rust
+//! let bus = OneWire::<port::ADC0>::new();
+//! bus.init();
+//! bus.reset()?;
+//! bus.skip_rom();
+//! bus.write_byte(0x44); // start the conversion
+//! delay_ms(750);
+//! bus.reset()?;
+//! bus.skip_rom();
+//! bus.write_byte(0xBE); // read the scratchpad
+//! let mut scratchpad = [0u8; 9];
+//! bus.read_bytes(&mut scratchpad);
+//! // temperature in 1/16°C
+//! let raw = i16::from_le_bytes([scratchpad[0], scratchpad[1]]);
+//! ```
+
+use crate::{interrupt, time::delay_us_exact, Pin};
+use core::{cmp::Ordering, marker::PhantomData};
+
+/// ROM code identifying a device on the bus: the family code, a 48-bit serial number and the
+/// `crc8` of the former seven bytes, in the order sent on the bus.
+pub type Rom = [u8; 8];
+
+/// ROM command addressing the device with the following ROM code.
+const MATCH_ROM: u8 = 0x55;
+
+/// ROM command addressing all devices at once.
+const SKIP_ROM: u8 = 0xCC;
+
+/// ROM command starting a search cycle, see `OneWire::search`.
+const SEARCH_ROM: u8 = 0xF0;
+
+/// Duration in µs of the reset pulse and of the following presence detection window.
+const RESET_US: u16 = 480;
+
+/// Time in µs after releasing the bus at which the presence pulse is sampled.
+const PRESENCE_SAMPLE_US: u16 = 70;
+
+/// Time in µs after which a pulled-up bus must read high, e.g., before a reset.
+const RELEASE_US: u16 = 5;
+
+/// Duration in µs of the low pulse starting a slot that writes a 1 or reads a bit.
+const SHORT_LOW_US: u16 = 6;
+
+/// Duration in µs of the low pulse of a slot that writes a 0.
+const LONG_LOW_US: u16 = 60;
+
+/// Time in µs after releasing the bus at which a read slot is sampled.
+const READ_SAMPLE_US: u16 = 9;
+
+/// Total duration of a slot in µs including the recovery time, at least 60µs + 1µs.
+const SLOT_US: u16 = 70;
+
+/// Errors of the 1-Wire bus.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OneWireError {
+    /// No device answered the reset pulse with a presence pulse.
+    NoDevice,
+    /// The bus reads low while released, i.e., it is shorted to ground or the pull-up is missing.
+    BusLow,
+    /// A ROM code read from the bus does not match its CRC.
+    Crc,
+    /// All devices stopped responding during a search, e.g., because one was disconnected.
+    Search,
+}
+
+/// Compute the CRC of `data` as used by 1-Wire devices, i.e., the polynomial
+/// `x^8 + x^5 + x^4 + 1` with bits processed least significant first. Computing the CRC of data
+/// including its trailing CRC byte yields zero.
+pub fn crc8(data: &[u8]) -> u8 {
+    data.iter().fold(0, |crc, &byte| {
+        (0..8)
+            .fold((crc, byte), |(crc, byte), _| {
+                let crc = if (crc ^ byte) & 1 != 0 {
+                    (crc >> 1) ^ 0x8C
+                } else {
+                    crc >> 1
+                };
+                (crc, byte >> 1)
+            })
+            .0
+    })
+}
+
+/// Bus master for a 1-Wire bus on pin `P`.
+///
+/// The pin is operated as open-drain output: its output value stays low, and the bus is driven
+/// low by switching the pin to an output, or released by switching it to an input. Each slot of
+/// a bit is timed via `delay_us_exact` with interrupts disabled, i.e., interrupts are delayed by up
+/// to 70µs per bit, and by 70µs during a reset. Between bits, interrupts are serviced, which the
+/// protocol allows, as the recovery time between slots has no upper bound.
+pub struct OneWire<P: Pin> {
+    _pin: PhantomData<fn() -> P>,
+}
+
+impl<P: Pin> OneWire<P> {
+    /// Create a new bus master. Call `init` before the first transfer.
+    pub const fn new() -> Self {
+        Self { _pin: PhantomData }
+    }
+
+    /// Release the bus, i.e., configure the pin as input without its internal pull-up.
+    pub fn init(&self) {
+        P::set_input();
+        P::set_low();
+    }
+
+    /// Reset all devices on the bus and detect their presence. Returns `OneWireError::BusLow` if
+    /// the bus does not read high before the reset, or `OneWireError::NoDevice` if no device
+    /// answered. Blocks for about 1ms.
+    pub fn reset(&self) -> Result<(), OneWireError> {
+        self.release();
+        delay_us_exact(RELEASE_US);
+        if P::is_low() {
+            return Err(OneWireError::BusLow);
+        }
+
+        // a longer reset pulse does no harm, so interrupts may extend it
+        self.drive_low();
+        delay_us_exact(RESET_US);
+        let present = interrupt::without_interrupts(|_| {
+            self.release();
+            delay_us_exact(PRESENCE_SAMPLE_US);
+            P::is_low()
+        });
+        delay_us_exact(RESET_US - PRESENCE_SAMPLE_US);
+
+        if present {
+            Ok(())
+        } else {
+            Err(OneWireError::NoDevice)
+        }
+    }
+
+    /// Write a single bit in a write slot.
+    pub fn write_bit(&self, bit: bool) {
+        interrupt::without_interrupts(|_| {
+            self.drive_low();
+            if bit {
+                delay_us_exact(SHORT_LOW_US);
+                self.release();
+                delay_us_exact(SLOT_US - SHORT_LOW_US);
+            } else {
+                delay_us_exact(LONG_LOW_US);
+                self.release();
+                delay_us_exact(SLOT_US - LONG_LOW_US);
+            }
+        });
+    }
+
+    /// Read a single bit in a read slot.
+    pub fn read_bit(&self) -> bool {
+        interrupt::without_interrupts(|_| {
+            self.drive_low();
+            delay_us_exact(SHORT_LOW_US);
+            self.release();
+            delay_us_exact(READ_SAMPLE_US);
+            let bit = P::is_high();
+            delay_us_exact(SLOT_US - SHORT_LOW_US - READ_SAMPLE_US);
+            bit
+        })
+    }
+
+    /// Write a byte, least significant bit first.
+    pub fn write_byte(&self, byte: u8) {
+        for bit in 0..8 {
+            self.write_bit(byte & (1 << bit) != 0);
+        }
+    }
+
+    /// Read a byte, least significant bit first.
+    pub fn read_byte(&self) -> u8 {
+        (0..8).fold(0, |byte, bit| byte | (u8::from(self.read_bit()) << bit))
+    }
+
+    /// Write all bytes of `data`.
+    pub fn write_bytes(&self, data: &[u8]) {
+        data.iter().for_each(|&byte| self.write_byte(byte));
+    }
+
+    /// Read bytes until `buffer` is full.
+    pub fn read_bytes(&self, buffer: &mut [u8]) {
+        buffer.iter_mut().for_each(|byte| *byte = self.read_byte());
+    }
+
+    /// Address all devices at once after a reset, e.g., if only a single device is connected, or
+    /// to start the conversions of all temperature sensors simultaneously.
+    pub fn skip_rom(&self) {
+        self.write_byte(SKIP_ROM);
+    }
+
+    /// Address the device with the given `rom` code after a reset, see `OneWire::search`.
+    pub fn select(&self, rom: &Rom) {
+        self.write_byte(MATCH_ROM);
+        self.write_bytes(rom);
+    }
+
+    /// Search the ROM codes of the devices on the bus and store them in `roms`, in ascending order
+    /// of their bits as sent on the bus. Returns the number of devices found, which is zero if no
+    /// device answers the reset. The search stops early once `roms` is full, i.e., further devices
+    /// are not reported.
+    ///
+    /// Implements the binary tree search of Maxim's application note 187: in each of the 64 steps,
+    /// all remaining devices send their next ROM bit and its complement, where a discrepancy, i.e.,
+    /// two zeros, indicates that devices differ in that bit. The master chooses a direction by
+    /// writing a bit, which deselects the other devices. Each pass takes the 1-branch at the last
+    /// discrepancy where the previous pass took the 0-branch, until no discrepancy is left.
+    pub fn search(&self, roms: &mut [Rom]) -> Result<usize, OneWireError> {
+        let mut rom: Rom = [0; 8];
+        // 1-based index of the bit at which the previous pass took the 0-branch last, or 0
+        let mut last_discrepancy = 0;
+        let mut found = 0;
+
+        while found < roms.len() {
+            match self.reset() {
+                Err(OneWireError::NoDevice) if found == 0 => return Ok(0),
+                result => result?,
+            }
+            self.write_byte(SEARCH_ROM);
+
+            let mut discrepancy = 0;
+            for index in 1..=64u8 {
+                let byte = usize::from((index - 1) / 8);
+                let mask = 1 << ((index - 1) % 8);
+                let direction = match (self.read_bit(), self.read_bit()) {
+                    (true, true) => return Err(OneWireError::Search),
+                    // all remaining devices agree on this bit
+                    (bit, complement) if bit != complement => bit,
+                    // discrepancy: follow the previous pass before its last discrepancy, take the
+                    // 1-branch at it, and the 0-branch after it
+                    _ => {
+                        let direction = match index.cmp(&last_discrepancy) {
+                            Ordering::Less => rom[byte] & mask != 0,
+                            Ordering::Equal => true,
+                            Ordering::Greater => false,
+                        };
+                        if !direction {
+                            discrepancy = index;
+                        }
+                        direction
+                    }
+                };
+                if direction {
+                    rom[byte] |= mask;
+                } else {
+                    rom[byte] &= !mask;
+                }
+                self.write_bit(direction);
+            }
+
+            if crc8(&rom) != 0 {
+                return Err(OneWireError::Crc);
+            }
+            roms[found] = rom;
+            found += 1;
+            last_discrepancy = discrepancy;
+            if last_discrepancy == 0 {
+                break;
+            }
+        }
+        Ok(found)
+    }
+
+    /// Drive the bus low.
+    #[inline(always)]
+    fn drive_low(&self) {
+        P::set_output();
+    }
+
+    /// Release the bus, which is pulled high by the external pull-up unless a device drives it low.
+    #[inline(always)]
+    fn release(&self) {
+        P::set_input();
+    }
+}
+
+impl<P: Pin> Default for OneWire<P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,17 @@
+//! Drivers for output devices that are commonly added to the robot via its expansion ports.
+
+/// Module implementing a driver for hobby servos timed by `Timer2`, see the feature
+/// `timer2-one-shot`.
+#[cfg(feature = "timer2-one-shot")]
+mod servo;
+#[cfg(feature = "timer2-one-shot")]
+pub use servo::{
+    Servo, SERVO_CHANNELS, SERVO_FRAME_MS, SERVO_MAX_ANGLE, SERVO_MAX_PULSE_US, SERVO_MIN_PULSE_US,
+};
+
+/// Module implementing a driver for serial-in, parallel-out shift registers such as the 74HC595.
+mod shift_register;
+pub use shift_register::ShiftRegister;
+
+pub mod ws2812;
+pub use ws2812::Rgb;
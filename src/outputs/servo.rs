@@ -0,0 +1,284 @@
+use crate::{
+    avr::{
+        config::CPU_FREQUENCY_HZ,
+        modules::{ClockSource8, Timer8},
+        timers::Timer2,
+    },
+    interrupt::{
+        self,
+        mutex::{DynamicMutex, Mutex},
+        CriticalSection,
+    },
+    time, Pin,
+};
+
+/// Number of servos that can be attached at once.
+pub const SERVO_CHANNELS: usize = 4;
+
+/// Period in ms at which the control pulses of all servos are repeated.
+pub const SERVO_FRAME_MS: u32 = 20;
+
+/// Length of the control pulse in µs for an angle of 0°.
+pub const SERVO_MIN_PULSE_US: u32 = 1000;
+
+/// Length of the control pulse in µs for an angle of `SERVO_MAX_ANGLE`.
+pub const SERVO_MAX_PULSE_US: u32 = 2000;
+
+/// Largest angle in degrees, larger angles are limited to it.
+pub const SERVO_MAX_ANGLE: u8 = 180;
+
+/// Duration of a tick of `Timer2` in µs with a prescaler of 64, i.e., the resolution of the pulses.
+const TICK_US: u32 = 64_000_000 / CPU_FREQUENCY_HZ;
+
+const _: () = assert!(
+    SERVO_MAX_PULSE_US / TICK_US <= u8::MAX as u32,
+    "The servo pulses exceed the range of `Timer2` at this CPU frequency."
+);
+
+/// A sweep of a servo at constant speed, see `Servo::sweep`.
+#[derive(Clone, Copy)]
+struct Sweep {
+    /// Angle at the start of the sweep.
+    from_deg: u8,
+    /// Angle at the end of the sweep.
+    to_deg: u8,
+    /// Speed in degrees per second, at least 1.
+    speed_deg_per_s: u16,
+    /// Time (see `time::millis`) at which the sweep started.
+    start_ms: u32,
+}
+
+impl Sweep {
+    /// The angle of the sweep at `now_ms` and whether the sweep has finished.
+    fn angle_at(&self, now_ms: u32) -> (u8, bool) {
+        let elapsed_ms = now_ms.wrapping_sub(self.start_ms);
+        let distance = u32::from(self.from_deg.abs_diff(self.to_deg));
+        let speed = u32::from(self.speed_deg_per_s);
+        if elapsed_ms >= distance * 1000 / speed {
+            return (self.to_deg, true);
+        }
+
+        // `elapsed_ms * speed < distance * 1000`, so the product cannot overflow
+        let travelled = (elapsed_ms * speed / 1000) as u8;
+        if self.from_deg < self.to_deg {
+            (self.from_deg + travelled, false)
+        } else {
+            (self.from_deg - travelled, false)
+        }
+    }
+}
+
+/// An attached servo.
+#[derive(Clone, Copy)]
+struct Channel {
+    /// Drives the servo's pin high or low.
+    set_pin: fn(bool),
+    /// Current angle in degrees.
+    angle_deg: u8,
+    /// Sweep in progress, if any.
+    sweep: Option<Sweep>,
+}
+
+/// The attached servos by channel.
+static CHANNELS: DynamicMutex<[Option<Channel>; SERVO_CHANNELS]> =
+    DynamicMutex::new([None; SERVO_CHANNELS]);
+
+/// Channel whose pulse is currently generated, or `None` between frames.
+static CURRENT: Mutex<Option<usize>> = Mutex::new(None);
+
+/// Time (see `time::millis`) at which the next frame of pulses is due.
+static NEXT_FRAME_MS: Mutex<u32> = Mutex::new(0);
+
+/// Drive the servo's pin `P` high or low.
+fn set_pin<P: Pin>(high: bool) {
+    if high {
+        P::set_high();
+    } else {
+        P::set_low();
+    }
+}
+
+/// Length of the control pulse for `angle_deg` in ticks of `Timer2`.
+fn pulse_ticks(angle_deg: u8) -> u8 {
+    let pulse_us = SERVO_MIN_PULSE_US
+        + u32::from(angle_deg) * (SERVO_MAX_PULSE_US - SERVO_MIN_PULSE_US)
+            / u32::from(SERVO_MAX_ANGLE);
+    (pulse_us / TICK_US) as u8
+}
+
+/// Driver for up to `SERVO_CHANNELS` hobby servos on arbitrary pins, e.g., of the expansion ports.
+///
+/// The servos are controlled by a pulse of `SERVO_MIN_PULSE_US` to `SERVO_MAX_PULSE_US` every
+/// `SERVO_FRAME_MS`, which are generated one after another. Each frame is started by
+/// `Servo::update`, which has to be called regularly from the main loop, while the pulses are
+/// timed by one-shots of `Timer2` (see `Timer8::after`) with a resolution of 8µs at 8MHz, i.e.,
+/// about 1.5°. Hence, the main loop is not blocked, but `Timer2` must not be used for any other
+/// purpose while servos are attached. Other interrupts, e.g., the system tick, may delay the end of
+/// a pulse by a few µs, which lets the servo jitter slightly.
+///
+/// Requires the system tick to be started with `time::init_tick`, and the feature
+/// `timer2-one-shot`.
+///
+/// Example:
+/// ```rust
+/// time::init_tick(time::DEFAULT_TICK_PERIOD_US).unwrap();
+/// Servo::attach::<port::ADC0>(0, 90);
+/// Servo::sweep(0, 0, 180, 90); // pan from 0° to 180° within 2s
+/// loop {
+///     if Servo::update() {
+///         // the sweep has finished, e.g., chain the sweep back
+///     }
+///     // react to events meanwhile
+/// }
+/// ```
+pub struct Servo;
+
+impl Servo {
+    /// Attach a servo on pin `P` as `channel` (0 to `SERVO_CHANNELS - 1`) at `angle_deg`, replacing
+    /// a servo previously attached as `channel`. Out-of-range channels are ignored.
+    pub fn attach<P: Pin>(channel: usize, angle_deg: u8) {
+        P::set_low();
+        P::set_output();
+        interrupt::without_interrupts(|cs| {
+            Self::detach_in(cs, channel);
+            if let Some(slot) = CHANNELS.lock(cs).borrow_mut().get_mut(channel) {
+                *slot = Some(Channel {
+                    set_pin: set_pin::<P>,
+                    angle_deg: angle_deg.min(SERVO_MAX_ANGLE),
+                    sweep: None,
+                });
+            }
+        });
+    }
+
+    /// Detach the servo of `channel`, which stops its pulses and drives its pin low. Note that most
+    /// servos stop holding their position without pulses.
+    pub fn detach(channel: usize) {
+        interrupt::without_interrupts(|cs| Self::detach_in(cs, channel));
+    }
+
+    /// Move the servo of `channel` to `angle_deg` with the next frame, cancelling its sweep, if
+    /// any.
+    pub fn set_angle(channel: usize, angle_deg: u8) {
+        interrupt::without_interrupts(|cs| {
+            if let Some(Some(servo)) = CHANNELS.lock(cs).borrow_mut().get_mut(channel) {
+                servo.angle_deg = angle_deg.min(SERVO_MAX_ANGLE);
+                servo.sweep = None;
+            }
+        });
+    }
+
+    /// The current angle of the servo of `channel`, or `None` if no servo is attached.
+    pub fn angle(channel: usize) -> Option<u8> {
+        interrupt::without_interrupts(|cs| {
+            let channels = CHANNELS.lock(cs).borrow();
+            channels
+                .get(channel)
+                .copied()
+                .flatten()
+                .map(|servo| servo.angle_deg)
+        })
+    }
+
+    /// Move the servo of `channel` smoothly from `from_deg` to `to_deg` at `speed_deg_per_s`
+    /// degrees per second (at least 1), starting now. The angle is interpolated linearly over time
+    /// (see `time::millis`) by `Servo::update`, so that the sweep does not block. A sweep replaces a
+    /// sweep in progress and ends at `to_deg`, which is kept afterwards.
+    pub fn sweep(channel: usize, from_deg: u8, to_deg: u8, speed_deg_per_s: u16) {
+        let from_deg = from_deg.min(SERVO_MAX_ANGLE);
+        let sweep = Sweep {
+            from_deg,
+            to_deg: to_deg.min(SERVO_MAX_ANGLE),
+            speed_deg_per_s: speed_deg_per_s.max(1),
+            start_ms: time::millis(),
+        };
+        interrupt::without_interrupts(|cs| {
+            if let Some(Some(servo)) = CHANNELS.lock(cs).borrow_mut().get_mut(channel) {
+                servo.angle_deg = from_deg;
+                servo.sweep = Some(sweep);
+            }
+        });
+    }
+
+    /// Check if the servo of `channel` is sweeping, see `Servo::sweep`.
+    pub fn is_sweeping(channel: usize) -> bool {
+        interrupt::without_interrupts(|cs| {
+            let channels = CHANNELS.lock(cs).borrow();
+            matches!(
+                channels.get(channel),
+                Some(Some(Channel { sweep: Some(_), .. }))
+            )
+        })
+    }
+
+    /// Advance the sweeps in progress to the current time and start the next frame of pulses if it
+    /// is due. Call this regularly from the main loop, at least every `SERVO_FRAME_MS`. Returns
+    /// whether all sweeps have finished, e.g., to chain the next sweep, which is also the case if
+    /// no sweep was started at all.
+    pub fn update() -> bool {
+        let now_ms = time::millis();
+        interrupt::without_interrupts(|cs| {
+            let mut channels = CHANNELS.lock(cs).borrow_mut();
+            let mut finished = true;
+            for servo in channels.iter_mut().flatten() {
+                if let Some(sweep) = servo.sweep {
+                    let (angle_deg, done) = sweep.angle_at(now_ms);
+                    servo.angle_deg = angle_deg;
+                    if done {
+                        servo.sweep = None;
+                    } else {
+                        finished = false;
+                    }
+                }
+            }
+
+            let next_frame_ms = NEXT_FRAME_MS.lock(cs);
+            let frame_running = CURRENT.lock(cs).get().is_some();
+            if !frame_running && now_ms.wrapping_sub(next_frame_ms.get()) as i32 >= 0 {
+                next_frame_ms.set(now_ms.wrapping_add(SERVO_FRAME_MS));
+                Timer2::set_clock_source(ClockSource8::Prescale64);
+                start_pulse(cs, &channels, 0);
+            }
+            finished
+        })
+    }
+
+    /// Remove the servo of `channel` and drive its pin low, also if its pulse is currently
+    /// generated.
+    fn detach_in(cs: &CriticalSection, channel: usize) {
+        if let Some(slot) = CHANNELS.lock(cs).borrow_mut().get_mut(channel) {
+            if let Some(servo) = slot.take() {
+                (servo.set_pin)(false);
+            }
+        }
+    }
+}
+
+/// Start the pulse of the first attached servo from channel `first` on. Once all pulses of the
+/// frame have been generated, `Timer2` is stopped until the next frame.
+fn start_pulse(cs: &CriticalSection, channels: &[Option<Channel>; SERVO_CHANNELS], first: usize) {
+    for (index, servo) in channels.iter().enumerate().skip(first) {
+        if let Some(servo) = servo {
+            CURRENT.lock(cs).set(Some(index));
+            (servo.set_pin)(true);
+            Timer2::after(pulse_ticks(servo.angle_deg), end_pulse);
+            return;
+        }
+    }
+
+    CURRENT.lock(cs).set(None);
+    Timer2::set_clock_source(ClockSource8::None);
+}
+
+/// End the pulse of the current servo and start the next one, called by the `Timer2` one-shot.
+fn end_pulse() {
+    interrupt::without_interrupts(|cs| {
+        let channels = CHANNELS.lock(cs).borrow();
+        if let Some(current) = CURRENT.lock(cs).get() {
+            if let Some(servo) = channels[current] {
+                (servo.set_pin)(false);
+            }
+            start_pulse(cs, &channels, current + 1);
+        }
+    });
+}
@@ -0,0 +1,95 @@
+use crate::{delay_us, Pin};
+use core::marker::PhantomData;
+
+/// Driver for serial-in, parallel-out shift registers with a storage latch, e.g., the 74HC595,
+/// which are a common building block for LED matrices and 7-segment displays. The pins `Data`
+/// (`SER`), `Clock` (`SRCLK`) and `Latch` (`RCLK`) are bit-banged, shifting out the data MSB-first.
+/// Several registers can be daisy-chained, e.g., two registers for `write_u16`.
+///
+/// Each level of the clock and latch signals is held for `DELAY_US` µs. The default of 1µs is
+/// conservative even for slow registers or long wires, while it can be set to 0 for maximum speed.
+pub struct ShiftRegister<Data: Pin, Clock: Pin, Latch: Pin, const DELAY_US: u32 = 1> {
+    _pins: PhantomData<(Data, Clock, Latch)>,
+}
+
+impl<Data: Pin, Clock: Pin, Latch: Pin, const DELAY_US: u32>
+    ShiftRegister<Data, Clock, Latch, DELAY_US>
+{
+    /// Create a new driver. Call `init` before writing any data.
+    pub const fn new() -> Self {
+        Self { _pins: PhantomData }
+    }
+
+    /// Configure all pins as outputs driven low.
+    pub fn init(&self) {
+        Data::set_low();
+        Data::set_output();
+        Clock::set_low();
+        Clock::set_output();
+        Latch::set_low();
+        Latch::set_output();
+    }
+
+    /// Shift out a single byte MSB-first and latch it to the outputs.
+    pub fn write_byte(&self, value: u8) {
+        self.shift_out(value);
+        self.latch();
+    }
+
+    /// Shift out two bytes MSB-first, i.e., the high byte ends up in the second of two
+    /// daisy-chained registers, and latch them to the outputs.
+    pub fn write_u16(&self, value: u16) {
+        let [high, low] = value.to_be_bytes();
+        self.shift_out(high);
+        self.shift_out(low);
+        self.latch();
+    }
+
+    /// Shift out the bytes of `values` in order, each MSB-first, and latch them to the outputs. The
+    /// last byte ends up in the first register of a daisy chain.
+    pub fn write(&self, values: &[u8]) {
+        for &value in values {
+            self.shift_out(value);
+        }
+        self.latch();
+    }
+
+    /// Shift a byte into the register(s) MSB-first without changing the outputs.
+    fn shift_out(&self, value: u8) {
+        for bit in (0..8).rev() {
+            if value & (1 << bit) != 0 {
+                Data::set_high();
+            } else {
+                Data::set_low();
+            }
+            Self::delay();
+            Clock::set_high();
+            Self::delay();
+            Clock::set_low();
+        }
+    }
+
+    /// Pulse the latch to transfer the shifted data to the outputs.
+    fn latch(&self) {
+        Self::delay();
+        Latch::set_high();
+        Self::delay();
+        Latch::set_low();
+    }
+
+    /// Hold a signal level for `DELAY_US` µs.
+    #[inline(always)]
+    fn delay() {
+        if DELAY_US > 0 {
+            delay_us(DELAY_US);
+        }
+    }
+}
+
+impl<Data: Pin, Clock: Pin, Latch: Pin, const DELAY_US: u32> Default
+    for ShiftRegister<Data, Clock, Latch, DELAY_US>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,115 @@
+//! Driver for WS2812 ("NeoPixel") RGB LEDs, which are daisy-chained on a single data line.
+//!
+//! The LEDs expect an 800kHz bit stream, where each bit of 1.25µs starts with a high pulse of
+//! about 0.4µs (`T0H`) for a 0 or 0.8µs (`T1H`) for a 1. At the 8MHz of the stock RP6, this leaves
+//! only 10 CPU cycles per bit, so the bit stream is generated by cycle-counted inline assembly:
+//! `T0H` is 3 cycles (375ns) and `T1H` is 6 cycles (750ns), which is within the tolerances of the
+//! WS2812 and WS2812B. Hence, this driver requires a CPU clock of exactly 8MHz, which is checked
+//! at compile time.
+
+use crate::{
+    avr::config::CPU_FREQUENCY_HZ,
+    delay_us,
+    interrupt::{self, CriticalSection},
+    Pin, Register,
+};
+#[cfg(target_arch = "avr")]
+use core::{arch::asm, mem::transmute};
+
+/// Time in µs the data line is held low after a transfer, so that the LEDs latch the received
+/// colors. The WS2812B requires at least 280µs, while older WS2812 require 50µs.
+const RESET_US: u32 = 300;
+
+/// Color of a single LED. The fields are stored in the order green, red, blue, which is the order
+/// the LEDs expect on the wire, so that a slice of colors can be transmitted as is.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Rgb {
+    pub g: u8,
+    pub r: u8,
+    pub b: u8,
+}
+
+impl Rgb {
+    /// All channels off.
+    pub const OFF: Rgb = Rgb::new(0, 0, 0);
+
+    /// Create a color from its red, green and blue channels.
+    pub const fn new(r: u8, g: u8, b: u8) -> Self {
+        Self { g, r, b }
+    }
+}
+
+/// Configure the data pin `P` as output driven low. The pin has to stay low for `RESET_US` before
+/// the first transfer, which `write` ensures after each transfer.
+pub fn init<P: Pin>() {
+    P::set_low();
+    P::set_output();
+    delay_us(RESET_US);
+}
+
+/// Transmit `colors` to the chain of LEDs on the data pin `P`, i.e., the first color is shown by
+/// the first LED of the chain. Interrupts are disabled during the transfer, which takes 30µs per
+/// LED, as any interruption would corrupt the bit stream. Afterwards, blocks for `RESET_US` (300µs)
+/// until the LEDs have latched the colors. Requires `init` to be called once beforehand.
+pub fn write<P: Pin>(colors: &[Rgb]) {
+    const {
+        assert!(
+            CPU_FREQUENCY_HZ == 8_000_000,
+            "The WS2812 driver is cycle-counted for a CPU clock of 8MHz."
+        );
+    }
+    if colors.is_empty() {
+        return;
+    }
+
+    // SAFETY: `Rgb` consists of three bytes, see `repr(C)`
+    let bytes =
+        unsafe { core::slice::from_raw_parts(colors.as_ptr() as *const u8, colors.len() * 3) };
+    interrupt::without_interrupts(|cs| transmit::<P>(cs, bytes));
+    delay_us(RESET_US);
+}
+
+/// Generate the bit stream of `bytes` (MSB-first) on pin `P` within 10 cycles per bit, see the
+/// module documentation. `bytes` must not be empty.
+#[inline(always)]
+fn transmit<P: Pin>(_cs: &CriticalSection, bytes: &[u8]) {
+    // the values of the port with the data pin set high and low, where the remaining pins keep
+    // their state as interrupts are disabled
+    let high = P::PORT::read() | P::MASK;
+    let low = high & !P::MASK;
+    #[cfg(not(target_arch = "avr"))]
+    let _ = (high, low, bytes);
+    #[cfg(target_arch = "avr")]
+    unsafe {
+        asm!(
+            // load the next byte and reset the bit counter
+            "2:",
+            "ld {byte}, X+",
+            "ldi {bits}, 8",
+            // transmit a single bit within 10 cycles, starting with the rising edge in cycle 0
+            "3:",
+            "out {port}, {high}",  // cycle 0
+            "nop",                 // cycle 1
+            "sbrs {byte}, 7",      // cycle 2 (and 3 when skipping, i.e., for a 1)
+            "out {port}, {low}",   // cycle 3: falling edge for a 0
+            "lsl {byte}",          // cycle 4
+            "nop",                 // cycle 5
+            "out {port}, {low}",   // cycle 6: falling edge for a 1
+            "dec {bits}",          // cycle 7
+            "brne 3b",             // cycles 8 and 9
+            // continue with the next byte, the line stays low in the meantime
+            "sbiw {length}, 1",
+            "brne 2b",
+            // I/O address of the port register, i.e., its memory address minus 0x20
+            port = const { transmute::<*mut u8, usize>(P::PORT::ADDRESS) - 0x20 },
+            high = in(reg) high,
+            low = in(reg) low,
+            byte = out(reg) _,
+            bits = out(reg_upper) _,
+            length = inout(reg_iw) bytes.len() as u16 => _,
+            inout("X") bytes.as_ptr() => _,
+            options(nostack),
+        )
+    }
+}
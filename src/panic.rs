@@ -0,0 +1,29 @@
+//! Panic handler that leaves the robot in a safe state.
+
+use crate::{print, Motors, RobotBase, Serial};
+use core::panic::PanicInfo;
+
+/// Stop the motors first, as a panic may occur at any time, e.g., while driving towards a wall.
+/// Then report the location of the panic over the `Serial` connection (without formatting the
+/// message, to keep the code small) and halt the robot, see `RobotBase::halt`.
+///
+/// As the panic may occur before the motors are initialized, `Motors::emergency_stop` is used,
+/// which is safe to call in any state. If `Serial` is not initialized, the report is lost.
+///
+/// The watchdog is deliberately not used to stop the motors: `emergency_stop` only writes
+/// registers and cannot block, whereas a watchdog reset would restart the application that
+/// panicked, which might start driving again.
+#[panic_handler]
+fn panic(info: &PanicInfo) -> ! {
+    Motors::emergency_stop();
+
+    print!("\nPANIC");
+    if let Some(location) = info.location() {
+        print!(" (", location.file(), ":");
+        Serial::write_dec_u32(location.line());
+        Serial::write(")");
+    }
+    Serial::new_line();
+
+    RobotBase::halt()
+}
@@ -0,0 +1,77 @@
+//! Pins that are reconfigured by the peripherals of this crate, e.g., to avoid conflicts when
+//! building expansion boards. Pins are named like in `crate::avr::port`, e.g., `"d0"`, and their
+//! function on the RP6 is given in `crate::port`.
+//!
+//! Example:
+//! ```rust
+//! // check that an expansion board may use `c0` and `c1` together with the motors
+//! let conflict = ["c0", "c1"].iter().any(|pin| pinout::is_used_by(pin, pinout::MOTOR_PINS));
+//! ```
+
+/// Pins of the UART, reconfigured and claimed by `Serial::init`: `RX` (`d0`) and `TX` (`d1`).
+pub const UART_PINS: &[&str] = &["d0", "d1"];
+
+/// Pins of the motors, reconfigured by `Motors::init`: the PWM outputs `Motor_L` (`d4`, `OC1B`)
+/// and `Motor_R` (`d5`, `OC1A`), which are claimed, and the direction outputs `Dir_L` (`c2`) and
+/// `Dir_R` (`c3`).
+pub const MOTOR_PINS: &[&str] = &["d4", "d5", "c2", "c3"];
+
+/// Pins of the ADC channels `ADC0` to `ADC7` converted by `Adc::read`. The pins are not
+/// reconfigured, but a channel only reads an analog voltage while its pin is an input without
+/// pull-up.
+pub const ADC_PINS: &[&str] = &["a0", "a1", "a2", "a3", "a4", "a5", "a6", "a7"];
+
+/// Pins of the TWI bus, claimed by `TwiMaster::init`: `SCL` (`c0`) and `SDA` (`c1`).
+pub const TWI_PINS: &[&str] = &["c0", "c1"];
+
+/// Pins of the SPI, reconfigured by `HardwareSpi::setup_master` and `HardwareSpi::setup_slave`:
+/// `SS` (`b4`), `MOSI` (`b5`), `MISO` (`b6`) and `SCK` (`b7`). Note that on the RP6, these pins
+/// are shared with `PowerOn`, `ResetButton`, `ACS_L` and `Led4`.
+pub const SPI_PINS: &[&str] = &["b4", "b5", "b6", "b7"];
+
+/// All peripherals with the pins they reconfigure.
+pub const PERIPHERALS: &[(&str, &[&str])] = &[
+    ("UART", UART_PINS),
+    ("Motors", MOTOR_PINS),
+    ("ADC", ADC_PINS),
+    ("TWI", TWI_PINS),
+    ("SPI", SPI_PINS),
+];
+
+/// Pins reconfigured by `Serial::init`, see `UART_PINS`.
+pub fn uart_pins() -> &'static [&'static str] {
+    UART_PINS
+}
+
+/// Pins reconfigured by `Motors::init`, see `MOTOR_PINS`.
+pub fn motor_pins() -> &'static [&'static str] {
+    MOTOR_PINS
+}
+
+/// Pins converted by `Adc::read`, see `ADC_PINS`.
+pub fn adc_pins() -> &'static [&'static str] {
+    ADC_PINS
+}
+
+/// Pins reconfigured by `TwiMaster::init`, see `TWI_PINS`.
+pub fn twi_pins() -> &'static [&'static str] {
+    TWI_PINS
+}
+
+/// Pins reconfigured by the SPI, see `SPI_PINS`.
+pub fn spi_pins() -> &'static [&'static str] {
+    SPI_PINS
+}
+
+/// Check if `pin` is contained in the list of `pins` of a peripheral, e.g., `MOTOR_PINS`.
+pub fn is_used_by(pin: &str, pins: &[&str]) -> bool {
+    pins.contains(&pin)
+}
+
+/// The names of all peripherals in `PERIPHERALS` that reconfigure `pin`.
+pub fn users(pin: &str) -> impl Iterator<Item = &'static str> + '_ {
+    PERIPHERALS
+        .iter()
+        .filter(move |(_, pins)| is_used_by(pin, pins))
+        .map(|&(name, _)| name)
+}
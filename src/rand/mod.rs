@@ -0,0 +1,123 @@
+//! Deterministic pseudo-random numbers, e.g., for random wandering behaviors.
+//!
+//! `Xorshift` is a 32-bit xorshift generator: it is small and fast, but its output is fully
+//! determined by its seed and can be predicted from a few observed values. Hence, it is **not**
+//! suitable for any cryptographic purpose, e.g., keys or nonces. Its statistical quality is
+//! sufficient for robot behaviors, but consecutive values are not independent in a strict sense.
+//!
+//! To get different sequences after each reset, seed the generator via `Xorshift::from_entropy`,
+//! which harvests the noise of the ADC, see `harvest_entropy`. A fixed seed via `Xorshift::new`
+//! reproduces the same sequence, e.g., for debugging.
+//!
+//! Example:
+//! ```rust
+//! let mut rng = Xorshift::from_entropy();
+//! let turn_ms = rng.range(200, 800); // turn for a random duration
+//! ```
+
+use crate::avr::adc::Adc;
+
+/// Seed used instead of zero, which is the only seed that the generator never leaves.
+const DEFAULT_SEED: u32 = 0x2545_F491;
+
+/// Number of pairs of conversions per ADC channel after which `harvest_entropy` moves on to the
+/// next channel, as channels without noise, e.g., saturated ones, never yield a bit.
+const PAIRS_PER_CHANNEL: u8 = 8;
+
+/// Upper bound of the total number of pairs of conversions of `harvest_entropy`, so that it
+/// terminates even if all channels are free of noise.
+const MAX_PAIRS: u16 = 1024;
+
+/// Harvest 32 bits of entropy from the noise in the least significant bit of ADC conversions.
+///
+/// Each channel `ADC0` to `ADC7` is converted against the internal 2.56V reference, which
+/// resolves about 2.5mV per step, so that the least significant bit is dominated by noise for
+/// most inputs, e.g., the battery voltage under load or unconnected inputs. The bits are
+/// debiased by the von Neumann extractor: of two consecutive conversions of the same channel,
+/// differing bits yield `0` or `1`, whereas equal bits (e.g., of a saturated or noiseless channel)
+/// are discarded.
+///
+/// Blocks for about 25ms typically (about 0.2ms per pair of conversions), but at most for
+/// `MAX_PAIRS` pairs, i.e., about 0.25s. If the noise is insufficient by then, the remaining bits
+/// are zero. Afterwards, `Adc::read` switches back to the `AVCC` reference, which takes 1ms.
+pub fn harvest_entropy() -> u32 {
+    let mut entropy = 0u32;
+    let mut bits = 0;
+    let mut channel = 0;
+    let mut pairs = 0;
+
+    while bits < 32 && pairs < MAX_PAIRS {
+        for _ in 0..PAIRS_PER_CHANNEL {
+            let first = Adc::read_with_internal_reference(channel) & 1;
+            let second = Adc::read_with_internal_reference(channel) & 1;
+            pairs += 1;
+            if first != second {
+                entropy = (entropy << 1) | u32::from(first);
+                bits += 1;
+                if bits == 32 {
+                    break;
+                }
+            }
+        }
+        channel = (channel + 1) % 8;
+    }
+    entropy
+}
+
+/// A 32-bit xorshift pseudo-random number generator, see the module documentation for its
+/// limitations.
+#[derive(Clone, Debug)]
+pub struct Xorshift {
+    /// The current state, never zero.
+    state: u32,
+}
+
+impl Xorshift {
+    /// Create a new generator from the given `seed`. A seed of zero is replaced by a fixed nonzero
+    /// seed, as the generator would only return zeros otherwise.
+    pub const fn new(seed: u32) -> Self {
+        Self {
+            state: if seed == 0 { DEFAULT_SEED } else { seed },
+        }
+    }
+
+    /// Create a new generator seeded via `harvest_entropy`, i.e., with a different sequence after
+    /// each reset. Blocks for the duration of `harvest_entropy`.
+    pub fn from_entropy() -> Self {
+        Self::new(harvest_entropy())
+    }
+
+    /// The next pseudo-random 32-bit value. Never returns zero.
+    pub fn next_u32(&mut self) -> u32 {
+        let mut x = self.state;
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        self.state = x;
+        x
+    }
+
+    /// The next pseudo-random 16-bit value, taken from the upper half of `Xorshift::next_u32`,
+    /// which is of better quality than the lower half.
+    pub fn next_u16(&mut self) -> u16 {
+        (self.next_u32() >> 16) as u16
+    }
+
+    /// A pseudo-random value in the range `lo..hi`, uniformly distributed without modulo bias.
+    /// Returns `lo` if the range is empty, i.e., if `hi <= lo`.
+    pub fn range(&mut self, lo: u16, hi: u16) -> u16 {
+        if hi <= lo {
+            return lo;
+        }
+
+        // reject values of the incomplete last "bucket" of `span` values to avoid the bias
+        let span = u32::from(hi - lo);
+        let limit = (1 << 16) / span * span;
+        loop {
+            let value = u32::from(self.next_u16());
+            if value < limit {
+                return lo + (value % span) as u16;
+            }
+        }
+    }
+}
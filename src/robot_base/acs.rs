@@ -1,6 +1,59 @@
 use super::{port::*, Pin, RobotBase};
+use crate::{delay_us, interrupt, time::delay_us_exact, Register};
+
+/// Number of bursts sent per side by `RobotBase::measure_acs`, i.e., the maximum signal strength.
+pub const ACS_BURSTS: u8 = 8;
+
+/// Number of carrier cycles per burst. The receiver only responds to bursts of at least 10 cycles.
+const BURST_CYCLES: u8 = 20;
+
+/// Number of carrier cycles after which the receiver responds to a burst at the latest.
+const RESPONSE_CYCLES: u8 = 10;
+
+/// Half of the period of the 36kHz carrier in µs, minus the overhead of toggling the LED.
+const HALF_PERIOD_US: u16 = 13;
+
+/// Pause in µs between two bursts, which the receiver needs to recover.
+const BURST_GAP_US: u32 = 1000;
+
+/// Approximate signal strength of the ACS per side, i.e., the number of the `ACS_BURSTS` bursts
+/// whose reflection was received, see `RobotBase::measure_acs`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AcsResult {
+    /// Number of bursts of the left IR LED that were reflected back.
+    pub left: u8,
+    /// Number of bursts of the right IR LED that were reflected back.
+    pub right: u8,
+}
+
+/// Power levels of the ACS, see `RobotBase::acs_power`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AcsPower {
+    /// The ACS is disabled, see `RobotBase::set_acs_power_off`.
+    Off,
+    /// See `RobotBase::set_acs_power_low`.
+    Low,
+    /// See `RobotBase::set_acs_power_medium`.
+    Medium,
+    /// See `RobotBase::set_acs_power_high`.
+    High,
+}
 
 impl RobotBase {
+    /// The power level of the ACS as set by `RobotBase::set_acs_power_off` and its siblings,
+    /// determined from the directions of the pins `ACS_Pwr` and `ACS_PwrH`.
+    pub fn acs_power() -> AcsPower {
+        match (
+            <ACS_Pwr as Pin>::DDR::is_mask_set_raw(ACS_Pwr::MASK),
+            <ACS_PwrH as Pin>::DDR::is_mask_set_raw(ACS_PwrH::MASK),
+        ) {
+            (false, false) => AcsPower::Off,
+            (true, false) => AcsPower::Low,
+            (false, true) => AcsPower::Medium,
+            (true, true) => AcsPower::High,
+        }
+    }
+
     /// Disable the ACS of the robot.
     pub fn set_acs_power_off() {
         ACS_Pwr::set_input();
@@ -34,4 +87,58 @@ impl RobotBase {
         ACS_PwrH::set_output();
         ACS_PwrH::set_high();
     }
+
+    /// Measure an approximate signal strength of the ACS per side, as a crude proximity estimate
+    /// that lets behaviors react proportionally instead of binary. Each IR LED sends `ACS_BURSTS`
+    /// bursts of a 36kHz carrier in turn, and each burst is counted if the receiver on `ACS`
+    /// detects its reflection. Returns counts from 0 (nothing detected) to `ACS_BURSTS` (every
+    /// burst detected), where intermediate counts occur near the edge of the range or with weakly
+    /// reflecting obstacles.
+    ///
+    /// The ACS power must be set beforehand, e.g., via `RobotBase::set_acs_power_low`. A higher
+    /// power increases the range, so that an obstacle at a given distance reflects more bursts:
+    /// the counts are only comparable at the same power level, and thresholds of behaviors must be
+    /// tuned per level. As near obstacles already reflect every burst at high power, use a lower
+    /// power to distinguish near distances, and a higher power to detect distant obstacles. Dark,
+    /// matte surfaces reflect far less than light ones, i.e., the counts are not a distance.
+    ///
+    /// Blocks for about 1.6ms per burst, i.e., about 25ms in total. The carrier is generated with
+    /// interrupts disabled for about 0.6ms per burst, which the `Serial` connection bridges with its
+    /// hardware buffer at the default baudrate.
+    pub fn measure_acs() -> AcsResult {
+        ACS::set_input();
+        ACS_L::set_low();
+        ACS_L::set_output();
+        ACS_R::set_low();
+        ACS_R::set_output();
+        AcsResult {
+            left: Self::count_reflections::<ACS_L>(),
+            right: Self::count_reflections::<ACS_R>(),
+        }
+    }
+
+    /// Send `ACS_BURSTS` bursts via the IR LED `LED` and count the bursts whose reflection was
+    /// detected by the receiver, whose output is low while it receives the carrier.
+    fn count_reflections<LED: Pin>() -> u8 {
+        let mut count = 0;
+        for _ in 0..ACS_BURSTS {
+            let detected = interrupt::without_interrupts(|_| {
+                let mut detected = false;
+                for cycle in 0..BURST_CYCLES {
+                    LED::toggle();
+                    delay_us_exact(HALF_PERIOD_US);
+                    LED::toggle();
+                    delay_us_exact(HALF_PERIOD_US);
+                    if cycle >= RESPONSE_CYCLES && ACS::is_low() {
+                        detected = true;
+                    }
+                }
+                LED::set_low();
+                detected
+            });
+            count += u8::from(detected);
+            delay_us(BURST_GAP_US);
+        }
+        count
+    }
 }
@@ -0,0 +1,174 @@
+use super::Motors;
+#[cfg(feature = "encoders")]
+use super::{Encoders, RobotBase};
+#[cfg(feature = "encoders")]
+use crate::delay_ms;
+use crate::interrupt::{self, mutex::Mutex};
+
+/// Geometry of the robot's drive, see `Drive::set_geometry`.
+#[derive(Clone, Copy)]
+struct Geometry {
+    /// Distance between the centers of the tracks in mm.
+    wheelbase_mm: u16,
+    /// Speed of a track in mm/s when driven with `Motors::MAX_SPEED`.
+    max_speed_mm_per_s: u16,
+}
+
+/// Geometry of the robot's drive, see `Drive::set_geometry`.
+static GEOMETRY: Mutex<Geometry> = Mutex::new(Geometry {
+    wheelbase_mm: Drive::DEFAULT_WHEELBASE_MM,
+    max_speed_mm_per_s: Drive::DEFAULT_MAX_SPEED_MM_PER_S,
+});
+
+/// Struct driving the robot as a whole via differential-drive kinematics, on top of `Motors`.
+pub struct Drive;
+
+impl Drive {
+    /// Nominal distance between the centers of the tracks of the RP6 in mm.
+    pub const DEFAULT_WHEELBASE_MM: u16 = 145;
+
+    /// Nominal speed of the RP6 in mm/s when driven with `Motors::MAX_SPEED` at full battery.
+    pub const DEFAULT_MAX_SPEED_MM_PER_S: u16 = 250;
+
+    /// Motor speed at which `Drive::calibrate_distance` drives, slow enough to not slip.
+    #[cfg(feature = "encoders")]
+    pub const CALIBRATION_SPEED: i16 = 60;
+
+    /// Period in ms at which `Drive::calibrate_distance` corrects the direction.
+    #[cfg(feature = "encoders")]
+    const CALIBRATION_PERIOD_MS: u16 = 10;
+
+    /// Time in ms after which `Drive::calibrate_distance` gives up if an encoder does not advance.
+    #[cfg(feature = "encoders")]
+    const CALIBRATION_STALL_MS: u16 = 500;
+
+    /// Set the geometry used by `Drive::set_velocity`: the distance `wheelbase_mm` between the
+    /// centers of the tracks and the speed `max_speed_mm_per_s` of a track when driven with
+    /// `Motors::MAX_SPEED`. Both vary per robot (and the latter with the battery voltage and the
+    /// floor), so measure them for accurate turns, e.g., by timing a straight run at full speed.
+    /// Zero values are replaced by 1.
+    pub fn set_geometry(wheelbase_mm: u16, max_speed_mm_per_s: u16) {
+        interrupt::without_interrupts(|cs| {
+            GEOMETRY.lock(cs).set(Geometry {
+                wheelbase_mm: wheelbase_mm.max(1),
+                max_speed_mm_per_s: max_speed_mm_per_s.max(1),
+            })
+        });
+    }
+
+    /// Drive with the `linear` speed in mm/s (positive is forwards) while turning at the
+    /// `angular` rate in mrad/s (positive is counterclockwise, i.e., to the left), see
+    /// `Drive::mix`. The speeds of the tracks are converted to motor speeds proportionally to the
+    /// geometry set via `Drive::set_geometry`, i.e., without closed-loop control.
+    pub fn set_velocity(linear: i16, angular: i16) {
+        let geometry = interrupt::without_interrupts(|cs| GEOMETRY.lock(cs).get());
+        let (left, right) = Self::mix(
+            linear,
+            angular,
+            geometry.wheelbase_mm,
+            geometry.max_speed_mm_per_s,
+        );
+        Motors::drive(left, right);
+    }
+
+    /// Measure the encoder ticks per mm of the robot, which vary per robot, e.g., with the tension
+    /// of the tracks, and are needed for accurate odometry. Returns the ticks per mm in units of
+    /// 1/100 tick, i.e., a result of 425 means 4.25 ticks per mm, or `None` if the robot did not
+    /// move or hit the wall right away.
+    ///
+    /// Procedure:
+    /// 1. Place the robot on the floor used later, facing a wall (or any other obstacle that
+    ///    triggers the bumpers) squarely, and measure the distance between the front of the
+    ///    bumpers and the wall as precisely as possible, e.g., 1000mm.
+    /// 2. Call this routine with that distance as `known_mm`. The robot drives straight at
+    ///    `CALIBRATION_SPEED` until a bumper hits the wall, then brakes (see `Motors::brake`) and
+    ///    computes the result from the ticks of both encoders.
+    /// 3. Repeat a few times and average the results, then store the value persistently, e.g., via
+    ///    `Eeprom::write(address, &ticks_per_mm.to_le_bytes())`, and load it at startup.
+    ///
+    /// To drive straight, the speeds of the tracks are corrected every `CALIBRATION_PERIOD_MS`
+    /// proportionally to the difference of their ticks. The routine aborts without a result if an
+    /// encoder does not advance for `CALIBRATION_STALL_MS`, e.g., because the robot is stuck.
+    /// Initializes the encoders via `Encoders::init`. Blocks until the wall is reached. Only
+    /// available with the feature `encoders`.
+    #[cfg(feature = "encoders")]
+    pub fn calibrate_distance(known_mm: u16) -> Option<u16> {
+        if known_mm == 0 || RobotBase::bumpers() != (false, false) {
+            return None;
+        }
+
+        Encoders::init();
+        let mut last_counts = (0, 0);
+        let mut stalled_ms = 0;
+        let counts = loop {
+            let (left, right) = Encoders::counts();
+
+            // slow down the track that is ahead
+            let correction = ((left as i32 - right as i32) * 2).clamp(
+                -i32::from(Self::CALIBRATION_SPEED),
+                i32::from(Self::CALIBRATION_SPEED),
+            ) as i16;
+            Motors::drive(
+                Self::CALIBRATION_SPEED - correction.max(0),
+                Self::CALIBRATION_SPEED + correction.min(0),
+            );
+
+            if RobotBase::bumpers() != (false, false) {
+                break Some((left, right));
+            }
+
+            if left == last_counts.0 || right == last_counts.1 {
+                stalled_ms += Self::CALIBRATION_PERIOD_MS;
+                if stalled_ms >= Self::CALIBRATION_STALL_MS {
+                    break None;
+                }
+            } else {
+                stalled_ms = 0;
+                last_counts = (left, right);
+            }
+            delay_ms(Self::CALIBRATION_PERIOD_MS.into());
+        };
+        Motors::brake();
+
+        let (left, right) = counts?;
+        let ticks = (left + right) / 2;
+        let ticks_per_mm = (ticks * 100 + u32::from(known_mm) / 2) / u32::from(known_mm);
+        Some(ticks_per_mm.min(u16::MAX.into()) as u16)
+    }
+
+    /// Compute the motor speeds `(left, right)` for `Motors::drive` that realize the `linear`
+    /// speed in mm/s and the `angular` rate in mrad/s by the differential-drive kinematics
+    /// ```text
+    /// left  = linear - angular * wheelbase_mm / 2000
+    /// right = linear + angular * wheelbase_mm / 2000
+    /// ```
+    /// where both track speeds in mm/s are scaled by `Motors::MAX_SPEED / max_speed_mm_per_s`.
+    ///
+    /// If a track would exceed `Motors::MAX_SPEED`, both are scaled down by the same factor, so
+    /// that the faster track runs at `MAX_SPEED` and the ratio of the track speeds, i.e., the
+    /// radius of the curve, is preserved. Only the robot's speed along the curve is reduced.
+    pub fn mix(
+        linear: i16,
+        angular: i16,
+        wheelbase_mm: u16,
+        max_speed_mm_per_s: u16,
+    ) -> (i16, i16) {
+        let turn = i32::from(angular) * i32::from(wheelbase_mm) / 2000;
+        let scale = |speed_mm_per_s: i32| {
+            speed_mm_per_s * i32::from(Motors::MAX_SPEED) / i32::from(max_speed_mm_per_s.max(1))
+        };
+        let left = scale(i32::from(linear) - turn);
+        let right = scale(i32::from(linear) + turn);
+
+        // saturate both tracks by the same factor to preserve the turn ratio, where the products
+        // may exceed `i32` for extreme inputs
+        let max = i64::from(Motors::MAX_SPEED);
+        let largest = i64::from(left.abs().max(right.abs()));
+        if largest > max {
+            let saturate = |speed: i32| (i64::from(speed) * max / largest) as i16;
+            (saturate(left), saturate(right))
+        } else {
+            (left as i16, right as i16)
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use super::port::{Enc_L, Enc_R};
+use crate::{
+    avr::{
+        bitmasks::{INT0, INT1, ISC0, ISC00, ISC1, ISC10},
+        registers::{GICR, MCUCR},
+    },
+    interrupt::{self, mutex::Mutex},
+    Pin, Register,
+};
+use avr_macros::interrupt;
+
+/// Number of edges counted on `Enc_L` since the last reset.
+static LEFT_TICKS: Mutex<u32> = Mutex::new(0);
+
+/// Number of edges counted on `Enc_R` since the last reset.
+static RIGHT_TICKS: Mutex<u32> = Mutex::new(0);
+
+/// Struct managing the single-channel encoders of the robot base's tracks on `Enc_L` (`INT0`) and
+/// `Enc_R` (`INT1`). Each edge of an encoder signal counts one tick, regardless of the direction of
+/// the track, which the single-channel encoders cannot sense.
+///
+/// Only available with the feature `encoders`, as the encoders claim the `INT0` and `INT1`
+/// interrupts, so that these are not available for other purposes, e.g., a `QuadratureDecoder`.
+pub struct Encoders;
+
+impl Encoders {
+    /// Configure the encoder pins as inputs and enable the `INT0` and `INT1` interrupts on any
+    /// logical change, as the original RP6Lib does. The counts are reset.
+    pub fn init() {
+        Enc_L::set_input();
+        Enc_R::set_input();
+        interrupt::without_interrupts(|cs| {
+            LEFT_TICKS.lock(cs).set(0);
+            RIGHT_TICKS.lock(cs).set(0);
+            MCUCR::write(MCUCR::read() & !(ISC0 | ISC1) | ISC00 | ISC10);
+            GICR::set_mask_raw(INT0 | INT1);
+        });
+    }
+
+    /// Check if the encoders are counting, i.e., if `Encoders::init` enabled their interrupts.
+    pub fn is_enabled() -> bool {
+        GICR::is_mask_set_raw(INT0 | INT1)
+    }
+
+    /// The ticks counted on the left and right encoder since the last reset.
+    pub fn counts() -> (u32, u32) {
+        interrupt::without_interrupts(|cs| (LEFT_TICKS.lock(cs).get(), RIGHT_TICKS.lock(cs).get()))
+    }
+
+    /// Reset the counts of both encoders to zero.
+    pub fn reset() {
+        interrupt::without_interrupts(|cs| {
+            LEFT_TICKS.lock(cs).set(0);
+            RIGHT_TICKS.lock(cs).set(0);
+        });
+    }
+}
+
+#[interrupt]
+fn INT0() {
+    interrupt::without_interrupts(|cs| LEFT_TICKS.lock(cs).update(|ticks| ticks.wrapping_add(1)));
+}
+
+#[interrupt]
+fn INT1() {
+    interrupt::without_interrupts(|cs| RIGHT_TICKS.lock(cs).update(|ticks| ticks.wrapping_add(1)));
+}
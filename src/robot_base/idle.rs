@@ -0,0 +1,47 @@
+use super::RobotBase;
+use crate::interrupt::{self, mutex::Mutex};
+
+/// Hook invoked while blocking primitives wait, see `RobotBase::set_idle_hook`.
+static IDLE_HOOK: Mutex<Option<fn()>> = Mutex::new(None);
+
+/// Whether the idle hook is currently running, to prevent invoking it recursively.
+static IN_IDLE_HOOK: Mutex<bool> = Mutex::new(false);
+
+impl RobotBase {
+    /// Set a hook that is invoked repeatedly while blocking primitives of this crate wait instead
+    /// of just spinning, e.g., `time::delay_ms_ticked` or `Serial::read_byte`, so that the main
+    /// loop can, e.g., feed the watchdog or toggle a heartbeat LED while idle. Replaces a
+    /// previously set hook. Primitives that put the device to sleep instead of spinning, e.g.,
+    /// `power::sleep_until`, do not invoke the hook.
+    ///
+    /// The hook is invoked from the main loop with interrupts enabled, possibly many times per
+    /// millisecond, so it should return quickly and must not block. If the hook itself calls a
+    /// blocking primitive, the hook is not invoked again until it has returned, i.e., it is never
+    /// re-entered. The hook must not be invoked from an interrupt handler.
+    pub fn set_idle_hook(hook: fn()) {
+        interrupt::without_interrupts(|cs| IDLE_HOOK.lock(cs).set(Some(hook)));
+    }
+
+    /// Remove the hook set via `RobotBase::set_idle_hook`.
+    pub fn clear_idle_hook() {
+        interrupt::without_interrupts(|cs| IDLE_HOOK.lock(cs).set(None));
+    }
+
+    /// Invoke the idle hook, if any, unless it is already running. Called by the blocking
+    /// primitives of this crate on each iteration of their wait loop, and may be called by custom
+    /// wait loops as well.
+    pub fn idle() {
+        let hook = interrupt::without_interrupts(|cs| {
+            let hook = IDLE_HOOK.lock(cs).get();
+            if hook.is_some() && IN_IDLE_HOOK.lock(cs).replace(true) {
+                return None;
+            }
+            hook
+        });
+
+        if let Some(hook) = hook {
+            hook();
+            interrupt::without_interrupts(|cs| IN_IDLE_HOOK.lock(cs).set(false));
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use super::{port::ResetButton, Motors, RobotBase};
+use crate::{
+    avr::{
+        bitmasks::{CS10, RXEN, TXEN},
+        registers::{TCCR1B, UBRRH, UBRRL, UCSRB},
+        timers::Timer1,
+    },
+    Pin, Register, UBRR_BAUD_LOW,
+};
+
+/// Reasons why `RobotBase::try_init` detected that the hardware does not behave as configured, or
+/// why `RobotBase::init_with_power_on_check` refused to enable the motors.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InitError {
+    /// The baudrate or the enabled receiver and transmitter of the UART do not read back.
+    Uart,
+    /// The reset button reads as pressed, i.e., it is stuck or the reset line is shorted to ground.
+    ResetButton,
+    /// The configuration of `Timer1` generating the motor PWM does not read back.
+    Motors,
+    /// The battery voltage did not reach the threshold of `RobotBase::init_with_power_on_check`
+    /// in time, so the motors were not enabled.
+    LowBattery,
+}
+
+impl RobotBase {
+    /// Initialize the robot like `RobotBase::init` and verify afterwards that the hardware behaves
+    /// as configured, e.g., by reading back the UART's baudrate and checking that the reset button
+    /// is released. Returns the first failed check in the order of initialization, so that a robot
+    /// with a hardware fault can fail fast with a reason. Note that the initialization itself is
+    /// always completed, i.e., the robot is never left half-initialized.
+    pub fn try_init() -> Result<(), InitError> {
+        Self::init();
+        Self::verify_uart()?;
+        Self::verify_reset_button()?;
+        Self::verify_motors()
+    }
+
+    /// Check that the UART runs with `UBRR_BAUD_LOW` and its receiver and transmitter enabled.
+    fn verify_uart() -> Result<(), InitError> {
+        let ubrr = u32::from(UBRRH::read()) << 8 | u32::from(UBRRL::read());
+        if ubrr == UBRR_BAUD_LOW && UCSRB::is_mask_set_raw(TXEN | RXEN) {
+            Ok(())
+        } else {
+            Err(InitError::Uart)
+        }
+    }
+
+    /// Check that the reset button, which is pulled up externally, is not pressed.
+    fn verify_reset_button() -> Result<(), InitError> {
+        if ResetButton::is_high() {
+            Ok(())
+        } else {
+            Err(InitError::ResetButton)
+        }
+    }
+
+    /// Check that `Timer1` is configured to generate the motor PWM.
+    fn verify_motors() -> Result<(), InitError> {
+        let (_, control_b) = Motors::pwm_mode().waveform_bits();
+        if TCCR1B::read() == control_b | CS10 && Timer1::read_icr1() == Motors::MAX_SPEED.into() {
+            Ok(())
+        } else {
+            Err(InitError::Motors)
+        }
+    }
+}
@@ -1,7 +1,19 @@
 //! Module for general interaction with the specific systems installed on the RP6's robot base.
 
 use super::Serial;
-use crate::{avr::registers, interrupt, set_pins, Pin, Register};
+use crate::{
+    avr::{
+        registers,
+        reset::{self, ResetCause},
+    },
+    delay_ms, delay_us,
+    input::{ActiveLevel, Button},
+    interrupt::{
+        self,
+        mutex::{DynamicMutex, Mutex},
+    },
+    Pin, Register,
+};
 
 /// Module binding pins to their device-specific function names.
 pub mod port;
@@ -10,17 +22,80 @@ use port::*;
 /// Module allowing for simple use of the robot's Anti-Collision System.
 pub mod acs;
 
+/// Module implementing the hook invoked by blocking primitives while they wait.
+mod idle;
+
+/// Module verifying the initialization of the robot.
+mod init;
+pub use init::InitError;
+
+/// Module checking the battery before enabling the motors.
+mod power_on;
+pub use power_on::PowerOnCheck;
+
+/// Module driving the robot via differential-drive kinematics.
+pub mod drive;
+pub use drive::Drive;
+
+/// Module counting the ticks of the robot's encoders, see the feature `encoders`.
+#[cfg(feature = "encoders")]
+pub mod encoders;
+#[cfg(feature = "encoders")]
+pub use encoders::Encoders;
+
+/// Module controlling the robot's drive motors.
+pub mod motors;
+pub use motors::{Motors, PwmMode};
+
 /// Struct managing all actions regarding the robot's base.
 pub struct RobotBase;
 
+/// Cause of the last reset, determined by `RobotBase::init`.
+static RESET_CAUSE: Mutex<ResetCause> = Mutex::new(ResetCause::Unknown);
+
+/// Debounced state of the reset button when used as user button, see
+/// `RobotBase::reset_button_pressed`. The button is pulled up externally, i.e., it is active low.
+static RESET_BUTTON: DynamicMutex<Button<ResetButton>> =
+    DynamicMutex::new(Button::new(ActiveLevel::Low));
+
+/// Ensure at compile time that the configured CPU frequency matches the stock RP6, as the baudrate
+/// and delay computations silently miscalibrate otherwise.
+#[cfg(feature = "stock-cpu-frequency")]
+const _: () = assert!(
+    crate::avr::config::CPU_FREQUENCY_HZ == RobotBase::CPU_FREQUENCY_HZ,
+    "The stock RP6 runs at 8MHz: set the environment variable `AVR_CPU_FREQUENCY_HZ=8000000` (e.g., \
+     in `.cargo/config.toml`) or disable the feature `stock-cpu-frequency` for a modified clock."
+);
+
 impl RobotBase {
+    /// CPU frequency of the stock RP6 robot base.
+    pub const CPU_FREQUENCY_HZ: u32 = 8_000_000;
+
+    /// Initialize the robot base: ports, UART and motors, and enable global interrupts. See `RobotBase::try_init` for a variant
+    /// that verifies the hardware afterwards, and `RobotBase::init_with_power_on_check` for a
+    /// variant that checks the battery before enabling the motors.
     pub fn init() {
+        Self::init_without_motors();
+        interrupt::without_interrupts(|_| Motors::init());
+    }
+
+    /// Initialize the ports and the UART and enable global interrupts like `RobotBase::init`, but
+    /// leave the motors stopped by `Motors::safe_init`, i.e., with their PWM disabled.
+    fn init_without_motors() {
+        // Stop the motors before anything else, in case the reset happened during motion.
+        Motors::safe_init();
+
         // Setup port directions and initial values.
         // THIS IS THE MOST IMPORTANT STEP!
         Self::init_ports();
 
         // Disable global interrupts
-        interrupt::without_interrupts(|_| {
+        interrupt::without_interrupts(|cs| {
+            // Remember the cause of the reset and clear the flags to detect the next reset.
+            let reset_cause = reset::reset_cause();
+            reset::clear_reset_flags();
+            RESET_CAUSE.lock(cs).set(reset_cause);
+
             // Make sure the Reset Button is enabled!
             // Do not disable it if you want to be able to
             // reset your robot! (Otherwise you can only
@@ -33,6 +108,10 @@ impl RobotBase {
             Self::set_acs_power_off();
 
             Serial::init();
+            if reset_cause == ResetCause::BrownOut {
+                Serial::write("\nBrown-out reset: check the battery!\n");
+            }
+
             /*
             // Initialize ADC:
             ADMUX = 0; //external reference
@@ -50,24 +129,6 @@ impl RobotBase {
                     | (0 << CS02)  | (1 << CS01) | (0 << CS00);
             OCR0  = 99;
 
-            // Initialize Timer1 - PWM:
-            // PWM, phase correct with ICR1 as top value.
-            TCCR1A = (0 << WGM10) | (1 << WGM11) | (1 << COM1A1) | (1 << COM1B1);
-            TCCR1B =  (1 << WGM13) | (0 << WGM12) | (1 << CS10);
-            ICR1 = 210; // Phase corret PWM top value - 210 results in
-                        // about 19 kHz PWM.
-                        // ICR1 is the maximum (=100% duty cycle) PWM value!
-                        // This means that the PWM resolution is a bit lower, but
-                        // if the frequency is lower than 19 kHz you may hear very
-                        // annoying high pitch noises from the motors!
-                        // 19 kHz is a bit over the maximum frequency most people can
-                        // hear!
-                        //
-                        // ATTENTION: Max PWM value is 210 and NOT 255 !!!
-            OCR1AL = 0;
-            OCR1BL = 0;
-            setMotorDir(FWD,FWD); 	// Direction Forwards
-
             // Initialize Timer2 - ACS:
             TCCR2 = (1 << WGM21) | (0 << COM20) | (1 << CS20);
             OCR2  = 0x6E; // 0x6E = 72kHz @8MHz
@@ -81,7 +142,16 @@ impl RobotBase {
             sysStatACS.channel = ACS_CHANNEL_RIGHT;
             acs_state = ACS_STATE_IRCOMM_DELAY;
                */
-        }); // Enable Global Interrupts
+        });
+
+        // interrupts are disabled after a reset, and `CriticalSection`s restore that state
+        interrupt::enable();
+    }
+
+    /// Cause of the last reset as determined by `RobotBase::init`, e.g., `ResetCause::BrownOut` if
+    /// the battery voltage sagged too low.
+    pub fn reset_cause() -> ResetCause {
+        interrupt::without_interrupts(|cs| RESET_CAUSE.lock(cs).get())
     }
 
     /// Initializes the IO ports of the robot.
@@ -120,16 +190,225 @@ impl RobotBase {
         ResetButton::set_output();
     }
 
+    /// Check if the reset button is currently held, debounced via `millis`, see `input::Button`.
+    /// This allows to repurpose the button as a user button. Requires the system tick, see
+    /// `time::init_tick`, and regular polling.
+    ///
+    /// The button pulls `ResetButton` (b5) low while pressed, which is only observable while the
+    /// button is enabled via `enable_reset_button` (as after `RobotBase::init`). Always returns
+    /// `false` while it is disabled via `disable_reset_button`, which drives `ResetButton` low.
+    /// Note that the button keeps its role as hardware reset: if it is wired to the reset line of
+    /// the microcontroller, a press resets the robot before it can be read here. Hence, only a
+    /// button that has been disconnected from the reset line can be used as user button.
+    pub fn reset_button_pressed() -> bool {
+        Self::is_reset_button_enabled()
+            && interrupt::without_interrupts(|cs| RESET_BUTTON.lock(cs).borrow_mut().is_held())
+    }
+
+    /// Check if the reset button has been pressed since the last call, i.e., each debounced press
+    /// is reported exactly once. See `RobotBase::reset_button_pressed` for the requirements.
+    pub fn reset_button_was_pressed() -> bool {
+        Self::is_reset_button_enabled()
+            && interrupt::without_interrupts(|cs| RESET_BUTTON.lock(cs).borrow_mut().was_pressed())
+    }
+
+    /// Check if `ResetButton` is configured as input, see `enable_reset_button`.
+    fn is_reset_button_enabled() -> bool {
+        !registers::DDRB::is_mask_set_raw(ResetButton::MASK)
+    }
+
     /// Disable the IRCOMM of the robot.
     pub fn disable_ircomm() {
         IRComm::set_low();
     }
 
-    /// Set the LEDs on the `RobotBase` to the least significant 6 bits of the provided value
+    /// Set the LEDs on the `RobotBase` to the least significant 6 bits of the provided value, where
+    /// bit 0 corresponds to SL1. The target values of PORTC (SL1-SL3) and PORTB (SL4-SL6) are
+    /// computed upfront, so that each port is written only once, while its remaining bits, e.g.,
+    /// of the ACS, `PowerOn`, `SCL`/`SDA` and the motor directions, are preserved.
+    ///
+    /// The LED pins are always configured as outputs in `DDRB`/`DDRC`, as they may have been
+    /// switched to inputs, e.g., by `RobotBase::bumpers`. The other bits of `DDRB`/`DDRC` are not
+    /// changed.
     pub fn set_leds(value: u8) {
-        // set LEDs SL1-SL3
-        set_pins!([Led3, Led2, Led1], value);
-        // set LEDs SL4-SL6
-        set_pins!([Led6, Led5, Led4], value >> 3);
+        const LEDS_C: u8 = Led1::MASK | Led2::MASK | Led3::MASK;
+        const LEDS_B: u8 = Led4::MASK | Led5::MASK | Led6::MASK;
+
+        let leds_c = Self::led_mask::<Led1>(value, 0)
+            | Self::led_mask::<Led2>(value, 1)
+            | Self::led_mask::<Led3>(value, 2);
+        let leds_b = Self::led_mask::<Led4>(value, 3)
+            | Self::led_mask::<Led5>(value, 4)
+            | Self::led_mask::<Led6>(value, 5);
+
+        registers::DDRC::set_mask_raw(LEDS_C);
+        registers::PORTC::write(registers::PORTC::read() & !LEDS_C | leds_c);
+        registers::DDRB::set_mask_raw(LEDS_B);
+        registers::PORTB::write(registers::PORTB::read() & !LEDS_B | leds_b);
+    }
+
+    /// Check if the left and right bumpers are pressed. The bumpers share their pins with the LEDs
+    /// SL6 (left) and SL3 (right): each pin is briefly switched to an input and driven high by its
+    /// bumper while pressed. Afterwards, the LED is restored.
+    pub fn bumpers() -> (bool, bool) {
+        interrupt::without_interrupts(|_| {
+            (
+                Self::read_bumper::<registers::DDRB, registers::PORTB, registers::PINB>(Led6::MASK),
+                Self::read_bumper::<registers::DDRC, registers::PORTC, registers::PINC>(Led3::MASK),
+            )
+        })
+    }
+
+    /// Read the bumper on the LED pin given by `mask` of the port with the registers `DDR`, `PORT`
+    /// and `PIN`, restoring the pin's configuration afterwards.
+    fn read_bumper<DDR: Register<T = u8>, PORT: Register<T = u8>, PIN: Register<T = u8>>(
+        mask: u8,
+    ) -> bool {
+        let ddr = DDR::read() & mask;
+        let port = PORT::read() & mask;
+        PORT::unset_mask_raw(mask);
+        DDR::unset_mask_raw(mask);
+        delay_us(1);
+        let pressed = PIN::is_mask_set_raw(mask);
+        DDR::set_mask_raw(ddr);
+        PORT::set_mask_raw(port);
+        pressed
+    }
+
+    /// The mask of the LED pin `P` if `bit` is set in `value`, or 0 otherwise.
+    #[inline(always)]
+    fn led_mask<P: Pin>(value: u8, bit: u8) -> u8 {
+        if value & (1 << bit) != 0 {
+            P::MASK
+        } else {
+            0
+        }
+    }
+
+    /// Turn the single LED with the given `index` on or off, where index 0 corresponds to SL1 and
+    /// index 5 to SL6. The other LEDs are not changed. Indices above 5 are ignored.
+    pub fn set_led(index: u8, on: bool) {
+        match index {
+            0 => Self::set_led_pin::<Led1>(on),
+            1 => Self::set_led_pin::<Led2>(on),
+            2 => Self::set_led_pin::<Led3>(on),
+            3 => Self::set_led_pin::<Led4>(on),
+            4 => Self::set_led_pin::<Led5>(on),
+            5 => Self::set_led_pin::<Led6>(on),
+            _ => {}
+        }
+    }
+
+    /// Drive the LED pin `P`, which may have been switched to an input, e.g., to read the bumpers.
+    fn set_led_pin<P: Pin>(on: bool) {
+        P::set_output();
+        if on {
+            P::set_high();
+        } else {
+            P::set_low();
+        }
+    }
+
+    /// Cycle through the LEDs, turning each on individually for a short time, and turn all of them
+    /// off afterwards. A bringup aid to visually confirm at power-on that all six LEDs work, e.g.,
+    /// called directly after `RobotBase::init`.
+    pub fn led_selftest() {
+        Self::set_leds(0b000000);
+        for index in 0..6 {
+            Self::set_led(index, true);
+            delay_ms(100);
+            Self::set_led(index, false);
+        }
+    }
+
+    /// Set the LEDs on the `RobotBase` to the least significant 6 bits of the provided value, like
+    /// `set_leds`, but without being interrupted. As the LEDs span PORTB and PORTC, `set_leds`
+    /// performs two read-modify-write operations, which may tear or overwrite port changes of an
+    /// interrupt handler, e.g., the ACS on PORTB. Use this variant if interrupt handlers also
+    /// modify PORTB or PORTC.
+    pub fn set_leds_atomic(value: u8) {
+        interrupt::without_interrupts(|_| Self::set_leds(value));
+    }
+
+    /// Halt the robot in a safe state, e.g., after a fatal error: interrupts are disabled, the
+    /// motors are stopped via `Motors::emergency_stop`, the ACS is turned off, and the LEDs blink
+    /// the pattern `0b001001` forever. Only a reset via the reset button recovers the robot.
+    pub fn halt() -> ! {
+        // the `CriticalSection` is never left, so interrupts stay disabled
+        let _cs = unsafe { interrupt::CriticalSection::new() };
+        Motors::emergency_stop();
+        Self::set_acs_power_off();
+        loop {
+            Self::set_leds(0b001001);
+            delay_ms(250);
+            Self::set_leds(0b000000);
+            delay_ms(250);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{port::*, RobotBase};
+    use crate::{
+        avr::{
+            mock,
+            registers::{DDRB, DDRC, PORTB, PORTC},
+        },
+        Pin, Register,
+    };
+
+    const LEDS_B: u8 = Led4::MASK | Led5::MASK | Led6::MASK;
+    const LEDS_C: u8 = Led1::MASK | Led2::MASK | Led3::MASK;
+
+    #[test]
+    fn init_enables_interrupts() {
+        let _guard = mock::lock_global_state();
+        mock::reset();
+        RobotBase::init();
+        assert!(mock::interrupts_enabled());
+    }
+
+    #[test]
+    fn set_leds_preserves_the_other_pins() {
+        mock::reset();
+        // the ACS, `PowerOn`, `SCL`/`SDA` and the motor directions are outputs driven high
+        let others_b = ACS::MASK | ACS_PwrH::MASK | PowerOn::MASK | ACS_L::MASK;
+        let others_c = SCL::MASK | SDA::MASK | Dir_L::MASK | Dir_R::MASK | ACS_R::MASK;
+        mock::preset::<DDRB>(others_b);
+        mock::preset::<PORTB>(others_b);
+        mock::preset::<DDRC>(others_c);
+        mock::preset::<PORTC>(others_c);
+
+        RobotBase::set_leds(0b101010);
+
+        assert_eq!(DDRB::read(), others_b | LEDS_B);
+        assert_eq!(PORTB::read(), others_b | Led4::MASK | Led6::MASK);
+        assert_eq!(DDRC::read(), others_c | LEDS_C);
+        assert_eq!(PORTC::read(), others_c | Led2::MASK);
+    }
+
+    #[test]
+    fn set_leds_clears_the_leds_only() {
+        mock::reset();
+        mock::preset::<DDRB>(0xFF);
+        mock::preset::<PORTB>(0xFF);
+        mock::preset::<DDRC>(0xFF);
+        mock::preset::<PORTC>(0xFF);
+
+        RobotBase::set_leds(0);
+
+        assert_eq!(DDRB::read(), 0xFF);
+        assert_eq!(PORTB::read(), !LEDS_B);
+        assert_eq!(DDRC::read(), 0xFF);
+        assert_eq!(PORTC::read(), !LEDS_C);
+    }
+
+    #[test]
+    fn set_leds_writes_each_port_once() {
+        mock::reset();
+        RobotBase::set_leds(0b111111);
+        assert_eq!(mock::take_writes::<PORTB>(), [LEDS_B]);
+        assert_eq!(mock::take_writes::<PORTC>(), [LEDS_C]);
     }
 }
@@ -0,0 +1,317 @@
+use super::port::{Dir_L, Dir_R, Motor_L, Motor_R};
+use crate::{
+    avr::{
+        bitmasks::{COM1A1, COM1B1, CS10, WGM11, WGM12, WGM13},
+        config::CPU_FREQUENCY_HZ,
+        registers::{DDRD, OCR1AH, OCR1AL, OCR1BH, OCR1BL, PORTD, TCCR1A, TCCR1B},
+        timers::Timer1,
+    },
+    delay_ms,
+    interrupt::{self, mutex::Mutex},
+    Pin, Register,
+};
+
+/// Minimum PWM value of nonzero speeds, see `Motors::set_deadband`.
+static DEADBAND: Mutex<u8> = Mutex::new(0);
+
+/// PWM mode of `Timer1`, see `Motors::set_pwm_mode`.
+static PWM_MODE: Mutex<PwmMode> = Mutex::new(PwmMode::PhaseCorrect);
+
+/// The PWM mode of `Timer1` generating the motor PWM. In both modes, `ICR1` holds the top value
+/// `Motors::MAX_SPEED` and the output compare registers `OCR1A`/`OCR1B` hold the PWM values, so that
+/// a PWM value of `MAX_SPEED` drives the output constantly high, i.e., the duty cycle is
+/// `pwm / MAX_SPEED` in both modes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PwmMode {
+    /// Mode 10 of `Timer1`: the counter runs up to `ICR1` and back down, setting the output on the
+    /// compare match while counting down and clearing it while counting up. The pulses are
+    /// centered within each period, and new PWM values only take effect at the top, so that no
+    /// glitches occur. Results in about 19kHz at 8MHz. This is the default.
+    PhaseCorrect,
+    /// Mode 14 of `Timer1`: the counter runs up to `ICR1` and restarts at zero, setting the output
+    /// at zero and clearing it on the compare match. Twice the frequency of `PhaseCorrect`, i.e.,
+    /// about 38kHz at 8MHz, which reduces the current ripple in the motors. New PWM values take
+    /// effect at zero. Note that a PWM value of 0 still produces a spike of a single timer cycle
+    /// per period, which is far too short to move the motors.
+    Fast,
+}
+
+impl PwmMode {
+    /// Frequency of the PWM signal in Hz.
+    pub const fn frequency_hz(self) -> u32 {
+        let top = Motors::MAX_SPEED as u32;
+        match self {
+            PwmMode::PhaseCorrect => CPU_FREQUENCY_HZ / (2 * top),
+            PwmMode::Fast => CPU_FREQUENCY_HZ / (top + 1),
+        }
+    }
+
+    /// The waveform generation mode bits of `TCCR1A` and `TCCR1B` selecting this mode with `ICR1`
+    /// as top value. The bits `WGM10` (`TCCR1A`) and, for `PhaseCorrect`, `WGM12` (`TCCR1B`) are
+    /// cleared, as they would select `OCR1A` as top value or a mode with fixed top value.
+    pub(super) const fn waveform_bits(self) -> (u8, u8) {
+        match self {
+            PwmMode::PhaseCorrect => (WGM11, WGM13),
+            PwmMode::Fast => (WGM11, WGM13 | WGM12),
+        }
+    }
+}
+
+/// Struct managing the two drive motors of the robot base. The motors are driven by `Timer1` with
+/// `ICR1` as top value, in phase correct PWM mode by default (see `Motors::set_pwm_mode`), on the
+/// pins `Motor_L` (`OC1B`) and `Motor_R` (`OC1A`), while the pins `Dir_L` and `Dir_R` select their
+/// direction.
+pub struct Motors;
+
+impl Motors {
+    /// Maximum PWM value, i.e., 100% duty cycle. Note that this is 210 and NOT 255, which results
+    /// in a PWM frequency of about 19kHz at 8MHz. At lower frequencies, the motors emit an
+    /// annoyingly high pitched noise.
+    pub const MAX_SPEED: u8 = 210;
+
+    /// Duration of the reverse pulse applied by `Motors::brake` to a motor running at
+    /// `MAX_SPEED`. Slower motors are braked for a proportionally shorter time.
+    pub const BRAKE_PULSE_MS: u8 = 20;
+
+    /// Initialize `Timer1` to generate the motor PWM and stop both motors, setting their direction
+    /// to forwards. The PWM outputs `Motor_L` and `Motor_R` are claimed by `Timer1`, see
+    /// `Pin::claim`.
+    pub fn init() {
+        Self::safe_init();
+        Self::configure_timer(Self::pwm_mode(), 0, 0);
+        Motor_L::claim();
+        Motor_R::claim();
+    }
+
+    /// Select the PWM mode of `Timer1`, see `PwmMode`. If the motors are already initialized,
+    /// `Timer1` is reconfigured immediately, keeping the current PWM values and directions of the
+    /// motors. Otherwise, the mode takes effect with `Motors::init`.
+    ///
+    /// While reconfiguring, `Timer1` is stopped and restarted at zero, as changing the top value
+    /// `ICR1` (which is not double-buffered) while the counter is running could let the counter
+    /// miss the new top value and run up to `0xFFFF`.
+    pub fn set_pwm_mode(mode: PwmMode) {
+        interrupt::without_interrupts(|cs| {
+            PWM_MODE.lock(cs).set(mode);
+            if TCCR1B::is_mask_set_raw(CS10) {
+                // reading the output compare registers yields the last written PWM values
+                let (left, right) = (Timer1::read_ocr1b(), Timer1::read_ocr1a());
+                Self::configure_timer(mode, left, right);
+            }
+        });
+    }
+
+    /// The PWM mode of `Timer1`, see `set_pwm_mode`.
+    pub fn pwm_mode() -> PwmMode {
+        interrupt::without_interrupts(|cs| PWM_MODE.lock(cs).get())
+    }
+
+    /// Configure `Timer1` to generate the motor PWM in the given `mode` with the PWM values `left`
+    /// and `right`, and start it without prescaler.
+    fn configure_timer(mode: PwmMode, left: u16, right: u16) {
+        let (control_a, control_b) = mode.waveform_bits();
+        TCCR1B::write(0);
+        TCCR1A::write(control_a | COM1A1 | COM1B1);
+        Timer1::write_icr1(Self::MAX_SPEED.into());
+        Timer1::write_counter(0);
+        Timer1::write_ocr1b(left);
+        Timer1::write_ocr1a(right);
+        TCCR1B::write(control_b | CS10);
+    }
+
+    /// Force the motors into a safe, stopped state without enabling the PWM: the PWM outputs are
+    /// disconnected from `Timer1` and driven low, the PWM values are set to zero and the direction
+    /// is set to forwards. This is the first step of `RobotBase::init`, so that a reset during
+    /// motion, e.g., a brown-out reset due to a sagging battery, always leaves the robot stopped.
+    pub fn safe_init() {
+        TCCR1A::write(0);
+        Motor_L::release();
+        Motor_R::release();
+        Motor_L::set_low();
+        Motor_L::set_output();
+        Motor_R::set_low();
+        Motor_R::set_output();
+        Self::drive(0, 0);
+        Dir_L::set_output();
+        Dir_R::set_output();
+    }
+
+    /// Stop both motors immediately, e.g., from a panic handler or `RobotBase::halt`: the PWM
+    /// outputs are disconnected from `Timer1` and driven low, and the PWM values are set to zero.
+    ///
+    /// In contrast to the other functions of `Motors`, this is safe to call in any state, in
+    /// particular before `Motors::init` (with an unconfigured `Timer1`), while the PWM outputs
+    /// are claimed and within a `CriticalSection`: only registers are written, without checking
+    /// claims, taking locks, entering a `CriticalSection` or allocating resources. The direction
+    /// pins are left unchanged. Call `Motors::init` to drive the motors again.
+    ///
+    /// The bytes of `OCR1A`/`OCR1B` are written directly, high byte first. An interrupt handler
+    /// accessing another 16-bit register of `Timer1` in between may corrupt the high byte, which
+    /// does not affect the motors, as the PWM outputs are already disconnected.
+    pub fn emergency_stop() {
+        const MOTORS: u8 = Motor_L::MASK | Motor_R::MASK;
+        TCCR1A::write(0);
+        PORTD::unset_mask_raw(MOTORS);
+        DDRD::set_mask_raw(MOTORS);
+        OCR1BH::write(0);
+        OCR1BL::write(0);
+        OCR1AH::write(0);
+        OCR1AL::write(0);
+    }
+
+    /// Drive the motors with the given signed speeds, where the sign selects the direction
+    /// (positive is forwards) and the magnitude selects the PWM value. Magnitudes are clamped to
+    /// `Motors::MAX_SPEED` (210) and remapped according to the deadband, see `set_deadband`.
+    pub fn drive(left: i16, right: i16) {
+        let deadband = Self::deadband();
+        Self::set_direction::<Dir_L>(left);
+        Self::set_direction::<Dir_R>(right);
+        Timer1::write_ocr1b(Self::pwm(left, deadband).into());
+        Timer1::write_ocr1a(Self::pwm(right, deadband).into());
+    }
+
+    /// Set the minimum PWM value `min_pwm` at which the motors start to move, i.e., overcome their
+    /// friction. Any nonzero speed magnitude `s` (clamped to `MAX_SPEED`) is then linearly
+    /// remapped into `[min_pwm, MAX_SPEED]` by
+    /// ```text
+    /// pwm = min_pwm + (s - 1) * (MAX_SPEED - min_pwm) / (MAX_SPEED - 1)
+    /// ```
+    /// so that a speed of 1 results in `min_pwm` and `MAX_SPEED` stays `MAX_SPEED`, while a speed
+    /// of 0 still stops the motor. This lets small commands of a closed-loop controller actually
+    /// produce motion. Takes effect with the next call to `drive`. The default of 0 disables the
+    /// remapping. Values above `MAX_SPEED` are clamped.
+    pub fn set_deadband(min_pwm: u8) {
+        interrupt::without_interrupts(|cs| DEADBAND.lock(cs).set(min_pwm.min(Self::MAX_SPEED)));
+    }
+
+    /// The minimum PWM value of nonzero speeds, see `set_deadband`.
+    pub fn deadband() -> u8 {
+        interrupt::without_interrupts(|cs| DEADBAND.lock(cs).get())
+    }
+
+    /// Stop both motors, keeping their direction. Same as `Motors::coast`.
+    pub fn stop() {
+        Self::coast();
+    }
+
+    /// Let both motors coast to a stop: the PWM values are set to zero, so that the H-bridges no
+    /// longer drive the motors, while their direction is kept. On the RP6, the motor current then
+    /// decays through the freewheeling diodes of the H-bridges and the motors spin down freely,
+    /// only slowed by the friction of the gears. Depending on the speed, the robot keeps rolling
+    /// for a few centimeters.
+    pub fn coast() {
+        Timer1::write_ocr1b(0);
+        Timer1::write_ocr1a(0);
+    }
+
+    /// Actively brake both motors and leave them coasting afterwards, which stops the robot much
+    /// more precisely than `Motors::coast`. Blocks for up to `BRAKE_PULSE_MS` milliseconds.
+    ///
+    /// The H-bridges of the RP6 are controlled by a single PWM signal and a direction signal per
+    /// motor, so they cannot short the motor terminals as dedicated brake modes of other drivers
+    /// do. Instead, each running motor is braked by plugging: its direction is reversed and it is
+    /// driven with `MAX_SPEED` for a pulse proportional to its current PWM value, i.e., the motor
+    /// actively counteracts its momentum. The pulse is short enough that the motor does not start
+    /// to turn backwards. Afterwards, the PWM values are set to zero and the original directions
+    /// are restored.
+    pub fn brake() {
+        let left = Timer1::read_ocr1b().min(Self::MAX_SPEED.into());
+        let right = Timer1::read_ocr1a().min(Self::MAX_SPEED.into());
+        let pulse_ms =
+            |pwm: u16| pwm * u16::from(Self::BRAKE_PULSE_MS) / u16::from(Self::MAX_SPEED);
+        let (left_ms, right_ms) = (pulse_ms(left), pulse_ms(right));
+
+        // reverse the running motors at full power
+        if left_ms > 0 {
+            Dir_L::toggle();
+            Timer1::write_ocr1b(Self::MAX_SPEED.into());
+        }
+        if right_ms > 0 {
+            Dir_R::toggle();
+            Timer1::write_ocr1a(Self::MAX_SPEED.into());
+        }
+
+        // end the pulse of each motor after its own duration
+        for ms in 0..left_ms.max(right_ms) {
+            if ms == left_ms {
+                Timer1::write_ocr1b(0);
+            }
+            if ms == right_ms {
+                Timer1::write_ocr1a(0);
+            }
+            delay_ms(1);
+        }
+
+        // restore the original directions
+        Self::coast();
+        if left_ms > 0 {
+            Dir_L::toggle();
+        }
+        if right_ms > 0 {
+            Dir_R::toggle();
+        }
+    }
+
+    /// Set the direction pin `DIR` of a motor according to the sign of `speed`. The motor drives
+    /// backwards while its direction pin is high.
+    fn set_direction<DIR: Pin>(speed: i16) {
+        if speed < 0 {
+            DIR::set_high();
+        } else {
+            DIR::set_low();
+        }
+    }
+
+    /// Compute the PWM value corresponding to the magnitude of `speed`, remapped into
+    /// `[deadband, MAX_SPEED]` if nonzero, see `set_deadband`.
+    fn pwm(speed: i16, deadband: u8) -> u8 {
+        let speed = speed.unsigned_abs().min(Self::MAX_SPEED as u16);
+        if speed == 0 || deadband == 0 {
+            return speed as u8;
+        }
+        let max = u16::from(Self::MAX_SPEED);
+        let deadband = u16::from(deadband);
+        (deadband + (speed - 1) * (max - deadband) / (max - 1)) as u8
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        super::port::{Motor_L, Motor_R},
+        Motors,
+    };
+    use crate::{
+        avr::{
+            mock,
+            registers::{DDRD, OCR1AH, OCR1AL, OCR1BH, OCR1BL, PORTD, TCCR1A},
+        },
+        Pin, Register,
+    };
+
+    #[test]
+    fn emergency_stop_disconnects_and_zeroes_the_pwm() {
+        mock::reset();
+        mock::preset::<TCCR1A>(0xA2);
+        mock::preset::<PORTD>(0xFF);
+        mock::preset::<OCR1AH>(0x01);
+        mock::preset::<OCR1AL>(0x23);
+        mock::preset::<OCR1BH>(0x01);
+        mock::preset::<OCR1BL>(0x23);
+
+        Motors::emergency_stop();
+
+        assert_eq!(TCCR1A::read(), 0);
+        assert_eq!(PORTD::read(), !(Motor_L::MASK | Motor_R::MASK));
+        assert_eq!(DDRD::read(), Motor_L::MASK | Motor_R::MASK);
+        assert_eq!(
+            [
+                OCR1AH::read(),
+                OCR1AL::read(),
+                OCR1BH::read(),
+                OCR1BL::read()
+            ],
+            [0; 4]
+        );
+    }
+}
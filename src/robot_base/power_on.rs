@@ -0,0 +1,101 @@
+use super::{InitError, Motors, RobotBase};
+use crate::{avr::adc::Adc, delay_ms, interrupt};
+
+/// ADC channel of the battery voltage `UBAT`.
+const UBAT_CHANNEL: u8 = 7;
+
+/// Number of conversions averaged per reading of the battery voltage, to suppress noise.
+const UBAT_SAMPLES: u16 = 4;
+
+/// Period in ms at which the battery is checked, and the LEDs toggled, while waiting.
+const CHECK_PERIOD_MS: u16 = 250;
+
+/// LED pattern blinked while waiting for the battery voltage: SL1 and SL4, i.e., both rows.
+const WAITING_LEDS: u8 = 0b001001;
+
+/// Configuration of `RobotBase::init_with_power_on_check`.
+///
+/// The battery voltage is compared as raw ADC reading of `UBAT` against `AVCC`, as on the stock
+/// RP6: its voltage divider yields about one step per 10mV of battery voltage, i.e., a reading of
+/// about 720 for the nominal 7.2V of the stock pack of six NiMH cells. The exact factor varies
+/// with the tolerances of the divider and `AVCC`, so compare the readings with a multimeter for a
+/// precise threshold.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PowerOnCheck {
+    /// Time in ms to wait after initializing the ports and the UART before the battery is checked,
+    /// e.g., to let the supply settle after switching on, or to step back from the robot.
+    pub grace_period_ms: u16,
+    /// Lowest raw ADC reading of `UBAT` at which the motors are enabled.
+    pub min_ubat: u16,
+    /// Time in ms to wait for the battery voltage to reach `min_ubat` after the grace period, or
+    /// `None` to wait forever, e.g., until a charged pack is plugged into a powered robot.
+    pub timeout_ms: Option<u32>,
+}
+
+impl PowerOnCheck {
+    /// Default threshold of `min_ubat`: 600, i.e., about 6.0V or 1.0V per cell of the stock
+    /// pack of six NiMH cells, at which NiMH cells are considered empty. Below this voltage, the
+    /// motors' load currents may cause brown-out resets.
+    pub const DEFAULT_MIN_UBAT: u16 = 600;
+
+    /// Default grace period in ms.
+    pub const DEFAULT_GRACE_PERIOD_MS: u16 = 500;
+
+    /// Configuration with the default threshold and grace period, waiting forever.
+    pub const fn new() -> Self {
+        Self {
+            grace_period_ms: Self::DEFAULT_GRACE_PERIOD_MS,
+            min_ubat: Self::DEFAULT_MIN_UBAT,
+            timeout_ms: None,
+        }
+    }
+}
+
+impl Default for PowerOnCheck {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RobotBase {
+    /// Initialize the robot base like `RobotBase::init`, but enable the motors only after a
+    /// grace period and once the battery voltage has reached a threshold, see `PowerOnCheck`.
+    /// Until then, the motors stay stopped with their PWM disabled (see `Motors::safe_init`),
+    /// while the LEDs blink the pattern `0b001001` to indicate the wait. A reading above the
+    /// threshold turns the LEDs off and enables the motors.
+    ///
+    /// Returns `InitError::LowBattery` if the battery does not reach the threshold within the
+    /// configured timeout. The motors then stay disabled, i.e., calling `Motors::drive` has no
+    /// effect, but the UART is usable, e.g., to report the error. The timeout is counted in steps
+    /// of the check period of 250ms, so that the system tick is not required.
+    pub fn init_with_power_on_check(check: PowerOnCheck) -> Result<(), InitError> {
+        Self::init_without_motors();
+        delay_ms(check.grace_period_ms.into());
+
+        let mut waited_ms: u32 = 0;
+        let mut leds_on = false;
+        while Self::read_ubat() < check.min_ubat {
+            if let Some(timeout_ms) = check.timeout_ms {
+                if waited_ms >= timeout_ms {
+                    Self::set_leds(0);
+                    return Err(InitError::LowBattery);
+                }
+            }
+            leds_on = !leds_on;
+            Self::set_leds(if leds_on { WAITING_LEDS } else { 0 });
+            delay_ms(CHECK_PERIOD_MS.into());
+            waited_ms = waited_ms.saturating_add(CHECK_PERIOD_MS.into());
+        }
+
+        Self::set_leds(0);
+        interrupt::without_interrupts(|_| Motors::init());
+        Ok(())
+    }
+
+    /// The raw ADC reading of the battery voltage `UBAT`, averaged over a few conversions to
+    /// suppress noise. See `PowerOnCheck` for its scale.
+    pub fn read_ubat() -> u16 {
+        let sum: u16 = (0..UBAT_SAMPLES).map(|_| Adc::read(UBAT_CHANNEL)).sum();
+        sum / UBAT_SAMPLES
+    }
+}
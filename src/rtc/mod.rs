@@ -0,0 +1,104 @@
+//! RTC = "Real-Time Clock"
+//!
+//! This module keeps the time in seconds with `Timer2` clocked asynchronously by an external
+//! 32.768kHz watch crystal, which keeps running in the `PowerSave` sleep mode, see `Rtc`. Only
+//! available with the feature `rtc`, as it claims the `TIMER2_OVF` interrupt.
+
+use crate::{
+    avr::{
+        bitmasks::{AS2, CS20, CS22, OCF2, OCIE2, OCR2UB, TCN2UB, TCR2UB, TOIE2, TOV2},
+        power::{self, SleepMode},
+        registers::{ASSR, OCR2, TCCR2, TCNT2, TIFR, TIMSK},
+    },
+    interrupt::{self, mutex::Mutex},
+    port::{Led3, ACS_R},
+    Pin, Register,
+};
+use avr_macros::interrupt;
+
+/// Bits of `ASSR` that are set while a write to the respective register of `Timer2` is being
+/// transferred to the asynchronous clock domain.
+const UPDATE_BUSY: u8 = TCN2UB | OCR2UB | TCR2UB;
+
+/// Seconds counted by the `TIMER2_OVF` interrupt since `Rtc::init`.
+static SECONDS: Mutex<u32> = Mutex::new(0);
+
+#[interrupt]
+fn TIMER2_OVF() {
+    interrupt::without_interrupts(|cs| SECONDS.lock(cs).update(|seconds| seconds.wrapping_add(1)));
+}
+
+/// Struct managing the real-time clock.
+///
+/// `Timer2` is clocked by a 32.768kHz crystal on the pins `TOSC1` (`c6`) and `TOSC2` (`c7`) with a
+/// prescaler of 128, so that the 8-bit counter overflows exactly once per second. As the crystal
+/// runs independently of the CPU clock, the clock keeps running in the `PowerSave` sleep mode,
+/// whose `TIMER2_OVF` interrupt wakes the device every second, see `Rtc::power_save`.
+///
+/// Note that the stock RP6 does not have this crystal, and its pins are used by the LED SL3 and the
+/// right IR LED of the ACS (`ACS_R`), which stop working once the oscillator is enabled: the crystal
+/// has to be fitted to the pins instead. Both pins are claimed by `Rtc::init` to detect accidental
+/// use in debug builds. Moreover, `Timer2` is exclusively used by the clock, i.e., the RTC cannot
+/// be used together with the ACS, the `Servo` driver or one-shots of `Timer2` (see
+/// `Timer8::after`).
+pub struct Rtc;
+
+impl Rtc {
+    /// Switch `Timer2` to the asynchronous clock of the crystal and start counting seconds from 0.
+    ///
+    /// Follows the procedure of the datasheet for switching to asynchronous operation: the
+    /// interrupts of `Timer2` are disabled, as its registers may be corrupted while switching,
+    /// `AS2` is set, and the registers are rewritten. As writes to the registers take effect only
+    /// after two cycles of the crystal, i.e., about 61µs, this waits until the update-busy bits in
+    /// `ASSR` have cleared before clearing the interrupt flags and enabling the overflow interrupt.
+    ///
+    /// The crystal oscillator may need up to about a second to stabilize after power-on, so that
+    /// the first second may be inaccurate.
+    pub fn init() {
+        Led3::set_input();
+        Led3::set_low();
+        Led3::claim();
+        ACS_R::set_input();
+        ACS_R::set_low();
+        ACS_R::claim();
+
+        interrupt::without_interrupts(|cs| {
+            TIMSK::unset_mask_raw(TOIE2 | OCIE2);
+            ASSR::set_mask_raw(AS2);
+            TCNT2::write(0);
+            OCR2::write(0);
+            // normal mode with a prescaler of 128: 32768Hz / 128 / 256 = 1Hz
+            TCCR2::write(CS22 | CS20);
+            Self::wait_until_updated();
+
+            // writing a one clears the flags
+            TIFR::write(TOV2 | OCF2);
+            SECONDS.lock(cs).set(0);
+            TIMSK::set_mask_raw(TOIE2);
+        });
+    }
+
+    /// Seconds elapsed since `Rtc::init`. Wraps around after about 136 years.
+    pub fn uptime_seconds() -> u32 {
+        interrupt::without_interrupts(|cs| SECONDS.lock(cs).get())
+    }
+
+    /// Enter the `PowerSave` sleep mode until an interrupt wakes the device, at the latest with the
+    /// next second of the clock.
+    ///
+    /// If the device went to sleep within the same cycle of the crystal in which the `TIMER2_OVF`
+    /// interrupt woke it, the interrupt logic would not be ready yet, and the device would not
+    /// wake up with the next overflow. Hence, as recommended by the datasheet, `TCCR2` is rewritten
+    /// and its update awaited first, which takes at least one cycle of the crystal.
+    pub fn power_save() {
+        TCCR2::write(TCCR2::read());
+        Self::wait_until_updated();
+        power::sleep(SleepMode::PowerSave);
+    }
+
+    /// Wait until the writes to `TCNT2`, `OCR2` and `TCCR2` have been transferred to the
+    /// asynchronous clock domain.
+    fn wait_until_updated() {
+        while ASSR::read() & UPDATE_BUSY != 0 {}
+    }
+}
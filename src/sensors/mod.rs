@@ -0,0 +1,9 @@
+//! Drivers for sensors that are commonly added to the robot via its expansion ports.
+
+/// Module decoding the signals of quadrature encoders.
+mod quadrature;
+pub use quadrature::QuadratureDecoder;
+
+/// Module implementing a driver for HC-SR04 ultrasonic distance sensors.
+mod ultrasonic;
+pub use ultrasonic::{UltrasonicSensor, ULTRASONIC_MAX_RANGE_CM};
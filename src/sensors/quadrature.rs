@@ -0,0 +1,106 @@
+use crate::Pin;
+use core::marker::PhantomData;
+
+/// Marker for a transition in which both channels changed at once, i.e., an edge was missed or the
+/// signal glitched. The direction of such a transition is unknown.
+const ILLEGAL: i8 = 2;
+
+/// Count change for each transition between two states of the channels, indexed by
+/// `previous_state << 2 | current_state`, where a state is `A << 1 | B`. Moving forward, the
+/// channels follow the Grey code sequence `00 -> 01 -> 11 -> 10 -> 00`.
+const TRANSITIONS: [i8; 16] = [
+    // previous state 00
+    0, 1, -1, ILLEGAL, //
+    // previous state 01
+    -1, 0, ILLEGAL, 1, //
+    // previous state 10
+    1, ILLEGAL, 0, -1, //
+    // previous state 11
+    ILLEGAL, -1, 1, 0, //
+];
+
+/// Marks that the channels have not been sampled yet.
+const UNINITIALIZED: u8 = 0xFF;
+
+/// Decoder for quadrature encoders with the two channels `A` and `B`, e.g., encoders of expansion
+/// modules that allow to sense the direction of a wheel, unlike the single-channel encoders of the
+/// robot base. Each transition of the 2-bit Grey code counts one step forward or backward.
+///
+/// The decoder must sample the channels on every edge of either channel by calling `update`, e.g.,
+/// from the handlers of two external interrupts that trigger on any logical change (the atmega32
+/// has no pin change interrupts), or periodically from a timer faster than the maximum edge rate.
+/// Note that `INT0` and `INT1` are only available if the feature `encoders` is disabled, as
+/// `Encoders` claims them otherwise.
+/// If an edge is missed, both channels appear to change at once. Such illegal transitions are not
+/// counted (as their direction is unknown), but are reported via `errors`.
+///
+/// To share the decoder between an interrupt handler and the main loop, wrap it in an
+/// `interrupt::mutex::DynamicMutex`.
+pub struct QuadratureDecoder<A: Pin, B: Pin> {
+    /// Last sampled state `A << 1 | B` of the channels.
+    state: u8,
+    /// Decoded position in steps.
+    count: i32,
+    /// Number of illegal transitions.
+    errors: u16,
+    _pins: PhantomData<fn() -> (A, B)>,
+}
+
+impl<A: Pin, B: Pin> QuadratureDecoder<A, B> {
+    /// Create a new decoder at position `0`. Call `init` before the first `update`.
+    pub const fn new() -> Self {
+        Self {
+            state: UNINITIALIZED,
+            count: 0,
+            errors: 0,
+            _pins: PhantomData,
+        }
+    }
+
+    /// Configure both channels as inputs and sample their initial state.
+    pub fn init(&mut self) {
+        A::set_input();
+        B::set_input();
+        self.state = Self::sample();
+    }
+
+    /// Sample both channels and decode the transition since the last sample.
+    pub fn update(&mut self) {
+        let state = Self::sample();
+        if self.state != UNINITIALIZED {
+            match TRANSITIONS[((self.state << 2) | state) as usize] {
+                ILLEGAL => self.errors = self.errors.saturating_add(1),
+                step => self.count += step as i32,
+            }
+        }
+        self.state = state;
+    }
+
+    /// Decoded position in steps, positive values correspond to the forward direction.
+    pub fn count(&self) -> i32 {
+        self.count
+    }
+
+    /// Number of illegal transitions, i.e., missed edges or glitches, since the last `reset`.
+    pub fn errors(&self) -> u16 {
+        self.errors
+    }
+
+    /// Reset the position and the number of illegal transitions to `0`.
+    pub fn reset(&mut self) {
+        self.count = 0;
+        self.errors = 0;
+    }
+
+    /// Read the current state `A << 1 | B` of the channels.
+    #[inline(always)]
+    fn sample() -> u8 {
+        ((A::is_high() as u8) << 1) | B::is_high() as u8
+    }
+}
+
+impl<A: Pin, B: Pin> Default for QuadratureDecoder<A, B> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
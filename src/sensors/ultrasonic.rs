@@ -0,0 +1,70 @@
+use crate::{
+    avr::capture::{measure_pulse_on, PulseLevel, CAPTURE_TICK_HZ},
+    delay_us, Pin,
+};
+use core::marker::PhantomData;
+
+/// Maximum range of the HC-SR04 in cm according to its datasheet.
+pub const ULTRASONIC_MAX_RANGE_CM: u16 = 400;
+
+/// Duration of the echo in µs per cm of distance: sound travels 1cm in about 29µs at room
+/// temperature, and the echo pulse covers the way there and back.
+const ECHO_US_PER_CM: u32 = 58;
+
+/// Driver for HC-SR04 ultrasonic distance sensors with the trigger pin `Trig` and the echo pin
+/// `Echo`, e.g., connected to spare pins of the expansion ports.
+///
+/// A measurement is started by a 10µs pulse on `Trig`, after which the sensor emits an ultrasonic
+/// burst and drives `Echo` high until the reflection is received. The width of the echo pulse is
+/// measured with `Timer1` as timebase, see `rp6::avr::capture::measure_pulse_on`, which
+/// temporarily stops the motors' PWM signals.
+pub struct UltrasonicSensor<Trig: Pin, Echo: Pin> {
+    _pins: PhantomData<fn() -> (Trig, Echo)>,
+}
+
+impl<Trig: Pin, Echo: Pin> UltrasonicSensor<Trig, Echo> {
+    /// Create a new driver. Call `init` before the first measurement.
+    pub const fn new() -> Self {
+        Self { _pins: PhantomData }
+    }
+
+    /// Configure the trigger pin as output and the echo pin as input.
+    pub fn init(&self) {
+        Trig::set_low();
+        Trig::set_output();
+        Echo::set_input();
+    }
+
+    /// Measure the distance to the closest obstacle in cm. Returns `None` if there is no obstacle
+    /// within `ULTRASONIC_MAX_RANGE_CM` (400cm, i.e., an echo of about 23ms), as well as on a
+    /// timeout if the echo does not start within 65536 ticks of `CAPTURE_TICK_HZ` (about 65ms at
+    /// 8MHz), e.g., because the sensor is not connected. Blocks for up to about 40ms (the sensor's
+    /// own timeout) or 65ms without a sensor.
+    ///
+    /// Interrupts stay enabled while measuring, e.g., so that no bytes received via `Serial` are
+    /// lost. An interrupt handler executing at an edge of the echo delays its detection by the
+    /// duration of the handler, i.e., by a few µs, which is well below the 58µs per cm of distance.
+    /// An interrupt during the trigger pulse merely extends it beyond the required 10µs.
+    ///
+    /// Successive measurements should be at least 60ms apart to avoid receiving the echo of the
+    /// previous burst.
+    pub fn read_cm(&self) -> Option<u16> {
+        Trig::set_high();
+        delay_us(10);
+        Trig::set_low();
+        let ticks = measure_pulse_on::<Echo>(PulseLevel::High)?;
+
+        let echo_us = ticks as u32 * 1_000_000 / CAPTURE_TICK_HZ;
+        let distance_cm = echo_us / ECHO_US_PER_CM;
+        if distance_cm > ULTRASONIC_MAX_RANGE_CM as u32 {
+            return None;
+        }
+        Some(distance_cm as u16)
+    }
+}
+
+impl<Trig: Pin, Echo: Pin> Default for UltrasonicSensor<Trig, Echo> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
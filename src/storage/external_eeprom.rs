@@ -0,0 +1,77 @@
+use crate::{
+    avr::twi::{TwiError, TwiMaster},
+    delay_us,
+};
+
+/// Interval in µs in which the EEPROM is polled while it finishes an internal write cycle.
+const WRITE_POLL_INTERVAL_US: u32 = 100;
+
+/// Maximum number of polls while waiting for a write cycle, i.e., 10ms in total. The datasheets of
+/// the 24Cxx series specify a maximum write cycle time of 5ms.
+const WRITE_POLL_ATTEMPTS: u16 = 100;
+
+/// Driver for I2C EEPROMs of the 24Cxx series with 16-bit memory addresses (24C32 to 24C512),
+/// e.g., a 24C256 for data logging, connected to the TWI bus. `PAGE_SIZE` is the size of the
+/// device's write pages in bytes, e.g., 64 for the 24C256 (default) or 32 for the 24C32/24C64.
+/// Requires `TwiMaster::init` to be called before use.
+pub struct ExternalEeprom<const PAGE_SIZE: u16 = 64> {
+    /// 7-bit address of the device on the TWI bus.
+    address: u8,
+}
+
+impl<const PAGE_SIZE: u16> ExternalEeprom<PAGE_SIZE> {
+    /// Base address of the 24Cxx series on the TWI bus, where the lower three bits are selected by
+    /// the pins `A2`, `A1` and `A0` of the device.
+    pub const BASE_ADDRESS: u8 = 0x50;
+
+    /// Create a driver for the device whose address pins `A2`, `A1` and `A0` are wired to the
+    /// lower three bits of `address_pins`. A `PAGE_SIZE` of zero is rejected at compile time.
+    pub const fn new(address_pins: u8) -> Self {
+        const { assert!(PAGE_SIZE > 0, "the page size must not be zero") };
+        Self {
+            address: Self::BASE_ADDRESS | (address_pins & 0b111),
+        }
+    }
+
+    /// Read `buffer.len()` bytes starting at memory address `address` into `buffer`. The device
+    /// increments the address internally, so reads may cross page boundaries.
+    pub fn read(&self, address: u16, buffer: &mut [u8]) -> Result<(), TwiError> {
+        if buffer.is_empty() {
+            return Ok(());
+        }
+        TwiMaster::write_read(self.address, &address.to_be_bytes(), buffer)
+    }
+
+    /// Write `data` starting at memory address `address`. A single write operation of the device
+    /// must not cross a page boundary, as its address would wrap around to the beginning of the
+    /// page, overwriting data there. Thus, `data` is split into chunks at the page boundaries,
+    /// each written in a separate operation. Blocks until the device has finished writing.
+    pub fn write(&self, address: u16, data: &[u8]) -> Result<(), TwiError> {
+        let mut address = address;
+        let mut remaining = data;
+        while !remaining.is_empty() {
+            let page_remaining = PAGE_SIZE - address % PAGE_SIZE;
+            let length = remaining.len().min(page_remaining as usize);
+            let (chunk, rest) = remaining.split_at(length);
+
+            TwiMaster::write_prefixed(self.address, &address.to_be_bytes(), chunk)?;
+            self.wait_until_ready()?;
+
+            address = address.wrapping_add(length as u16);
+            remaining = rest;
+        }
+        Ok(())
+    }
+
+    /// Wait until the device has finished its internal write cycle. During the write cycle, the
+    /// device does not acknowledge its address ("acknowledge polling").
+    pub fn wait_until_ready(&self) -> Result<(), TwiError> {
+        for _ in 0..WRITE_POLL_ATTEMPTS {
+            if TwiMaster::probe(self.address) {
+                return Ok(());
+            }
+            delay_us(WRITE_POLL_INTERVAL_US);
+        }
+        Err(TwiError::Timeout)
+    }
+}
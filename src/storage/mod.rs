@@ -0,0 +1,5 @@
+//! Drivers for external memories that are commonly added to the robot via its expansion bus.
+
+/// Module implementing a driver for I2C EEPROMs of the 24Cxx series.
+mod external_eeprom;
+pub use external_eeprom::ExternalEeprom;
@@ -0,0 +1,105 @@
+//! Cooperative multitasking of independent robot tasks from the main loop.
+//!
+//! Each task, e.g., motor ramping, telemetry output, ACS scanning or a behavior state machine,
+//! implements `Task` and is polled in turn by a `Runner`, which avoids interleaving them manually
+//! in the main loop. The runner does not allocate: it borrows a fixed array of tasks.
+//!
+//! All tasks run on the main loop without preemption, so each task has to honor the following
+//! contract:
+//! - `Task::poll` never blocks, i.e., it never waits for an event or uses `delay_ms`. Instead, it
+//!   checks whether there is work to do, does it, and returns. Waiting is expressed by comparing
+//!   `now_ms` with a point in time stored in the task.
+//! - `Task::poll` returns quickly, as a long-running task delays all other tasks.
+//! - Tasks are only polled from the main loop, never from an interrupt.
+//!
+//! Example:
+//! ```rust
+//! struct Blink { next_ms: u32, on: bool }
+//!
+//! impl Task for Blink {
+//!     fn poll(&mut self, now_ms: u32) -> TaskState {
+//!         if now_ms.wrapping_sub(self.next_ms) as i32 >= 0 {
+//!             self.on = !self.on;
+//!             RobotBase::set_led(0, self.on);
+//!             self.next_ms = now_ms.wrapping_add(500);
+//!         }
+//!         TaskState::Running
+//!     }
+//! }
+//!
+//! time::init_tick(time::DEFAULT_TICK_PERIOD_US).unwrap();
+//! let mut blink = Blink { next_ms: 0, on: false };
+//! let mut telemetry = |now_ms: u32| {
+//!     println!(now_ms);
+//!     TaskState::Running
+//! };
+//! Runner::new([&mut blink, &mut telemetry]).run();
+//! ```
+
+use crate::time;
+
+/// Result of polling a `Task`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TaskState {
+    /// The task wants to be polled again.
+    Running,
+    /// The task has finished and is not polled anymore.
+    Done,
+}
+
+/// A task that is polled cooperatively from the main loop, see the module documentation for the
+/// contract it has to honor.
+pub trait Task {
+    /// Perform the work that is due at `now_ms` (see `time::millis`) without blocking, and report
+    /// whether the task has finished.
+    fn poll(&mut self, now_ms: u32) -> TaskState;
+}
+
+/// Closures are tasks, e.g., for simple periodic work.
+impl<F: FnMut(u32) -> TaskState> Task for F {
+    fn poll(&mut self, now_ms: u32) -> TaskState {
+        self(now_ms)
+    }
+}
+
+/// Polls a fixed number `N` of tasks in turn until all of them are done.
+pub struct Runner<'a, const N: usize> {
+    /// The tasks in the order they are polled.
+    tasks: [&'a mut dyn Task; N],
+    /// Whether the task at the same index is done.
+    done: [bool; N],
+}
+
+impl<'a, const N: usize> Runner<'a, N> {
+    /// Create a new runner polling `tasks` in the given order.
+    pub fn new(tasks: [&'a mut dyn Task; N]) -> Self {
+        Self {
+            tasks,
+            done: [false; N],
+        }
+    }
+
+    /// Poll each task that is not done yet once, all with the same current time. Returns whether
+    /// any task is still running. Call this from the main loop, possibly along with other work.
+    ///
+    /// Requires the system tick to be started with `time::init_tick` for `now_ms` to advance.
+    pub fn poll(&mut self) -> bool {
+        let now_ms = time::millis();
+        for (task, done) in self.tasks.iter_mut().zip(self.done.iter_mut()) {
+            if !*done {
+                *done = task.poll(now_ms) == TaskState::Done;
+            }
+        }
+        !self.is_done()
+    }
+
+    /// Whether all tasks are done.
+    pub fn is_done(&self) -> bool {
+        self.done.iter().all(|&done| done)
+    }
+
+    /// Poll the tasks in a loop until all of them are done.
+    pub fn run(&mut self) {
+        while self.poll() {}
+    }
+}
@@ -0,0 +1,102 @@
+//! Fixed-rate telemetry output over the `Serial` connection.
+//!
+//! Telemetry lines are paced by the system tick (see `rp6::time`) rather than by `delay_ms`, so
+//! that their cadence is independent of the timing of the remaining main loop. The CSV helpers
+//! allow to log the output on a PC and plot it, e.g., with a spreadsheet.
+//!
+//! Example:
+//! ```rust
+//! time::init_tick(time::DEFAULT_TICK_PERIOD_US).unwrap();
+//! telemetry::write_csv_header(&["ms", "ubat"]);
+//! telemetry::stream(10, || telemetry::write_csv_row(&[time::millis() as i32, read_ubat()]));
+//! ```
+
+use crate::{time, Serial};
+
+/// Paces a telemetry closure to a fixed rate, see `TelemetryStream::poll`.
+pub struct TelemetryStream {
+    /// Period between two invocations in ms.
+    period_ms: u32,
+    /// Time (see `time::millis`) at which the next invocation is due.
+    next_ms: u32,
+    /// Number of periods skipped because an invocation took too long.
+    overruns: u16,
+}
+
+impl TelemetryStream {
+    /// Create a new stream invoking its closure `rate_hz` times per second, starting with the first
+    /// call to `poll`. The period is rounded to whole milliseconds, and rates above 1000Hz are
+    /// limited to 1000Hz.
+    pub fn new(rate_hz: u16) -> Self {
+        Self {
+            period_ms: (1000 / u32::from(rate_hz.max(1))).max(1),
+            next_ms: time::millis(),
+            overruns: 0,
+        }
+    }
+
+    /// Invoke `f` if its next period is due, and return whether it was invoked. Call this
+    /// regularly from the main loop, at least once per period. The invocations are scheduled at
+    /// fixed multiples of the period, so that delays of single invocations do not accumulate.
+    ///
+    /// If the invocation of `f` (or the rest of the main loop) takes longer than a period, the
+    /// missed periods are skipped instead of invoking `f` repeatedly to catch up, and counted as
+    /// overruns, see `TelemetryStream::overruns`.
+    ///
+    /// Requires the system tick to be started with `time::init_tick`.
+    pub fn poll(&mut self, f: impl FnOnce()) -> bool {
+        let now = time::millis();
+        if now.wrapping_sub(self.next_ms) as i32 >= 0 {
+            f();
+            self.next_ms = self.next_ms.wrapping_add(self.period_ms);
+
+            // skip periods that have already passed
+            let now = time::millis();
+            while now.wrapping_sub(self.next_ms) as i32 >= 0 {
+                self.next_ms = self.next_ms.wrapping_add(self.period_ms);
+                self.overruns = self.overruns.saturating_add(1);
+            }
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Number of periods skipped because an invocation took longer than the period.
+    pub fn overruns(&self) -> u16 {
+        self.overruns
+    }
+}
+
+/// Invoke `f` `rate_hz` times per second forever, e.g., to emit a line of telemetry per call. See
+/// `TelemetryStream` for interleaving the telemetry with other work of the main loop.
+///
+/// Requires the system tick to be started with `time::init_tick`.
+pub fn stream(rate_hz: u16, mut f: impl FnMut()) -> ! {
+    let mut stream = TelemetryStream::new(rate_hz);
+    loop {
+        stream.poll(&mut f);
+    }
+}
+
+/// Write a CSV header line consisting of the given column `labels`.
+pub fn write_csv_header(labels: &[&str]) {
+    for (index, label) in labels.iter().enumerate() {
+        if index > 0 {
+            Serial::write(',');
+        }
+        Serial::write(*label);
+    }
+    Serial::new_line();
+}
+
+/// Write a CSV line consisting of the given `values`.
+pub fn write_csv_row(values: &[i32]) {
+    for (index, &value) in values.iter().enumerate() {
+        if index > 0 {
+            Serial::write(',');
+        }
+        Serial::write(value);
+    }
+    Serial::new_line();
+}
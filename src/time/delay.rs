@@ -0,0 +1,54 @@
+use crate::avr::config::CPU_FREQUENCY_HZ;
+#[cfg(target_arch = "avr")]
+use core::arch::asm;
+
+/// CPU cycles per µs, rounded down to whole MHz.
+const CYCLES_PER_US: u32 = CPU_FREQUENCY_HZ / 1_000_000;
+
+/// CPU cycles per iteration of the loop in `busy_loop`: `SBIW` (2 cycles) and a taken `BRNE` (2
+/// cycles).
+const CYCLES_PER_ITERATION: u32 = 4;
+
+/// Blocks for `us` µs by executing a busy loop of exactly known duration, with the number of
+/// iterations derived from `CPU_FREQUENCY_HZ` (rounded down to whole MHz), e.g., for bit-banged
+/// protocols such as a software UART or WS2812 LEDs. The delay is rounded down to full loop
+/// iterations, i.e., to multiples of 0.5µs at 8MHz.
+///
+/// With a constant `us`, the function is inlined and the number of iterations is computed at
+/// compile time, so that the only overhead is loading the loop counter (2 cycles) minus the final
+/// not-taken branch (1 cycle). Thus, the delay is exact to within 1 cycle (0.125µs at 8MHz) for
+/// any `us >= 1`. A runtime value of `us` adds a few cycles for the multiplication (shifts at
+/// 8MHz), i.e., delays below 2µs are not reliably achievable in that case.
+///
+/// Note that interrupt handlers executing during the delay extend it, so timing-critical code
+/// should run inside `interrupt::without_interrupts`.
+#[inline(always)]
+pub fn delay_us_exact(us: u16) {
+    let mut iterations = u32::from(us) * CYCLES_PER_US / CYCLES_PER_ITERATION;
+    // delays longer than `u16::MAX` iterations (about 32ms at 8MHz) are split into multiple loops
+    while iterations > u32::from(u16::MAX) {
+        busy_loop(u16::MAX);
+        iterations -= u32::from(u16::MAX);
+    }
+    if iterations > 0 {
+        busy_loop(iterations as u16);
+    }
+}
+
+/// Busy loop of `iterations * CYCLES_PER_ITERATION - 1` cycles, where `iterations` must not be
+/// zero (which would loop 65536 times).
+#[inline(always)]
+fn busy_loop(iterations: u16) {
+    #[cfg(not(target_arch = "avr"))]
+    let _ = iterations;
+    #[cfg(target_arch = "avr")]
+    unsafe {
+        asm!(
+            "1:",
+            "sbiw {iterations}, 1",
+            "brne 1b",
+            iterations = inout(reg_iw) iterations => _,
+            options(nomem, nostack),
+        )
+    }
+}
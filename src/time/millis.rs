@@ -0,0 +1,65 @@
+use crate::{Serial, SerialWritable};
+
+/// A duration or point in time in milliseconds, e.g., as returned by `millis`, that is written to
+/// the `Serial` connection with its unit, e.g., as `"1234ms"`. Use `Millis::as_seconds` to write it
+/// as seconds with a fractional part instead, e.g., as `"1.234s"`.
+///
+/// Example:
+/// ```rust
+/// let start = millis();
+/// // ...
+/// println!("Elapsed: ", Millis::since(start)); // e.g., "Elapsed: 1234ms"
+/// println!("Uptime: ", Millis::now().as_seconds()); // e.g., "Uptime: 61.005s"
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Millis(pub u32);
+
+impl Millis {
+    /// The time elapsed since the system tick was started, see `millis`.
+    pub fn now() -> Self {
+        Self(super::millis())
+    }
+
+    /// The time elapsed since `start`, which was previously obtained from `millis`.
+    pub fn since(start: u32) -> Self {
+        Self(super::millis().wrapping_sub(start))
+    }
+
+    /// Format the duration as seconds with a fractional part, e.g., as `"1.234s"`.
+    pub fn as_seconds(self) -> MillisAsSeconds {
+        MillisAsSeconds(self)
+    }
+}
+
+impl From<u32> for Millis {
+    fn from(ms: u32) -> Self {
+        Self(ms)
+    }
+}
+
+impl SerialWritable for Millis {
+    /// Write the duration in milliseconds, e.g., as `"1234ms"`.
+    fn write_to_serial(&self) {
+        Serial::write_dec_u32(self.0);
+        Serial::write("ms");
+    }
+}
+
+/// A `Millis` duration that is written to the `Serial` connection as seconds with a fractional
+/// part, e.g., as `"1.234s"`. Obtained by `Millis::as_seconds`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MillisAsSeconds(pub Millis);
+
+impl SerialWritable for MillisAsSeconds {
+    /// Write the duration in seconds with exactly three fractional digits, e.g., as `"1.005s"`.
+    fn write_to_serial(&self) {
+        let ms = (self.0).0;
+        let fraction = ms % 1000;
+        Serial::write_dec_u32(ms / 1000);
+        Serial::write('.');
+        Serial::write((b'0' + (fraction / 100) as u8) as char);
+        Serial::write((b'0' + (fraction / 10 % 10) as u8) as char);
+        Serial::write((b'0' + (fraction % 10) as u8) as char);
+        Serial::write('s');
+    }
+}
@@ -0,0 +1,179 @@
+//! Module providing a periodic system tick based on `Timer0` and the time elapsed since it was
+//! started.
+//!
+//! The tick is driven by the `TIMER0_COMP` interrupt, which is defined in this module. Note that
+//! the tick and one-shots via `Timer0::after` are mutually exclusive, as both reconfigure `Timer0`.
+//! Likewise, the tick depends on the clock source selected by `init_tick`, i.e., the smallest
+//! prescaler achieving the tick period (`Prescale8` for the default period at 8MHz). It must not
+//! be changed via `Timer0::set_clock_source` while the tick is running.
+
+use crate::{
+    avr::{
+        config::CPU_FREQUENCY_HZ,
+        modules::{ClockSource8, Timer8, Timer8Setup, WaveformGenerationMode8},
+        power,
+        timers::Timer0,
+    },
+    delay_ms,
+    interrupt::{self, mutex::Mutex},
+    Register, RobotBase,
+};
+use avr_macros::interrupt;
+
+/// Module implementing busy delays of exactly known duration.
+mod delay;
+pub use delay::delay_us_exact;
+
+/// Module implementing the formatting of durations for the `Serial` connection.
+mod millis;
+pub use millis::{Millis, MillisAsSeconds};
+
+/// Period of the tick in µs as initialized by the original RP6Lib.
+pub const DEFAULT_TICK_PERIOD_US: u16 = 100;
+
+/// Shortest supported tick period in µs. Shorter periods would mostly keep the CPU busy with
+/// executing the tick interrupt.
+pub const MIN_TICK_PERIOD_US: u16 = 10;
+
+/// Reasons why a tick period cannot be achieved by `Timer0` at `CPU_FREQUENCY_HZ`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TickPeriodError {
+    /// The period is shorter than `MIN_TICK_PERIOD_US`.
+    TooShort,
+    /// The period exceeds 256 timer ticks even with the largest prescaler.
+    TooLong,
+    /// The period is not an exact multiple of the timer ticks of any suitable prescaler, which
+    /// would let `millis` drift.
+    Inexact,
+}
+
+/// Period of the tick in µs, or 0 if the tick is not running.
+static TICK_PERIOD_US: Mutex<u16> = Mutex::new(0);
+
+/// Milliseconds elapsed since the tick was started.
+static MILLIS: Mutex<u32> = Mutex::new(0);
+
+/// Microseconds elapsed since `MILLIS` was last incremented.
+static MICROS_REMAINDER: Mutex<u16> = Mutex::new(0);
+
+/// Clock sources of `Timer0` with the corresponding prescaler, in ascending order.
+const PRESCALERS: [(u32, ClockSource8); 5] = [
+    (1, ClockSource8::Prescale1),
+    (8, ClockSource8::Prescale8),
+    (64, ClockSource8::Prescale64),
+    (256, ClockSource8::Prescale256),
+    (1024, ClockSource8::Prescale1024),
+];
+
+/// Compute the clock source and output compare value that let `Timer0` generate a compare match
+/// interrupt every `period_us` µs. The smallest suitable prescaler is chosen for best accuracy.
+fn tick_configuration(period_us: u16) -> Result<(ClockSource8, u8), TickPeriodError> {
+    if period_us < MIN_TICK_PERIOD_US {
+        return Err(TickPeriodError::TooShort);
+    }
+
+    let cycles = u64::from(period_us) * u64::from(CPU_FREQUENCY_HZ);
+    if !cycles.is_multiple_of(1_000_000) {
+        return Err(TickPeriodError::Inexact);
+    }
+    let cycles = (cycles / 1_000_000) as u32;
+
+    let mut error = TickPeriodError::TooLong;
+    for (prescaler, clock_source) in PRESCALERS {
+        let ticks = cycles / prescaler;
+        if ticks > 256 {
+            continue;
+        }
+        if cycles.is_multiple_of(prescaler) {
+            return Ok((clock_source, (ticks - 1) as u8));
+        }
+        error = TickPeriodError::Inexact;
+    }
+    Err(error)
+}
+
+/// Start the system tick with a period of `period_us` µs, computing the prescaler and output
+/// compare value of `Timer0` at `CPU_FREQUENCY_HZ`. Resets the time returned by `millis`.
+///
+/// The resolution of `millis` is 1ms for periods up to 1000µs and `period_us / 1000` ms otherwise,
+/// e.g., at 8MHz, the original RP6Lib tick of 100µs results in 10 interrupts per ms, whereas a
+/// tick of 1000µs saves ISR overhead while keeping the full resolution. Periods that are not
+/// exactly achievable are rejected, as `millis` would drift otherwise.
+pub fn init_tick(period_us: u16) -> Result<(), TickPeriodError> {
+    let (clock_source, compare) = tick_configuration(period_us)?;
+
+    interrupt::without_interrupts(|cs| {
+        TICK_PERIOD_US.lock(cs).set(period_us);
+        MILLIS.lock(cs).set(0);
+        MICROS_REMAINDER.lock(cs).set(0);
+
+        Timer8Setup::<Timer0>::new()
+            .waveform_generation_mode(WaveformGenerationMode8::ClearOnTimerMatchOutputCompare)
+            .clock_source(clock_source)
+            .output_compare_1(Some(compare))
+            .configure();
+    });
+    Ok(())
+}
+
+/// Stop the system tick. The time returned by `millis` is kept.
+pub fn stop_tick() {
+    interrupt::without_interrupts(|cs| {
+        <Timer0 as Timer8>::InterruptMask::unset(Timer0::OCIEA);
+        TICK_PERIOD_US.lock(cs).set(0);
+    });
+}
+
+/// Period of the running system tick in µs, or `None` if the tick is not running.
+pub fn tick_period_us() -> Option<u16> {
+    match interrupt::without_interrupts(|cs| TICK_PERIOD_US.lock(cs).get()) {
+        0 => None,
+        period_us => Some(period_us),
+    }
+}
+
+/// Milliseconds elapsed since the system tick was started with `init_tick`. Wraps around after
+/// about 49.7 days.
+pub fn millis() -> u32 {
+    interrupt::without_interrupts(|cs| MILLIS.lock(cs).get())
+}
+
+/// Advances the time by a single tick.
+fn tick() {
+    interrupt::without_interrupts(|cs| {
+        let period_us = TICK_PERIOD_US.lock(cs).get();
+        let micros = MICROS_REMAINDER.lock(cs).get() as u32 + period_us as u32;
+        let millis = MILLIS.lock(cs);
+        millis.set(millis.get().wrapping_add(micros / 1000));
+        MICROS_REMAINDER.lock(cs).set((micros % 1000) as u16);
+    });
+}
+
+#[interrupt]
+fn TIMER0_COMP() {
+    Timer0::on_compare_match();
+    tick();
+    #[cfg(feature = "diag")]
+    crate::diag::on_tick();
+    power::signal(power::EVENT_TICK);
+}
+
+/// Blocks for `ms` milliseconds by waiting on the time returned by `millis`. In contrast to
+/// `delay_ms`, which busy-waits by counting instructions, interrupts keep being serviced (e.g.,
+/// the ACS or the `Serial` ringbuffer), while the delay stays calibrated by `Timer0`. The idle hook
+/// is invoked while waiting, see `RobotBase::set_idle_hook`.
+///
+/// Requires the system tick to be started with `init_tick` beforehand. The accuracy of the delay is
+/// limited by the resolution of `millis`, i.e., it may end up to one `millis` step early. If the
+/// tick is not running, this falls back to `delay_ms` instead of blocking forever.
+pub fn delay_ms_ticked(ms: u32) {
+    if tick_period_us().is_none() {
+        delay_ms(ms);
+        return;
+    }
+
+    let start = millis();
+    while millis().wrapping_sub(start) < ms {
+        RobotBase::idle();
+    }
+}
@@ -0,0 +1,82 @@
+use super::Serial;
+use crate::{
+    avr::{
+        capture::{measure_pulse_on, PulseLevel, CAPTURE_TICK_HZ},
+        config::CPU_FREQUENCY_HZ,
+        registers::{UBRRH, UBRRL},
+    },
+    interrupt,
+    port::RX,
+    Register,
+};
+
+/// Baudrates that `Serial::autobaud` chooses from. Note that at 8MHz, 57600 and 115200 Baud can
+/// only be approximated with an error of 3.5% and 8.5%, respectively, which is too large for
+/// reliable communication.
+pub const AUTOBAUD_RATES: [u32; 9] = [
+    2400, 4800, 9600, 19200, 38400, 57600, 115200, 250000, 500000,
+];
+
+/// Number of low pulses of the reference character measured by `Serial::autobaud`.
+const AUTOBAUD_PULSES: u8 = 4;
+
+/// Maximum deviation of the measured bit time from the chosen baudrate in percent.
+const AUTOBAUD_TOLERANCE_PERCENT: u32 = 20;
+
+impl Serial {
+    /// Detect the baudrate of the sender by measuring the bit time of a reference character on the
+    /// `RX` pin, configure the UART accordingly and return the detected baudrate. The sender must
+    /// transmit the character `'U'` (`0x55`, alternating bits), or alternatively a carriage return
+    /// `'\r'` (`0x0D`), in 8N1 format, e.g., by pressing `Shift+U` or `Enter` in a terminal,
+    /// repeatedly until the detection succeeds.
+    ///
+    /// Every low pulse of these characters, e.g., the start bit, spans a whole number of bits, with
+    /// at least one of them spanning a single bit. Hence, the shortest of the first
+    /// `AUTOBAUD_PULSES` low pulses is taken as bit time. It is measured in ticks of
+    /// `CAPTURE_TICK_HZ` by polling the pin with `Timer1` as timebase, see
+    /// `capture::measure_pulse_on`, and the closest baudrate of `AUTOBAUD_RATES` is chosen.
+    /// Interrupts are disabled during the measurement, which also interrupts the motor PWM.
+    ///
+    /// Returns `None` if no character is received within about 65ms, or if the bit time deviates
+    /// from all `AUTOBAUD_RATES` by more than 20%. The baudrate is not changed in that case. Due to
+    /// the resolution of 1µs and the polling, the detection is only reliable up to 38400 Baud.
+    /// Bytes received during the detection are discarded, see `Serial::drain`.
+    pub fn autobaud() -> Option<u32> {
+        let bit_ticks = interrupt::without_interrupts(|_| {
+            let mut shortest = measure_pulse_on::<RX>(PulseLevel::Low)?;
+            for _ in 1..AUTOBAUD_PULSES {
+                match measure_pulse_on::<RX>(PulseLevel::Low) {
+                    Some(ticks) => shortest = shortest.min(ticks),
+                    None => break,
+                }
+            }
+            Some(u32::from(shortest))
+        });
+        Self::drain();
+
+        let bit_ticks = bit_ticks?.max(1);
+        let baudrate = Self::closest_baudrate(bit_ticks)?;
+        Self::set_baudrate(baudrate);
+        Some(baudrate)
+    }
+
+    /// Choose the baudrate of `AUTOBAUD_RATES` whose bit time is closest to `bit_ticks` ticks of
+    /// `CAPTURE_TICK_HZ`, if it deviates by at most `AUTOBAUD_TOLERANCE_PERCENT`.
+    fn closest_baudrate(bit_ticks: u32) -> Option<u32> {
+        // compare the measured bit time `bit_ticks / CAPTURE_TICK_HZ` with `1 / rate`
+        let deviation = |rate: u32| bit_ticks.saturating_mul(rate).abs_diff(CAPTURE_TICK_HZ);
+        AUTOBAUD_RATES
+            .iter()
+            .copied()
+            .min_by_key(|&rate| deviation(rate))
+            .filter(|&rate| deviation(rate) <= CAPTURE_TICK_HZ / 100 * AUTOBAUD_TOLERANCE_PERCENT)
+    }
+
+    /// Configure the `Serial` connection to the given baudrate, rounding `UBRR` to the closest
+    /// achievable baudrate.
+    fn set_baudrate(baudrate: u32) {
+        let ubrr = ((CPU_FREQUENCY_HZ + 8 * baudrate) / (16 * baudrate)).saturating_sub(1);
+        UBRRH::write((ubrr >> 8) as u8);
+        UBRRL::write(ubrr as u8);
+    }
+}
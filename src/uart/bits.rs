@@ -0,0 +1,106 @@
+use super::{Serial, SerialWritable};
+
+/// Write the lowest `N` bits of `value` MSB-first, optionally named and labeled, see `Bits8`.
+fn write_bits<const N: usize>(
+    value: u16,
+    name: Option<&str>,
+    labels: Option<&[&str; N]>,
+    separator: &str,
+) {
+    if let Some(name) = name {
+        Serial::write(name);
+        Serial::write(" = ");
+    }
+    Serial::write('[');
+    for index in 0..N {
+        if index > 0 {
+            Serial::write(separator);
+        }
+        if let Some(labels) = labels {
+            Serial::write(labels[index]);
+            Serial::write(':');
+        }
+        let bit = N - 1 - index;
+        Serial::write(if value & (1 << bit) != 0 { '1' } else { '0' });
+    }
+    Serial::write(']');
+}
+
+/// Define a newtype for dumping the bits of a `$type` value via the `Serial` connection.
+macro_rules! impl_bits {
+    ($(#[$attr: meta])* $name: ident, $type: ty, $bits: expr) => {
+        $(#[$attr])*
+        #[derive(Clone, Copy)]
+        pub struct $name<'a> {
+            value: $type,
+            name: Option<&'a str>,
+            labels: Option<&'a [&'a str; $bits]>,
+            separator: &'a str,
+        }
+
+        impl<'a> $name<'a> {
+            /// Dump the bits of `value`, separated by a space and without name or labels.
+            pub const fn new(value: $type) -> Self {
+                Self {
+                    value,
+                    name: None,
+                    labels: None,
+                    separator: " ",
+                }
+            }
+
+            /// Prefix the bits with `name = `, e.g., the name of the register.
+            pub const fn name(mut self, name: &'a str) -> Self {
+                self.name = Some(name);
+                self
+            }
+
+            /// Prefix each bit with its label and a colon, where `labels` are given MSB-first.
+            pub const fn labels(mut self, labels: &'a [&'a str; $bits]) -> Self {
+                self.labels = Some(labels);
+                self
+            }
+
+            /// Separate the bits by `separator` instead of a space.
+            pub const fn separator(mut self, separator: &'a str) -> Self {
+                self.separator = separator;
+                self
+            }
+        }
+
+        impl From<$type> for $name<'_> {
+            fn from(value: $type) -> Self {
+                Self::new(value)
+            }
+        }
+
+        impl SerialWritable for $name<'_> {
+            /// Write all bits MSB-first, e.g., `UCSRA = [RXC:1 TXC:0 UDRE:1 ...]`.
+            fn write_to_serial(&self) {
+                write_bits::<$bits>(self.value.into(), self.name, self.labels, self.separator);
+            }
+        }
+    };
+}
+
+impl_bits!(
+    /// Newtype to dump all 8 bits of a value, e.g., of a register, via the `Serial` connection.
+    /// Much more useful than a bare binary number when inspecting the state of a peripheral.
+    ///
+    /// Example:
+    /// ```rust
+    /// const UCSRA_LABELS: [&str; 8] = ["RXC", "TXC", "UDRE", "FE", "DOR", "PE", "U2X", "MPCM"];
+    /// println!(Bits8::new(UCSRA::read()).name("UCSRA").labels(&UCSRA_LABELS));
+    /// // e.g., writes "UCSRA = [RXC:0 TXC:1 UDRE:1 FE:0 DOR:0 PE:0 U2X:0 MPCM:0]"
+    /// ```
+    Bits8,
+    u8,
+    8
+);
+
+impl_bits!(
+    /// Newtype to dump all 16 bits of a value via the `Serial` connection, see `Bits8`.
+    Bits16,
+    u16,
+    16
+);
@@ -0,0 +1,151 @@
+use super::Serial;
+use crate::{
+    collections::RingBuffer,
+    interrupt::{self, mutex::DynamicMutex},
+    RobotBase,
+};
+
+/// Byte starting each frame of a logical channel, see `Serial::channel`.
+pub const CHANNEL_FRAME_START: u8 = 0x02;
+
+/// Size of the ringbuffer of each logical channel. One slot always stays unused, i.e., each
+/// ringbuffer holds up to `CHANNEL_BUFFER_SIZE - 1` bytes.
+pub const CHANNEL_BUFFER_SIZE: usize = 32;
+
+/// Largest payload of a single frame in bytes, as its length is sent as a single byte.
+pub const CHANNEL_MAX_PAYLOAD: usize = u8::MAX as usize;
+
+/// Number of logical channels, see `ChannelId`.
+const CHANNELS: usize = 2;
+
+/// Logical channels multiplexed over the `Serial` connection, see `Serial::channel`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum ChannelId {
+    /// Channel for commands, e.g., from a remote control.
+    Command = 0,
+    /// Channel for high-rate telemetry.
+    Telemetry = 1,
+}
+
+/// State of the demultiplexer, i.e., the part of a frame that is expected next.
+#[derive(Clone, Copy)]
+enum Demultiplexer {
+    /// Bytes are discarded until `CHANNEL_FRAME_START` is received.
+    Idle,
+    /// The channel byte is expected.
+    Channel,
+    /// The length byte of a frame for the given channel (`None` for unknown ones) is expected.
+    Length(Option<usize>),
+    /// The given number of payload bytes for the given channel is expected.
+    Payload(Option<usize>, u8),
+}
+
+impl Demultiplexer {
+    /// Advance by the received `byte`, storing payload bytes in the ringbuffer of their channel.
+    /// Payload bytes of unknown channels, or of a full ringbuffer, are discarded.
+    fn feed(self, byte: u8, buffers: &mut [RingBuffer<u8, CHANNEL_BUFFER_SIZE>; CHANNELS]) -> Self {
+        match self {
+            Self::Idle if byte == CHANNEL_FRAME_START => Self::Channel,
+            Self::Idle => Self::Idle,
+            Self::Channel => Self::Length(Some(usize::from(byte)).filter(|&id| id < CHANNELS)),
+            Self::Length(_) if byte == 0 => Self::Idle,
+            Self::Length(channel) => Self::Payload(channel, byte),
+            Self::Payload(channel, remaining) => {
+                if let Some(channel) = channel {
+                    buffers[channel].push(byte);
+                }
+                match remaining - 1 {
+                    0 => Self::Idle,
+                    remaining => Self::Payload(channel, remaining),
+                }
+            }
+        }
+    }
+}
+
+/// Ringbuffers of the logical channels and the state of the demultiplexer filling them.
+static DEMULTIPLEXER: DynamicMutex<(
+    Demultiplexer,
+    [RingBuffer<u8, CHANNEL_BUFFER_SIZE>; CHANNELS],
+)> = DynamicMutex::new((Demultiplexer::Idle, [RingBuffer::new(), RingBuffer::new()]));
+
+/// Handle of a logical channel multiplexed over the `Serial` connection, see `Serial::channel`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Channel {
+    id: ChannelId,
+}
+
+impl Serial {
+    /// Get a handle of the logical channel `id`, which shares the `Serial` connection with the
+    /// other channels, e.g., to separate incoming commands from a flood of telemetry, so that
+    /// neither starves the other:
+    /// ```rust
+    /// Serial::channel(ChannelId::Telemetry).write(&samples);
+    /// if let Some(command) = Serial::channel(ChannelId::Command).read() {
+    ///     // handle the command
+    /// }
+    /// ```
+    ///
+    /// Data is sent in frames of `CHANNEL_FRAME_START`, the channel id, the length of the payload
+    /// (1 to `CHANNEL_MAX_PAYLOAD` bytes), and the payload. Thus, each frame has an overhead of 3
+    /// bytes, i.e., about 0.8ms at `BAUD_LOW`, so prefer few large writes over many small ones.
+    /// Received frames are demultiplexed from the receive ringbuffer into a ringbuffer of
+    /// `CHANNEL_BUFFER_SIZE` per channel whenever a channel is read. Once a channel's ringbuffer is
+    /// full, further bytes for it are discarded without affecting the other channel. Bytes outside
+    /// of frames, e.g., of a partial frame after a reset, are discarded until the next
+    /// `CHANNEL_FRAME_START`.
+    ///
+    /// As the demultiplexer consumes all received bytes, other reads from the `Serial` connection,
+    /// e.g., via `Serial::read`, must not be mixed with reads from channels.
+    pub fn channel(id: ChannelId) -> Channel {
+        Channel { id }
+    }
+}
+
+impl Channel {
+    /// Send `data` to the channel, split into frames of up to `CHANNEL_MAX_PAYLOAD` bytes. Blocks
+    /// until all frames have been handed to the hardware, like `Serial::write_raw`.
+    pub fn write(&self, data: &[u8]) {
+        for payload in data.chunks(CHANNEL_MAX_PAYLOAD) {
+            Serial::write_raw(CHANNEL_FRAME_START);
+            Serial::write_raw(self.id as u8);
+            Serial::write_raw(payload.len() as u8);
+            payload.iter().for_each(|&byte| Serial::write_raw(byte));
+        }
+    }
+
+    /// Read the oldest byte received on the channel. Returns `None` if no byte is available.
+    pub fn read(&self) -> Option<u8> {
+        self.with_buffer(|buffer| buffer.pop())
+    }
+
+    /// Read the oldest byte received on the channel. Blocks until a byte is available, invoking
+    /// the idle hook meanwhile, see `RobotBase::set_idle_hook`.
+    pub fn read_byte(&self) -> u8 {
+        loop {
+            if let Some(byte) = self.read() {
+                return byte;
+            }
+            RobotBase::idle();
+        }
+    }
+
+    /// Number of bytes received on the channel that can be read without blocking.
+    pub fn available(&self) -> usize {
+        self.with_buffer(|buffer| buffer.len())
+    }
+
+    /// Demultiplex all bytes of the receive ringbuffer, then call `f` with the channel's
+    /// ringbuffer, all within a critical section.
+    fn with_buffer<T>(&self, f: impl FnOnce(&mut RingBuffer<u8, CHANNEL_BUFFER_SIZE>) -> T) -> T {
+        interrupt::without_interrupts(|cs| {
+            let mut state = DEMULTIPLEXER.lock(cs).borrow_mut();
+            let (demultiplexer, buffers) = &mut *state;
+            while let Some(byte) = Serial::read() {
+                *demultiplexer = demultiplexer.feed(byte, buffers);
+            }
+            f(&mut buffers[self.id as usize])
+        })
+    }
+}
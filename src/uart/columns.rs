@@ -0,0 +1,50 @@
+use super::{Serial, SerialWritableDecimal, StringType};
+use ufmt::uwrite;
+
+/// Default width of a column written by `Serial::write_columns`, in characters.
+pub const DEFAULT_COLUMN_WIDTH: usize = 12;
+
+impl Serial {
+    /// Write a number formatted as decimal to the `Serial` connection, right-aligned to `width`
+    /// characters by padding it with leading spaces. Numbers longer than `width` are written
+    /// completely.
+    pub fn write_dec_padded<T: SerialWritableDecimal>(value: T, width: usize) {
+        let mut buffer = T::DecimalString::new();
+        let result = uwrite!(&mut buffer, "{}", value);
+        Self::write_spaces(width.saturating_sub(buffer.as_str().len()));
+        buffer.write_formatted(result);
+    }
+
+    /// Write pairs of labels and values as aligned columns of `DEFAULT_COLUMN_WIDTH` characters in
+    /// a single line, e.g., for a simple telemetry dashboard in a terminal:
+    /// ```text
+    /// enc_l:  1234 enc_r:  1201 ubat:    712
+    /// ```
+    /// Surplus labels or values (if the slices differ in length) are ignored.
+    pub fn write_columns(labels: &[&str], values: &[i32]) {
+        Self::write_columns_with_width(labels, values, DEFAULT_COLUMN_WIDTH);
+    }
+
+    /// Write pairs of labels and values as aligned columns like `Serial::write_columns`, but with
+    /// columns of `width` characters each. Each column consists of the label, a colon and the
+    /// right-aligned value, and columns are separated by a space. Columns that do not fit into
+    /// `width` are written completely, shifting the following columns.
+    pub fn write_columns_with_width(labels: &[&str], values: &[i32], width: usize) {
+        for (index, (label, &value)) in labels.iter().zip(values).enumerate() {
+            if index > 0 {
+                Self::write(' ');
+            }
+            Self::write(*label);
+            Self::write(':');
+            Self::write_dec_padded(value, width.saturating_sub(label.len() + 1));
+        }
+        Self::new_line();
+    }
+
+    /// Write `count` spaces to the `Serial` connection.
+    fn write_spaces(count: usize) {
+        for _ in 0..count {
+            Self::write(' ');
+        }
+    }
+}
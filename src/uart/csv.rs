@@ -0,0 +1,41 @@
+use super::{Serial, SerialWritable};
+
+impl Serial {
+    /// Write `fields` of mixed types as a line of comma-separated values, e.g., for data logging
+    /// that is ingested by a spreadsheet or plotting tool. The fields are separated by `','`
+    /// without a trailing comma, and the line ends with `"\r\n"`, which all tools accept. See
+    /// `csv_row!` for a shorthand that takes the fields directly.
+    ///
+    /// The fields are trait objects, so that a single, non-generic function writes any mix of
+    /// types, at the cost of an indirect call per field. Note that the fields are written as is,
+    /// i.e., text fields should not contain commas or line breaks.
+    ///
+    /// Example:
+    /// ```rust
+    /// // e.g., writes "1234,7200,left,-15\r\n"
+    /// Serial::write_csv_row(&[&time::millis(), &ubat_mv, &"left", &speed]);
+    /// ```
+    pub fn write_csv_row(fields: &[&dyn SerialWritable]) {
+        for (index, field) in fields.iter().enumerate() {
+            if index > 0 {
+                Self::write(',');
+            }
+            field.write_to_serial();
+        }
+        Self::write("\r\n");
+    }
+}
+
+/// Convenience macro that writes its arguments of mixed types as a line of comma-separated
+/// values, see `Serial::write_csv_row`.
+///
+/// Example:
+/// ```rust
+/// csv_row!(time::millis(), ubat_mv, "left", speed); // e.g., writes "1234,7200,left,-15\r\n"
+/// ```
+#[macro_export]
+macro_rules! csv_row {
+    ($($field: expr),* $(,)?) => {
+        $crate::Serial::write_csv_row(&[$(&$field as &dyn $crate::SerialWritable),*]);
+    };
+}
@@ -0,0 +1,73 @@
+use super::{Serial, SerialWritable};
+
+/// Newtype to write bytes via the `Serial` connection in a terminal-safe way, see
+/// `Serial::write_escaped`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Escaped<'a>(pub &'a [u8]);
+
+impl SerialWritable for Escaped<'_> {
+    fn write_to_serial(&self) {
+        for &byte in self.0 {
+            write_escaped_byte(byte, false);
+        }
+    }
+}
+
+/// Newtype to write text via the `Serial` connection with its control characters escaped, see
+/// `Serial::write_str_escaped`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EscapedStr<'a>(pub &'a str);
+
+impl SerialWritable for EscapedStr<'_> {
+    fn write_to_serial(&self) {
+        for &byte in self.0.as_bytes() {
+            write_escaped_byte(byte, true);
+        }
+    }
+}
+
+/// Write a single `byte` with the escapes of `Serial::write_escaped`. Bytes of multi-byte UTF-8
+/// sequences, i.e., above `0x7F`, are only escaped if `pass_utf8` is `false`.
+fn write_escaped_byte(byte: u8, pass_utf8: bool) {
+    match byte {
+        b'\\' => Serial::write("\\\\"),
+        b'\n' => Serial::write("\\n"),
+        b'\r' => Serial::write("\\r"),
+        b'\t' => Serial::write("\\t"),
+        b'\0' => Serial::write("\\0"),
+        b' '..=b'~' => Serial::write_raw(byte),
+        0x80.. if pass_utf8 => Serial::write_raw(byte),
+        _ => {
+            Serial::write("\\x");
+            Serial::write_raw(hex_digit(byte >> 4));
+            Serial::write_raw(hex_digit(byte & 0x0f));
+        }
+    }
+}
+
+/// The uppercase hexadecimal digit of the nibble `value`.
+pub(super) fn hex_digit(value: u8) -> u8 {
+    match value {
+        0..=9 => b'0' + value,
+        _ => b'A' + value - 10,
+    }
+}
+
+impl Serial {
+    /// Write `data` to the `Serial` connection with all non-printable bytes escaped, e.g., to log
+    /// received bytes of a textual protocol without garbling the terminal. Printable ASCII
+    /// characters are written as is, `'\n'`, `'\r'`, `'\t'` and `'\0'` as `\n`, `\r`, `\t` and
+    /// `\0`, and all other bytes as `\xHH`. A backslash itself is written as `\\`, so that the
+    /// output can be unescaped unambiguously, e.g., the bytes `\x41` are written as `\\x41`.
+    pub fn write_escaped(data: &[u8]) {
+        Self::write(Escaped(data));
+    }
+
+    /// Write the text `s` to the `Serial` connection with its control characters escaped like
+    /// `Serial::write_escaped`, e.g., to echo a received command back to a terminal for
+    /// confirmation. In contrast to `Serial::write_escaped`, multi-byte UTF-8 characters are
+    /// written unchanged, e.g., `"10°C\r\n"` is written as `10°C\r\n`.
+    pub fn write_str_escaped(s: &str) {
+        Self::write(EscapedStr(s));
+    }
+}
@@ -0,0 +1,98 @@
+use super::{Serial, RX_BUFFER_SIZE};
+use crate::{
+    interrupt::{self, mutex::Mutex, CriticalSection},
+    Pin, RobotBase,
+};
+
+/// Fill level of the receive ringbuffer at which the sender is asked to pause via `RTS`.
+pub const RTS_HIGH_WATERMARK: usize = RX_BUFFER_SIZE * 3 / 4;
+/// Fill level of the receive ringbuffer at which the sender is allowed to resume via `RTS`.
+pub const RTS_LOW_WATERMARK: usize = RX_BUFFER_SIZE / 4;
+
+/// Monomorphized accessors of the pins used for flow control.
+#[derive(Clone, Copy)]
+struct FlowControl {
+    /// Drives the `RTS` pin, `true` asks the sender to pause.
+    set_rts: fn(bool),
+    /// Checks if the `CTS` pin allows to transmit.
+    is_clear_to_send: fn() -> bool,
+}
+
+/// Pins used for flow control, `None` if flow control is disabled (default).
+static FLOW_CONTROL: Mutex<Option<FlowControl>> = Mutex::new(None);
+
+/// Drive the `RTS` pin high to ask the sender to pause, or low to allow it to send.
+fn set_rts<RTS: Pin>(pause: bool) {
+    if pause {
+        RTS::set_high();
+    } else {
+        RTS::set_low();
+    }
+}
+
+/// The sender may transmit while its `CTS` input is driven low.
+fn is_clear_to_send<CTS: Pin>() -> bool {
+    CTS::is_low()
+}
+
+impl Serial {
+    /// Enable hardware flow control using the (active low) `RTS` output and `CTS` input pins,
+    /// e.g., two spare pins of PORTA. While flow control is enabled, `Serial`
+    /// - drives `RTS` high when its receive ringbuffer is filled up to `RTS_HIGH_WATERMARK` bytes
+    ///   and low again once it has been read down to `RTS_LOW_WATERMARK` bytes, and
+    /// - only transmits bytes while `CTS` is driven low by the other side.
+    ///
+    /// Flow control is disabled by default.
+    pub fn enable_flow_control<RTS: Pin, CTS: Pin>() {
+        CTS::set_input();
+        RTS::set_output();
+
+        interrupt::without_interrupts(|cs| {
+            FLOW_CONTROL.lock(cs).set(Some(FlowControl {
+                set_rts: set_rts::<RTS>,
+                is_clear_to_send: is_clear_to_send::<CTS>,
+            }));
+            set_rts::<RTS>(Self::rx_buffer_len(cs) >= RTS_HIGH_WATERMARK);
+        });
+    }
+
+    /// Disable hardware flow control. The `RTS` pin is driven low, so that the sender is never
+    /// left paused.
+    pub fn disable_flow_control() {
+        interrupt::without_interrupts(|cs| {
+            if let Some(flow_control) = FLOW_CONTROL.lock(cs).take() {
+                (flow_control.set_rts)(false);
+            }
+        });
+    }
+
+    /// Update the `RTS` pin according to the current fill level of the receive ringbuffer.
+    pub(super) fn update_rts(cs: &CriticalSection, fill_level: usize) {
+        if let Some(flow_control) = FLOW_CONTROL.lock(cs).get() {
+            if fill_level >= RTS_HIGH_WATERMARK {
+                (flow_control.set_rts)(true);
+            } else if fill_level <= RTS_LOW_WATERMARK {
+                (flow_control.set_rts)(false);
+            }
+        }
+    }
+
+    /// Checks if the `CTS` pin allows to transmit. Always `true` if flow control is disabled.
+    pub(super) fn is_clear_to_send(cs: &CriticalSection) -> bool {
+        match FLOW_CONTROL.lock(cs).get() {
+            Some(flow_control) => (flow_control.is_clear_to_send)(),
+            None => true,
+        }
+    }
+
+    /// Blocks until the `CTS` pin allows to transmit, invoking the idle hook meanwhile. Returns
+    /// immediately if flow control is disabled.
+    pub(super) fn wait_until_clear_to_send() {
+        if let Some(flow_control) = interrupt::without_interrupts(|cs| FLOW_CONTROL.lock(cs).get())
+        {
+            while !(flow_control.is_clear_to_send)() {
+                RobotBase::idle();
+            }
+        }
+    }
+}
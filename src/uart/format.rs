@@ -0,0 +1,100 @@
+/// Number of bytes that `format!` reserves per argument. Suffices for the decimal and hexadecimal
+/// representation of all integers up to 64 bits, for `char` and `bool`, and for strings of up to
+/// this length.
+pub const FORMAT_ARGUMENT_CAPACITY: usize = 20;
+
+// the longest representations of the supported types must fit into the reserved capacity
+const _: () = assert!("-9223372036854775808".len() <= FORMAT_ARGUMENT_CAPACITY); // i64::MIN
+const _: () = assert!("18446744073709551615".len() <= FORMAT_ARGUMENT_CAPACITY); // u64::MAX
+const _: () = assert!("ffffffffffffffff".len() <= FORMAT_ARGUMENT_CAPACITY); // u64::MAX as hex
+const _: () = assert!(char::MAX.len_utf8() <= FORMAT_ARGUMENT_CAPACITY);
+const _: () = assert!("false".len() <= FORMAT_ARGUMENT_CAPACITY);
+
+/// The `ufmt` crate, which the expansion of `uwrite!` refers to, for crates calling `format!`
+/// without depending on `ufmt` themselves.
+#[doc(hidden)]
+pub use ufmt as __ufmt;
+
+/// Format the arguments like `uwrite!` into a `heapless::String`, whose capacity is computed at
+/// compile time from the length of the format string plus `FORMAT_ARGUMENT_CAPACITY` bytes per
+/// argument, e.g., to build a line for a display or for retransmission without an allocator.
+///
+/// The capacity suffices for any integer of up to 64 bits (also as hexadecimal via `{:x}`), as
+/// well as `char`, `bool` and strings of up to `FORMAT_ARGUMENT_CAPACITY` bytes. For longer
+/// arguments, e.g., longer strings or `Debug` representations of structs, formatting does not
+/// panic, but the string is cut off before the first piece that does not fit anymore.
+///
+/// Example:
+/// ```rust
+/// let line = rp6::format!("enc: {} / {}", left, right);
+/// Serial::write(line.as_str());
+/// ```
+#[macro_export]
+macro_rules! format {
+    (@count $($argument: expr),*) => {
+        0 $(+ $crate::format!(@one $argument))*
+    };
+    (@one $argument: expr) => {
+        1
+    };
+    ($format: literal $(, $argument: expr)* $(,)?) => {{
+        #[allow(unused_imports)]
+        use $crate::__ufmt as ufmt;
+
+        let mut string = $crate::String::<
+            {
+                $format.len()
+                    + $crate::format!(@count $($argument),*) * $crate::FORMAT_ARGUMENT_CAPACITY
+            },
+        >::new();
+        // an overflowing piece is not written, which leaves the string cut off before it
+        let _ = $crate::uwrite!(&mut string, $format $(, $argument)*);
+        string
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn extreme_integers_fit() {
+        assert_eq!(
+            crate::format!("{}", i64::MIN).as_str(),
+            "-9223372036854775808"
+        );
+        assert_eq!(
+            crate::format!("{}", u64::MAX).as_str(),
+            "18446744073709551615"
+        );
+        assert_eq!(
+            crate::format!("min {} max {}!", i64::MIN, i64::MAX).as_str(),
+            "min -9223372036854775808 max 9223372036854775807!"
+        );
+    }
+
+    #[test]
+    fn hexadecimal_fits() {
+        assert_eq!(
+            crate::format!("{:x}", u64::MAX).as_str(),
+            "ffffffffffffffff"
+        );
+        assert_eq!(
+            crate::format!("{:#x}", u64::MAX).as_str(),
+            "0xffffffffffffffff"
+        );
+    }
+
+    #[test]
+    fn char_and_bool_fit() {
+        assert_eq!(
+            crate::format!("{}{}", char::MAX, char::MAX).as_str(),
+            "\u{10ffff}\u{10ffff}"
+        );
+        assert_eq!(crate::format!("{} {}", false, true).as_str(), "false true");
+    }
+
+    #[test]
+    fn long_argument_is_cut_off() {
+        let long = "0123456789abcdefghijklmnopqrstuvwxyz";
+        assert_eq!(crate::format!("[{}]", long).as_str(), "[");
+    }
+}
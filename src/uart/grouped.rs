@@ -0,0 +1,42 @@
+use super::Serial;
+
+/// Number of digits per group written by `Serial::write_dec_grouped`.
+const GROUP_DIGITS: usize = 3;
+
+impl Serial {
+    /// Write an `i32` formatted as decimal with `separator` between groups of three digits, e.g.,
+    /// `1,000,000` for a separator of `','` or `-12_345` for `'_'`, which keeps large counters
+    /// readable. Only the digits of the magnitude are grouped, i.e., the minus of a negative value
+    /// precedes the first group, and values below 1000 (including zero) get no separator.
+    pub fn write_dec_grouped(value: i32, separator: char) {
+        if value < 0 {
+            Self::write_raw(b'-');
+        }
+        Self::write_dec_grouped_u32(value.unsigned_abs(), separator);
+    }
+
+    /// Write a `u32` formatted as decimal with `separator` between groups of three digits, see
+    /// `Serial::write_dec_grouped`.
+    pub fn write_dec_grouped_u32(mut n: u32, separator: char) {
+        // `u32::MAX` has 10 decimal digits
+        let mut buffer = [0u8; 10];
+        let mut start = buffer.len();
+        loop {
+            start -= 1;
+            buffer[start] = b'0' + (n % 10) as u8;
+            n /= 10;
+            if n == 0 {
+                break;
+            }
+        }
+
+        // a separator precedes each digit that starts a group, except for the first digit
+        let digits = &buffer[start..];
+        for (index, &digit) in digits.iter().enumerate() {
+            if index > 0 && (digits.len() - index) % GROUP_DIGITS == 0 {
+                Self::write(separator);
+            }
+            Self::write_raw(digit);
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use super::{escaped::hex_digit, Serial, SerialWritable};
+
+/// Newtype to write bytes via the `Serial` connection as two uppercase hexadecimal digits each,
+/// optionally separated by a character, see `Serial::write_bytes_hex`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HexBytes<'a>(pub &'a [u8], pub Option<char>);
+
+impl SerialWritable for HexBytes<'_> {
+    fn write_to_serial(&self) {
+        for (index, &byte) in self.0.iter().enumerate() {
+            if let Some(separator) = self.1.filter(|_| index > 0) {
+                Serial::write(separator);
+            }
+            Serial::write_raw(hex_digit(byte >> 4));
+            Serial::write_raw(hex_digit(byte & 0x0f));
+        }
+    }
+}
+
+impl Serial {
+    /// Write `data` to the `Serial` connection as two uppercase hexadecimal digits per byte,
+    /// separated by `separator` if given, e.g., to dump TWI registers, EEPROM blocks or protocol
+    /// frames: `Serial::write_bytes_hex(&[0xDE, 0xAD, 0x0B], Some(':'))` writes `DE:AD:0B`, and
+    /// with `None`, `DEAD0B`. In contrast to `Serial::write_hex`, leading zeros are never
+    /// omitted, so that the bytes stay aligned. An empty slice writes nothing.
+    pub fn write_bytes_hex(data: &[u8], separator: Option<char>) {
+        Self::write(HexBytes(data, separator));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{avr::mock, HexBytes, Serial};
+
+    #[test]
+    fn empty_slice_writes_nothing() {
+        assert!(mock::capture_serial(|| Serial::write_bytes_hex(&[], Some(':'))).is_empty());
+        assert!(mock::capture_serial(|| Serial::write(HexBytes(&[], None))).is_empty());
+    }
+
+    #[test]
+    fn bytes_are_padded_and_separated() {
+        let data = [0xDE, 0xAD, 0x0B];
+        assert_eq!(
+            mock::capture_serial(|| Serial::write_bytes_hex(&data, Some(':'))),
+            b"DE:AD:0B"
+        );
+        assert_eq!(
+            mock::capture_serial(|| Serial::write_bytes_hex(&data, None)),
+            b"DEAD0B"
+        );
+    }
+}
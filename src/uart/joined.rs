@@ -0,0 +1,31 @@
+use super::{Serial, SerialWritable};
+
+/// Newtype to write four bytes in dotted decimal notation via the `Serial` connection, e.g., the
+/// IP address `Dotted([192, 168, 0, 1])` as `192.168.0.1`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Dotted(pub [u8; 4]);
+
+impl SerialWritable for Dotted {
+    fn write_to_serial(&self) {
+        for (index, &byte) in self.0.iter().enumerate() {
+            if index > 0 {
+                Serial::write('.');
+            }
+            Serial::write_dec_u32(byte.into());
+        }
+    }
+}
+
+impl Serial {
+    /// Write the numbers in `values` as decimal, separated by `separator`, to the `Serial`
+    /// connection, e.g., `Serial::write_joined(&[1, 2, 3], ", ")` writes `1, 2, 3`. The numbers are
+    /// written one by one, i.e., without building the complete string in memory.
+    pub fn write_joined(values: &[u16], separator: &str) {
+        for (index, &value) in values.iter().enumerate() {
+            if index > 0 {
+                Self::write(separator);
+            }
+            Self::write_dec_u32(value.into());
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use super::Serial;
+use crate::interrupt::{self, mutex::Mutex, CriticalSection};
+
+/// Whether the last byte read by `Serial::read_text_byte` was a `'\r'`. In that case, a directly
+/// following `'\n'` belongs to the same line ending (`"\r\n"`) and is skipped.
+static LAST_BYTE_WAS_CR: Mutex<bool> = Mutex::new(false);
+
+impl Serial {
+    /// Reads a line of text from the receive ringbuffer into `buffer` and returns it without its
+    /// line ending. Blocks until a line ending is received, where `"\n"`, `"\r"` and `"\r\n"` are
+    /// all accepted. Characters exceeding the length of `buffer` are discarded. If the line is not
+    /// valid UTF-8, only its valid prefix is returned.
+    pub fn read_line(buffer: &mut [u8]) -> &str {
+        let mut length = 0;
+        loop {
+            match Self::read_text_byte() {
+                b'\n' => break,
+                byte => {
+                    if length < buffer.len() {
+                        buffer[length] = byte;
+                        length += 1;
+                    }
+                }
+            }
+        }
+
+        Self::valid_prefix(&buffer[..length])
+    }
+
+    /// Reads a line of text like `Serial::read_line`, but for interactive use via a terminal: each
+    /// accepted character is echoed, so that the user sees what they type, and backspace (`0x08`)
+    /// or delete (`0x7F`, sent by most terminals for the backspace key) erases the last character,
+    /// both from `buffer` and from the terminal. Other control characters as well as characters
+    /// exceeding the length of `buffer` are ignored without echo. The line ending is echoed as
+    /// `"\n"`.
+    pub fn read_line_interactive(buffer: &mut [u8]) -> &str {
+        let mut length = 0;
+        loop {
+            match Self::read_text_byte() {
+                b'\n' => break,
+                0x08 | 0x7F => {
+                    if length > 0 {
+                        // erase all bytes of a multi-byte UTF-8 character, but only a single
+                        // character on the terminal
+                        length -= 1;
+                        while length > 0 && buffer[length] & 0b1100_0000 == 0b1000_0000 {
+                            length -= 1;
+                        }
+                        Self::write("\x08 \x08");
+                    }
+                }
+                byte if byte < b' ' => {}
+                byte => {
+                    if length < buffer.len() {
+                        buffer[length] = byte;
+                        length += 1;
+                        Self::write_raw(byte);
+                    }
+                }
+            }
+        }
+        Self::new_line();
+
+        Self::valid_prefix(&buffer[..length])
+    }
+
+    /// The longest prefix of `bytes` that is valid UTF-8.
+    fn valid_prefix(bytes: &[u8]) -> &str {
+        match core::str::from_utf8(bytes) {
+            Ok(line) => line,
+            // SAFETY: `valid_up_to` marks the end of the longest valid UTF-8 prefix.
+            Err(error) => unsafe { core::str::from_utf8_unchecked(&bytes[..error.valid_up_to()]) },
+        }
+    }
+
+    /// Reads the next byte of text from the receive ringbuffer (blocking), where each of the line
+    /// endings `"\n"`, `"\r"` and `"\r\n"` is returned as a single `'\n'`.
+    pub(super) fn read_text_byte() -> u8 {
+        loop {
+            let byte = Self::read_byte();
+            let after_cr = interrupt::without_interrupts(|cs| {
+                LAST_BYTE_WAS_CR.lock(cs).replace(byte == b'\r')
+            });
+            match byte {
+                b'\n' if after_cr => continue,
+                b'\r' => return b'\n',
+                byte => return byte,
+            }
+        }
+    }
+
+    /// Forgets a `'\r'` read last, e.g., after discarding received bytes with `Serial::drain`.
+    pub(super) fn reset_line_ending(cs: &CriticalSection) {
+        LAST_BYTE_WAS_CR.lock(cs).set(false);
+    }
+}
@@ -0,0 +1,29 @@
+use super::Serial;
+use crate::interrupt::{self, mutex::Mutex};
+
+/// Line endings written by `Serial::new_line` and thus `println!`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LineEnding {
+    /// `"\n"`, as expected by Unix terminals (default).
+    #[default]
+    Lf,
+    /// `"\r\n"`, as expected by Windows terminals and some serial monitors.
+    CrLf,
+}
+
+/// Line ending written by `Serial::new_line`.
+static LINE_ENDING: Mutex<LineEnding> = Mutex::new(LineEnding::Lf);
+
+impl Serial {
+    /// Select the line ending written by `Serial::new_line` and thus `println!`, e.g.,
+    /// `LineEnding::CrLf` for terminals that do not return the cursor on `'\n'`. Defaults to
+    /// `LineEnding::Lf`. Explicit line endings in written text, e.g., `"\n"`, are not affected.
+    pub fn set_line_ending(line_ending: LineEnding) {
+        interrupt::without_interrupts(|cs| LINE_ENDING.lock(cs).set(line_ending));
+    }
+
+    /// The line ending written by `Serial::new_line`, see `Serial::set_line_ending`.
+    pub fn line_ending() -> LineEnding {
+        interrupt::without_interrupts(|cs| LINE_ENDING.lock(cs).get())
+    }
+}
@@ -3,14 +3,110 @@
 //! This module contains data transfer functions that allow easy access to the robot's serial UART
 //! connection. Receiving messages is asynchronous (using interrupts) and uses ringbuffers for
 //! intermediate storage of the received messages.
+//!
+//! The handlers of the interrupts `USART_UDRE` and `USART_TXC`, as well as of `USART_RXC` with the
+//! default feature `serial-rx-interrupt`, are defined by this module, so that applications must
+//! not define them again, see the crate documentation.
 use super::port::{RX, TX};
 use crate::{
     avr::{
-        bitmasks::{RXC, RXCIE, RXEN, TXCIE, TXEN, UCSZ, UDRE, URSEL},
+        bitmasks::{RXB8, RXC, RXCIE, RXEN, TXCIE, TXEN, UCSZ, UDRE, UDRIE, URSEL},
+        power,
         registers::{UBRRH, UBRRL, UCSRA, UCSRB, UCSRC, UDR},
     },
-    Pin, Register,
+    collections::RingBuffer,
+    interrupt::{
+        self,
+        mutex::{DynamicMutex, Mutex},
+        CriticalSection,
+    },
+    Pin, Register, RobotBase,
 };
+#[cfg(feature = "serial-rx-interrupt")]
+use avr_macros::interrupt;
+
+/// Module implementing the detection of the sender's baudrate.
+mod autobaud;
+pub use autobaud::AUTOBAUD_RATES;
+
+/// Module implementing dumps of the bits of values such as registers.
+mod bits;
+pub use bits::{Bits16, Bits8};
+
+/// Module multiplexing logical channels over the `Serial` connection.
+mod channel;
+pub use channel::{
+    Channel, ChannelId, CHANNEL_BUFFER_SIZE, CHANNEL_FRAME_START, CHANNEL_MAX_PAYLOAD,
+};
+
+/// Module implementing the aligned output of numbers and columns.
+mod columns;
+pub use columns::DEFAULT_COLUMN_WIDTH;
+
+/// Module implementing the output of comma-separated values.
+mod csv;
+
+/// Module implementing the terminal-safe output of arbitrary bytes.
+mod escaped;
+pub use escaped::{Escaped, EscapedStr};
+
+/// Module that implements optional hardware flow control via `RTS` and `CTS` pins.
+mod flow_control;
+pub use flow_control::{RTS_HIGH_WATERMARK, RTS_LOW_WATERMARK};
+
+/// Module implementing the `format!` macro building strings without an allocator.
+mod format;
+pub use format::*;
+
+/// Module implementing the output of decimal numbers with grouped digits.
+mod grouped;
+
+/// Module implementing hex dumps of bytes.
+mod hex_bytes;
+pub use hex_bytes::HexBytes;
+
+/// Module implementing the output of numbers joined by separators.
+mod joined;
+pub use joined::Dotted;
+
+/// Module implementing line-oriented reading from the `Serial` connection.
+mod line;
+
+/// Module implementing the configurable line ending of `Serial::new_line`.
+mod line_ending;
+pub use line_ending::LineEnding;
+
+/// Module implementing the multi-processor communication mode of the `Serial` connection.
+mod multiprocessor;
+
+/// Module implementing reading numbers from the `Serial` connection.
+mod parse;
+pub use parse::ParseError;
+
+/// Module implementing the output of numbers in arbitrary bases.
+mod radix;
+pub use radix::{InvalidRadix, MAX_RADIX, MIN_RADIX};
+
+/// Module implementing the output of scaled sensor readings and fixed-point numbers.
+mod scaled;
+pub use scaled::{Scaled, MAX_SCALED_DECIMALS};
+
+/// Module implementing a half-duplex RS-485 transceiver on the `Serial` connection.
+mod rs485;
+pub use rs485::{Rs485, RS485_DRIVER_ENABLE_US};
+
+/// Module implementing a loopback self-test of the `Serial` connection.
+mod self_test;
+
+/// Module implementing the hook invoked once a transmission has completed.
+mod transmit_complete;
+
+/// Module implementing non-blocking writes via a transmit ringbuffer.
+mod tx_buffer;
+pub use tx_buffer::TX_BUFFER_SIZE;
+
+/// Module implementing the access to `UCSRC`, which shares its I/O address with `UBRRH`.
+mod ucsrc;
 
 /// Module that implements `Serial::write` and formatting behavior for types.
 mod serial_writable;
@@ -24,20 +120,107 @@ pub const UBRR_BAUD_LOW: u32 = (CPU_FREQUENCY_HZ / (16 * BAUD_LOW)) - 1;
 pub const BAUD_HIGH: u32 = 500000; // High speed: 500.000 Baud
 pub const UBRR_BAUD_HIGH: u32 = (CPU_FREQUENCY_HZ / (16 * BAUD_HIGH)) - 1;
 
+/// Size of the ringbuffer storing bytes received via the `Serial` connection. One slot always
+/// stays unused, i.e., the ringbuffer holds up to `RX_BUFFER_SIZE - 1` bytes.
+pub const RX_BUFFER_SIZE: usize = 32;
+
+/// Ringbuffer storing the bytes received via the `USART_RXC` interrupt until they are read.
+static RX_BUFFER: DynamicMutex<RingBuffer<u8, RX_BUFFER_SIZE>> =
+    DynamicMutex::new(RingBuffer::new());
+
+/// Whether the UART is initialized, see `Serial::init` and `Serial::deinit`.
+static INITIALIZED: Mutex<bool> = Mutex::new(false);
+
+/// Stores each received byte in the ringbuffer, see `Serial::on_receive`. Only defined with the
+/// feature `serial-rx-interrupt`.
+#[cfg(feature = "serial-rx-interrupt")]
+#[interrupt]
+fn USART_RXC() {
+    Serial::on_receive();
+}
+
 /// Struct managing all access to the robot's serial port connection
 pub struct Serial;
 
 impl Serial {
-    /// Initialize the serial connection on pins `RX` and `TX`.
+    /// Stores the received byte in the ringbuffer. Bytes received while the ringbuffer is full are
+    /// lost. In the multi-processor communication mode, address frames are handled separately, see
+    /// `Serial::enable_mpcm`. Must be called from the `USART_RXC` interrupt.
+    ///
+    /// The handler of that interrupt calling this function is defined by the crate with the
+    /// default feature `serial-rx-interrupt`. Applications defining the handler themselves
+    /// instead, i.e., with the default features disabled, may call this function from it to keep
+    /// receiving via `Serial::read`:
+    /// ```rust
+    /// #[interrupt]
+    /// fn USART_RXC() {
+    ///     Serial::on_receive();
+    ///     // custom handling, e.g., of a protocol's timeouts
+    /// }
+    /// ```
+    pub fn on_receive() {
+        // the 9th bit must be read before `UDR`
+        let address_frame = UCSRB::is_mask_set_raw(RXB8);
+        let byte = UDR::read();
+        interrupt::without_interrupts(|cs| {
+            if address_frame && Self::on_address_frame(cs, byte) {
+                return;
+            }
+            let mut buffer = RX_BUFFER.lock(cs).borrow_mut();
+            buffer.push(byte);
+            Self::update_rts(cs, buffer.len());
+        });
+        power::signal(power::EVENT_SERIAL_RECEIVED);
+    }
+
+    /// Initialize the serial connection on pins `RX` and `TX`, which are claimed by the UART, see
+    /// `Pin::claim`. If the UART is already initialized, it is shut down via `Serial::deinit`
+    /// first, so that pending bytes are sent with the previous configuration.
     pub fn init() {
+        Self::deinit();
         RX::set_input();
         TX::set_low();
         TX::set_output();
+        RX::claim();
+        TX::claim();
         // UART:
         Self::set_baudrate_low();
         UCSRA::write(0x00);
+        // `URSEL` selects `UCSRC` instead of `UBRRH`, see `Serial::modify_ucsrc`
         UCSRC::write(URSEL | UCSZ);
         UCSRB::write(TXEN | RXEN | RXCIE);
+        interrupt::without_interrupts(|cs| INITIALIZED.lock(cs).set(true));
+    }
+
+    /// Shut down the serial connection, e.g., to use the pins `RX` and `TX` for another purpose
+    /// such as a bit-banged protocol, until the next `Serial::init`. Does nothing if the UART is
+    /// not initialized.
+    ///
+    /// First, all bytes queued by `Serial::write_nonblocking` are sent via `Serial::flush`, as
+    /// disabling the transmitter earlier would discard them. Then, the `USART_RXC`, `USART_TXC`
+    /// and `USART_UDRE` interrupts as well as the receiver and transmitter are disabled. The
+    /// hardware defers disabling the transmitter until the byte being shifted out is complete, and
+    /// keeps driving `TX` meanwhile, so no byte is cut off. Finally, the pins are released and
+    /// configured as inputs, `TX` with its pull-up enabled to keep the line at its idle level.
+    /// Bytes that were received but not read yet stay available via `Serial::read`.
+    pub fn deinit() {
+        if !Self::is_initialized() {
+            return;
+        }
+        Self::flush();
+        UCSRB::unset_mask_raw(RXCIE | TXCIE | UDRIE | RXEN | TXEN);
+        RX::release();
+        TX::release();
+        RX::set_input();
+        RX::set_low();
+        TX::set_input();
+        TX::set_high();
+        interrupt::without_interrupts(|cs| INITIALIZED.lock(cs).set(false));
+    }
+
+    /// Check whether the UART is initialized, see `Serial::init`.
+    pub fn is_initialized() -> bool {
+        interrupt::without_interrupts(|cs| INITIALIZED.lock(cs).get())
     }
 
     /// Enable the USART_RXC interrupt
@@ -46,7 +229,8 @@ impl Serial {
         UCSRB::set_mask_raw(RXCIE);
     }
 
-    /// Enable the USART_TXC interrupt
+    /// Enable the USART_TXC interrupt, which invokes the hook set via
+    /// `Serial::on_transmit_complete`
     #[allow(non_snake_case)]
     pub fn enable_USART_TXC_interrupt() {
         UCSRB::set_mask_raw(TXCIE);
@@ -76,22 +260,93 @@ impl Serial {
         UBRRL::write(UBRR_BAUD_HIGH as u8);
     }
 
-    /// Reads a single raw byte from the `Serial` connection. Blocks until the processor is ready
-    /// to receive the next byte, i.e., the corresponding bit `UDRE` is set in `UCSRA`.
+    /// Reads the oldest byte received via the `Serial` connection from the ringbuffer. Returns
+    /// `None` if no byte is available. Requires the `USART_RXC` interrupt to be enabled, which is
+    /// the default after `Serial::init`, and to be handled via `Serial::on_receive`.
+    pub fn read() -> Option<u8> {
+        interrupt::without_interrupts(|cs| {
+            let mut buffer = RX_BUFFER.lock(cs).borrow_mut();
+            let byte = buffer.pop();
+            Self::update_rts(cs, buffer.len());
+            byte
+        })
+    }
+
+    /// Number of bytes received via the `Serial` connection that are currently stored in the
+    /// ringbuffer, i.e., that can be read without blocking. At most `RX_BUFFER_SIZE - 1`. As the
+    /// fill level is computed inside a critical section, it is consistent even if the `USART_RXC`
+    /// interrupt receives a byte at the same time, e.g., as the basis of non-blocking parsers:
+    /// ```rust
+    /// if Serial::available() >= MESSAGE_LENGTH {
+    ///     // read the complete message without blocking
+    /// }
+    /// ```
+    pub fn available() -> usize {
+        interrupt::without_interrupts(|cs| Self::rx_buffer_len(cs))
+    }
+
+    /// Discards all bytes received so far, i.e., the contents of the ringbuffer as well as bytes
+    /// still pending in the hardware's receive buffer `UDR`. Useful before starting a protocol
+    /// exchange, so that stale bytes such as a partial line do not corrupt the first real read.
+    pub fn drain() {
+        interrupt::without_interrupts(|cs| {
+            while UCSRA::is_mask_set_raw(RXC) {
+                UDR::read();
+            }
+            RX_BUFFER.lock(cs).borrow_mut().clear();
+            Self::reset_line_ending(cs);
+            Self::update_rts(cs, 0);
+        });
+    }
+
+    /// Calls `f` with a view of all bytes currently stored in the receive ringbuffer, oldest first,
+    /// e.g., to parse received messages without reading them byte by byte. The bytes are not
+    /// removed from the ringbuffer. Interrupts are disabled while `f` is executed, so `f` should be
+    /// short to not lose received bytes, and must not read from the `Serial` connection itself. The
+    /// slice is only valid within `f`, i.e., it cannot escape the closure.
+    pub fn with_rx_buffer<T>(f: impl FnOnce(&[u8]) -> T) -> T {
+        interrupt::without_interrupts(|cs| f(RX_BUFFER.lock(cs).borrow_mut().make_contiguous()))
+    }
+
+    /// Reads the oldest byte received via the `Serial` connection from the ringbuffer. Blocks
+    /// until a byte is available, invoking the idle hook meanwhile, see `RobotBase::set_idle_hook`.
+    pub fn read_byte() -> u8 {
+        loop {
+            if let Some(byte) = Self::read() {
+                return byte;
+            }
+            RobotBase::idle();
+        }
+    }
+
+    /// Reads a single raw byte from the `Serial` connection. Blocks until the processor has
+    /// received the next byte, i.e., the corresponding bit `RXC` is set in `UCSRA`.
+    ///
+    /// Note that this bypasses the ringbuffer and must only be used while the `USART_RXC`
+    /// interrupt is disabled.
     #[inline(always)]
     pub fn read_raw() -> u8 {
         UCSRA::wait_until_mask_set_raw(RXC);
         UDR::read()
     }
 
-    /// Writes a single raw byte to the `Serial` connection. Blocks until the processor is ready to
-    /// send the next byte, i.e., the corresponding bit `UDRE` is set in `UCSRA`.
+    /// Writes a single raw byte to the `Serial` connection. Blocks until the bytes queued by
+    /// `Serial::write_nonblocking` have been sent (see `Serial::flush`), the processor is ready to
+    /// send the next byte, i.e., the corresponding bit `UDRE` is set in `UCSRA`, and, if flow
+    /// control is enabled, until the other side is ready to receive.
     #[inline(always)]
     pub fn write_raw(b: u8) {
+        Self::flush();
+        Self::wait_until_clear_to_send();
         UCSRA::wait_until_mask_set_raw(UDRE);
         UDR::write(b);
     }
 
+    /// Number of bytes currently stored in the receive ringbuffer.
+    fn rx_buffer_len(cs: &CriticalSection) -> usize {
+        RX_BUFFER.lock(cs).borrow().len()
+    }
+
     /*
     /// Tries to write a single raw byte to the `Serial` connection. If the processor is not ready
     /// to send, i.e., the corresponding bit `UDRE` is not set in `UCSRA`, returns with an `Error`.
@@ -106,23 +361,36 @@ impl Serial {
     */
 
     /// Write something to the `Serial` connection. By default, supports `&str`, `char`, and basic
-    /// number types (in decimal notation).
+    /// number types (in decimal notation), as well as `Option`, `Result` and tuples of up to four
+    /// elements of these, e.g., `Serial::write((left, right))` writes `(1234, 1201)`.
     pub fn write<T: SerialWritable>(value: T) {
         value.write_to_serial();
     }
 
-    /*
     /// Write a number formatted as binary to the `Serial` connection.
     pub fn write_bin<T: SerialWritableBinary>(value: T) {
         value.write_to_serial_as_bin();
     }
-    */
+
+    /// Write a number formatted as binary with the prefix `0b` to the `Serial` connection, e.g.,
+    /// `0b1010`.
+    pub fn write_bin_prefixed<T: SerialWritableBinary>(value: T) {
+        Self::write("0b");
+        value.write_to_serial_as_bin();
+    }
 
     /// Write a number formatted as decimal to the `Serial` connection.
     pub fn write_dec<T: SerialWritableDecimal>(value: T) {
         value.write_to_serial_as_dec();
     }
 
+    /// Write a `u32` formatted as decimal to the `Serial` connection. In contrast to `write_dec`,
+    /// the digits are computed directly into a small stack buffer without the generic formatting
+    /// machinery, which results in smaller and faster code for hot paths such as logging loops.
+    pub fn write_dec_u32(n: u32) {
+        Self::write_digits(n, 10);
+    }
+
     /*
     /// Write a number formatted as exponential to the `Serial` connection.
     pub fn write_exp<T: SerialWritableExponential>(value: T) {
@@ -135,6 +403,13 @@ impl Serial {
         value.write_to_serial_as_hex();
     }
 
+    /// Write a number formatted as hexadecimal with the prefix `0x` to the `Serial` connection,
+    /// e.g., `0x1f`.
+    pub fn write_hex_prefixed<T: SerialWritableHexadecimal>(value: T) {
+        Self::write("0x");
+        value.write_to_serial_as_hex();
+    }
+
     /*
     /// Write a number formatted as octal to the `Serial` connection.
     pub fn write_oct<T: SerialWritableOctal>(value: T) {
@@ -142,14 +417,19 @@ impl Serial {
     }
     */
 
-    /// Write a `'\n'` (newline character) to the serial connection.
+    /// Write a line ending to the serial connection, i.e., `'\n'` (newline character) by default,
+    /// see `Serial::set_line_ending`.
     pub fn new_line() {
+        if Self::line_ending() == LineEnding::CrLf {
+            Self::write('\r');
+        }
         Self::write('\n');
     }
 }
 
 /// Convenience macro that allows to write multiple (formatted) `Serial::write` statements as a
-/// single call. Currently supported formatters are `dec` and `hex` for numbers.
+/// single call. Currently supported formatters are `bin`, `dec` and `hex` for numbers, as well as
+/// `BINP` and `HEXP` for binary and hexadecimal numbers with the prefixes `0b` and `0x`.
 ///
 /// Example:
 /// ```rust
@@ -170,12 +450,21 @@ macro_rules! print {
     ($($writable: expr $(=> $format: tt)?),* $(,)?) => {
         $($crate::print!(@write $writable $(=> $format)?);)*
     };
+    (@write $writable: expr => bin) => {
+        Serial::write_bin($writable);
+    };
+    (@write $writable: expr => BINP) => {
+        Serial::write_bin_prefixed($writable);
+    };
     (@write $writable: expr => dec) => {
         Serial::write_dec($writable);
     };
     (@write $writable: expr => hex) => {
         Serial::write_hex($writable);
     };
+    (@write $writable: expr => HEXP) => {
+        Serial::write_hex_prefixed($writable);
+    };
     (@write $writable: expr) => {
         Serial::write($writable);
     };
@@ -189,3 +478,27 @@ macro_rules! println {
         Serial::new_line();
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Serial;
+    use crate::{
+        avr::{mock, registers::UDR},
+        Register,
+    };
+
+    #[test]
+    fn on_receive_fills_the_ringbuffer() {
+        let _guard = mock::lock_global_state();
+        mock::reset();
+        Serial::drain();
+        for byte in *b"ok" {
+            mock::preset::<UDR>(byte);
+            Serial::on_receive();
+        }
+        assert_eq!(Serial::available(), 2);
+        assert_eq!(Serial::read(), Some(b'o'));
+        assert_eq!(Serial::read(), Some(b'k'));
+        assert_eq!(Serial::read(), None);
+    }
+}
@@ -0,0 +1,89 @@
+use super::Serial;
+use crate::{
+    avr::{
+        bitmasks::{MPCM, TXB8, U2X, UCSZ2, UDRE},
+        registers::{UCSRA, UCSRB, UDR},
+    },
+    interrupt::{self, mutex::Mutex, CriticalSection},
+    Register,
+};
+
+/// Own address on a multi-processor bus, `None` if the multi-processor communication mode is
+/// disabled (default).
+static MPCM_ADDRESS: Mutex<Option<u8>> = Mutex::new(None);
+
+impl Serial {
+    /// Enable or disable 9-bit frames, which are required for the multi-processor communication
+    /// mode. The 9th bit distinguishes address frames (set) from data frames (cleared). All
+    /// devices on the bus, including the master, must use the same frame size.
+    pub fn set_9bit_frames(enable: bool) {
+        if enable {
+            UCSRB::set_mask_raw(UCSZ2);
+        } else {
+            UCSRB::unset_mask_raw(UCSZ2 | TXB8);
+        }
+    }
+
+    /// Enable the multi-processor communication mode (`MPCM`) as a slave with the given `address`,
+    /// e.g., to build an addressable multi-drop (RS-485 style) bus of several robots. This also
+    /// enables 9-bit frames.
+    ///
+    /// While enabled, the hardware ignores all data frames until an address frame matching
+    /// `address` is received, see `Serial::write_address`. The following data frames are then
+    /// stored in the receive ringbuffer as usual, until an address frame of another device is
+    /// received. Address frames themselves are never stored in the ringbuffer. Requires the
+    /// `USART_RXC` interrupt to be enabled, which is the default after `Serial::init`.
+    pub fn enable_mpcm(address: u8) {
+        interrupt::without_interrupts(|cs| {
+            MPCM_ADDRESS.lock(cs).set(Some(address));
+            Self::set_9bit_frames(true);
+            Self::set_mpcm(true);
+        });
+    }
+
+    /// Disable the multi-processor communication mode and return to 8-bit frames, i.e., receive
+    /// all frames again.
+    pub fn disable_mpcm() {
+        interrupt::without_interrupts(|cs| {
+            MPCM_ADDRESS.lock(cs).set(None);
+            Self::set_mpcm(false);
+            Self::set_9bit_frames(false);
+        });
+    }
+
+    /// Write an address frame to select the slave with the given `address` on a multi-processor
+    /// bus. Subsequent bytes written via `Serial::write_raw` (and thus `Serial::write`) are sent
+    /// as data frames to this slave. Requires 9-bit frames, see `Serial::set_9bit_frames`.
+    pub fn write_address(address: u8) {
+        Self::flush();
+        Self::wait_until_clear_to_send();
+        UCSRA::wait_until_mask_set_raw(UDRE);
+        // the 9th bit must be written before `UDR`
+        UCSRB::set_mask_raw(TXB8);
+        UDR::write(address);
+        // keep the 9th bit until the frame has been moved to the transmit shift register
+        UCSRA::wait_until_mask_set_raw(UDRE);
+        UCSRB::unset_mask_raw(TXB8);
+    }
+
+    /// Handle an address frame received by the `USART_RXC` interrupt. If the multi-processor
+    /// communication mode is enabled, data frames are only received after an address frame
+    /// matching the own address. Returns `false` if the multi-processor communication mode is
+    /// disabled, i.e., the frame is to be treated as data.
+    pub(super) fn on_address_frame(cs: &CriticalSection, address: u8) -> bool {
+        match MPCM_ADDRESS.lock(cs).get() {
+            Some(own_address) => {
+                Self::set_mpcm(address != own_address);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Set or clear the `MPCM` bit. The status flags in `UCSRA` must be written as zero (and `TXC`
+    /// would be cleared by writing a one), so only `U2X` is preserved.
+    fn set_mpcm(enable: bool) {
+        let mpcm = if enable { MPCM } else { 0 };
+        UCSRA::write((UCSRA::read() & U2X) | mpcm);
+    }
+}
@@ -0,0 +1,100 @@
+use super::Serial;
+use core::num::IntErrorKind;
+
+/// Maximum length of a number token, sufficient for any `i32` in decimal and any `u32` in hex
+/// including a `0x` prefix. Longer tokens are reported as `ParseError::Overflow`.
+const MAX_TOKEN_LENGTH: usize = 12;
+
+/// Errors that may occur when reading a number from the `Serial` connection.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseError {
+    /// A line ending was received before any digits.
+    Empty,
+    /// The token contains a character that is not a valid digit.
+    InvalidDigit,
+    /// The number does not fit into the requested type.
+    Overflow,
+}
+
+impl Serial {
+    /// Reads a single whitespace-separated token from the receive ringbuffer (blocking) into
+    /// `buffer`. Leading spaces and tabs are skipped, while a line ending before the token results
+    /// in `ParseError::Empty`.
+    fn read_token(buffer: &mut [u8; MAX_TOKEN_LENGTH]) -> Result<&str, ParseError> {
+        let mut byte = Self::read_text_byte();
+        while byte == b' ' || byte == b'\t' {
+            byte = Self::read_text_byte();
+        }
+
+        let mut length = 0;
+        let mut too_long = false;
+        while !byte.is_ascii_whitespace() {
+            if length < buffer.len() {
+                buffer[length] = byte;
+                length += 1;
+            } else {
+                too_long = true;
+            }
+            byte = Self::read_text_byte();
+        }
+
+        if length == 0 {
+            Err(ParseError::Empty)
+        } else if too_long {
+            Err(ParseError::Overflow)
+        } else {
+            core::str::from_utf8(&buffer[..length]).map_err(|_| ParseError::InvalidDigit)
+        }
+    }
+
+    /// Strips an optional `0x` or `0X` prefix from a hexadecimal token.
+    fn strip_hex_prefix(token: &str) -> &str {
+        token
+            .strip_prefix("0x")
+            .or_else(|| token.strip_prefix("0X"))
+            .unwrap_or(token)
+    }
+}
+
+/// Maps the errors of `from_str_radix` to `ParseError`.
+fn to_parse_error(kind: &IntErrorKind) -> ParseError {
+    match kind {
+        IntErrorKind::Empty => ParseError::Empty,
+        IntErrorKind::PosOverflow | IntErrorKind::NegOverflow => ParseError::Overflow,
+        _ => ParseError::InvalidDigit,
+    }
+}
+
+/// Implement `Serial::read_<type>` for reading decimal numbers and optionally
+/// `Serial::read_hex_<type>` for reading hexadecimal numbers.
+macro_rules! impl_serial_read_num {
+    ($type: ty, $read_dec: ident $(,)?) => {
+        impl Serial {
+            #[doc = concat!("Reads a whitespace-terminated decimal `", stringify!($type), "` from the `Serial` connection (blocking).")]
+            pub fn $read_dec() -> Result<$type, ParseError> {
+                let mut buffer = [0; MAX_TOKEN_LENGTH];
+                let token = Self::read_token(&mut buffer)?;
+                token.parse::<$type>().map_err(|error| to_parse_error(error.kind()))
+            }
+        }
+    };
+    ($type: ty, $read_dec: ident, $read_hex: ident $(,)?) => {
+        impl_serial_read_num!($type, $read_dec);
+
+        impl Serial {
+            #[doc = concat!("Reads a whitespace-terminated hexadecimal `", stringify!($type), "`, optionally prefixed by `0x`, from the `Serial` connection (blocking).")]
+            pub fn $read_hex() -> Result<$type, ParseError> {
+                let mut buffer = [0; MAX_TOKEN_LENGTH];
+                let token = Self::strip_hex_prefix(Self::read_token(&mut buffer)?);
+                <$type>::from_str_radix(token, 16).map_err(|error| to_parse_error(error.kind()))
+            }
+        }
+    };
+}
+
+impl_serial_read_num!(u8, read_u8, read_hex_u8);
+impl_serial_read_num!(u16, read_u16, read_hex_u16);
+impl_serial_read_num!(u32, read_u32, read_hex_u32);
+impl_serial_read_num!(i8, read_i8);
+impl_serial_read_num!(i16, read_i16);
+impl_serial_read_num!(i32, read_i32);
@@ -0,0 +1,65 @@
+use super::Serial;
+
+/// Smallest radix supported by `Serial::write_radix`.
+pub const MIN_RADIX: u8 = 2;
+/// Largest radix supported by `Serial::write_radix`, using the digits `0-9` and `a-z`.
+pub const MAX_RADIX: u8 = 36;
+
+/// Error returned when writing a number with a radix outside of `MIN_RADIX..=MAX_RADIX`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InvalidRadix(pub u8);
+
+impl Serial {
+    /// Write an `i32` in the given `radix` (2 to 36) to the `Serial` connection, e.g., base 36 for
+    /// compact IDs. Digits above 9 are written as lowercase letters and negative values get a
+    /// leading minus. Returns `InvalidRadix` without writing anything for an unsupported radix.
+    pub fn write_radix(value: i32, radix: u8) -> Result<(), InvalidRadix> {
+        Self::check_radix(radix)?;
+        if value < 0 {
+            Self::write_raw(b'-');
+        }
+        Self::write_digits(value.unsigned_abs(), radix);
+        Ok(())
+    }
+
+    /// Write a `u32` in the given `radix` (2 to 36) to the `Serial` connection, see
+    /// `Serial::write_radix`.
+    pub fn write_radix_u32(value: u32, radix: u8) -> Result<(), InvalidRadix> {
+        Self::check_radix(radix)?;
+        Self::write_digits(value, radix);
+        Ok(())
+    }
+
+    /// Check that `radix` is within `MIN_RADIX..=MAX_RADIX`.
+    fn check_radix(radix: u8) -> Result<(), InvalidRadix> {
+        if (MIN_RADIX..=MAX_RADIX).contains(&radix) {
+            Ok(())
+        } else {
+            Err(InvalidRadix(radix))
+        }
+    }
+
+    /// Write the digits of `n` in the given valid `radix`. The digits are computed from the least
+    /// significant one into a stack buffer, as `core::fmt` only supports the bases 2, 8, 10 and 16.
+    pub(super) fn write_digits(mut n: u32, radix: u8) {
+        // `u32::MAX` has 32 binary digits
+        let mut digits = [0u8; 32];
+        let mut start = digits.len();
+        let radix = u32::from(radix);
+        loop {
+            start -= 1;
+            digits[start] = match (n % radix) as u8 {
+                digit @ 0..=9 => b'0' + digit,
+                digit => b'a' + digit - 10,
+            };
+            n /= radix;
+            if n == 0 {
+                break;
+            }
+        }
+
+        for &digit in &digits[start..] {
+            Self::write_raw(digit);
+        }
+    }
+}
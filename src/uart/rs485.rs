@@ -0,0 +1,213 @@
+use super::Serial;
+use crate::{
+    delay_us,
+    interrupt::{self, mutex::Mutex},
+    Pin, RobotBase,
+};
+use core::marker::PhantomData;
+
+/// Time in µs between enabling the driver of the transceiver and sending the first start bit,
+/// covering the driver enable time of common transceivers, including slew-rate limited ones.
+pub const RS485_DRIVER_ENABLE_US: u32 = 10;
+
+/// Progress of `Rs485::send`, shared with the `USART_TXC` interrupt via `Rs485::release_bus`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Transmission {
+    /// All bytes of the frame have been queued, if any, so that the next completion ends it.
+    Idle,
+    /// `Rs485::send` is still queueing bytes of the frame, so that a completion must not end it.
+    Queueing,
+    /// The transmitter ran dry while `Rs485::send` was queueing, which then has to end the frame
+    /// itself if it has no bytes left.
+    Drained,
+}
+
+/// Progress of the transmission, see `Transmission`.
+static TRANSMISSION: Mutex<Transmission> = Mutex::new(Transmission::Idle);
+
+/// Half-duplex RS-485 transceiver, e.g., a MAX485, connected to the `Serial` connection, whose
+/// driver is enabled via pin `DE`, e.g., for a multi-drop bus connecting several robots or
+/// controllers.
+///
+/// The transceiver receives while `DE` is low, and drives the bus while `DE` is high. Hence, `DE`
+/// is raised by `Rs485::send` before the first byte, and lowered again by the `USART_TXC`
+/// interrupt once the stop bit of the last byte has left the shift register, see
+/// `Serial::on_transmit_complete`. Lowering it on `UDRE` instead would cut off the last byte,
+/// whereas lowering it later than necessary would collide with the answer of another node. If the
+/// transmitter runs dry while `Rs485::send` still waits for space in the transmit ringbuffer, e.g.,
+/// because the idle hook takes long, `DE` stays high, so that the frame is not split.
+///
+/// The turnaround timing is as follows: the first start bit is sent `RS485_DRIVER_ENABLE_US`
+/// after raising `DE`. After the last stop bit, `DE` is lowered with the latency of the
+/// interrupt, i.e., typically within 10µs, but later if interrupts are disabled meanwhile, e.g.,
+/// by a 1-Wire slot for up to 70µs. Thus, other nodes should wait for at least 100µs before
+/// answering, which is less than the duration of a single byte at `BAUD_LOW`.
+///
+/// If the receiver enable pin `/RE` of the transceiver is tied to `DE`, as usual, the sent bytes
+/// are not received. Otherwise, they are echoed into the receive ringbuffer, and have to be
+/// discarded, e.g., via `Serial::drain` after `Rs485::flush`.
+///
+/// Example:
+/// ```rust
+/// let bus = Rs485::<port::ADC0>::new();
+/// bus.init();
+/// bus.send(b"\x01PING");
+/// bus.flush();
+/// let answer = Serial::read_byte();
+/// ```
+pub struct Rs485<DE: Pin> {
+    _pin: PhantomData<fn() -> DE>,
+}
+
+impl<DE: Pin> Rs485<DE> {
+    /// Create a new transceiver. Call `init` before the first transfer.
+    pub const fn new() -> Self {
+        Self { _pin: PhantomData }
+    }
+
+    /// Switch the transceiver to receiving and register the hook lowering `DE` via
+    /// `Serial::on_transmit_complete`, which replaces any other such hook. `Serial::init` has to
+    /// be called before, e.g., by `RobotBase::init`.
+    pub fn init(&self) {
+        DE::set_low();
+        DE::set_output();
+        Serial::on_transmit_complete(Self::release_bus);
+    }
+
+    /// Send `data` over the bus, enabling the driver of the transceiver until the last byte has
+    /// been sent, even if the transmitter runs dry in between. Blocks until a previous
+    /// transmission has completed, and until all bytes have been queued via
+    /// `Serial::write_nonblocking`, invoking the idle hook meanwhile (see
+    /// `RobotBase::set_idle_hook`), but returns while they are still being sent, see
+    /// `Rs485::flush`.
+    ///
+    /// All bytes have to be sent via this function while the transceiver is used: other writes to
+    /// the `Serial` connection would either be sent with the driver disabled, or complete the
+    /// transmission early if written in between.
+    pub fn send(&self, data: &[u8]) {
+        if data.is_empty() {
+            return;
+        }
+        self.flush();
+        // the transmitter is idle, so its `TXC` flag has been handled already and is set again
+        // once the queued bytes have been sent, which only ends the frame if no bytes are left
+        let mut remaining = interrupt::without_interrupts(|cs| {
+            TRANSMISSION.lock(cs).set(Transmission::Queueing);
+            DE::set_high();
+            delay_us(RS485_DRIVER_ENABLE_US);
+            &data[Serial::write_nonblocking(data)..]
+        });
+        while !remaining.is_empty() {
+            RobotBase::idle();
+            remaining = interrupt::without_interrupts(|cs| {
+                // the transmitter may have run dry meanwhile, but continues with these bytes
+                TRANSMISSION.lock(cs).set(Transmission::Queueing);
+                &remaining[Serial::write_nonblocking(remaining)..]
+            });
+        }
+        interrupt::without_interrupts(|cs| {
+            let transmission = TRANSMISSION.lock(cs);
+            if transmission.get() == Transmission::Drained {
+                DE::set_low();
+            }
+            transmission.set(Transmission::Idle);
+        });
+    }
+
+    /// Check whether a transmission is in progress, i.e., whether the driver is enabled.
+    pub fn is_transmitting(&self) -> bool {
+        DE::is_high()
+    }
+
+    /// Block until the transmission in progress, if any, has completed and the transceiver has
+    /// switched back to receiving, e.g., before waiting for an answer. Invokes the idle hook
+    /// meanwhile, see `RobotBase::set_idle_hook`.
+    pub fn flush(&self) {
+        while self.is_transmitting() {
+            RobotBase::idle();
+        }
+    }
+
+    /// Disable the driver, switching the transceiver to receiving, unless `send` is still queueing
+    /// bytes of the frame. Invoked by the `USART_TXC` interrupt.
+    fn release_bus() {
+        interrupt::without_interrupts(|cs| {
+            let transmission = TRANSMISSION.lock(cs);
+            if transmission.get() == Transmission::Queueing {
+                transmission.set(Transmission::Drained);
+            } else {
+                DE::set_low();
+            }
+        });
+    }
+}
+
+impl<DE: Pin> Default for Rs485<DE> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Rs485;
+    use crate::{
+        avr::{
+            bitmasks::UDRE,
+            mock,
+            registers::{PORTA, UCSRA},
+        },
+        port::ADC0,
+        Register, RobotBase, Serial, TX_BUFFER_SIZE,
+    };
+
+    type Bus = Rs485<ADC0>;
+
+    /// Check whether the driver is enabled via `ADC0`, i.e., `PA0`.
+    fn driver_enabled() -> bool {
+        PORTA::read() & 1 != 0
+    }
+
+    #[test]
+    fn completion_after_the_last_byte_releases_the_bus() {
+        let sent = mock::capture_serial(|| {
+            let bus = Bus::new();
+            bus.init();
+            // `init` acknowledges `TXC` by writing `UCSRA`, which clears `UDRE` in the mock
+            mock::preset::<UCSRA>(UDRE);
+            bus.send(b"PING");
+            assert!(driver_enabled());
+            Serial::flush();
+            // `USART_TXC` invokes the hook after the last byte
+            Bus::release_bus();
+            assert!(!driver_enabled());
+        });
+        assert_eq!(sent, b"PING");
+    }
+
+    #[test]
+    fn completion_while_queueing_keeps_the_bus() {
+        /// Send all queued bytes and complete the transmission while `send` waits for space.
+        fn run_dry() {
+            Serial::flush();
+            Bus::release_bus();
+            assert!(driver_enabled());
+        }
+
+        let frame = [0x55; TX_BUFFER_SIZE + 10];
+        let sent = mock::capture_serial(|| {
+            let bus = Bus::new();
+            bus.init();
+            // `init` acknowledges `TXC` by writing `UCSRA`, which clears `UDRE` in the mock
+            mock::preset::<UCSRA>(UDRE);
+            RobotBase::set_idle_hook(run_dry);
+            bus.send(&frame);
+            RobotBase::clear_idle_hook();
+            assert!(driver_enabled());
+            Serial::flush();
+            Bus::release_bus();
+            assert!(!driver_enabled());
+        });
+        assert_eq!(sent, frame);
+    }
+}
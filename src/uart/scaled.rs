@@ -0,0 +1,142 @@
+use super::{Serial, SerialWritable};
+
+/// Largest number of fractional digits written by `Scaled`.
+pub const MAX_SCALED_DECIMALS: u8 = 9;
+
+/// Newtype to write a raw sensor reading scaled to engineering units via the `Serial` connection,
+/// e.g., ADC counts to mV, mm or °C, without using floats. Writes `raw * num / den` as decimal with
+/// `decimals` fractional digits, rounded half away from zero. A `den` of zero is written as `NaN`.
+///
+/// Example:
+/// ```rust
+/// // a 10-bit reading against a reference of 5V, in V with 3 fractional digits
+/// println!(Scaled::new(reading, 5, 1024).with_decimals(3), "V"); // e.g., writes "2.500V"
+/// // the same reading in mV without fractional digits
+/// println!(Scaled::new(reading, 5000, 1024), "mV"); // e.g., writes "2500mV"
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Scaled {
+    /// The raw reading.
+    pub raw: u16,
+    /// Numerator of the scale factor.
+    pub num: i16,
+    /// Denominator of the scale factor.
+    pub den: i16,
+    /// Number of fractional digits, at most `MAX_SCALED_DECIMALS`.
+    pub decimals: u8,
+}
+
+impl Scaled {
+    /// Scale `raw` by `num / den`, written without fractional digits.
+    pub const fn new(raw: u16, num: i16, den: i16) -> Self {
+        Self {
+            raw,
+            num,
+            den,
+            decimals: 0,
+        }
+    }
+
+    /// Write the scaled value with `decimals` fractional digits, limited to `MAX_SCALED_DECIMALS`.
+    pub const fn with_decimals(mut self, decimals: u8) -> Self {
+        self.decimals = if decimals < MAX_SCALED_DECIMALS {
+            decimals
+        } else {
+            MAX_SCALED_DECIMALS
+        };
+        self
+    }
+}
+
+impl SerialWritable for Scaled {
+    fn write_to_serial(&self) {
+        if self.den == 0 {
+            Serial::write("NaN");
+            return;
+        }
+
+        // |raw * num| < 2^32 and |den| <= 2^15, so all intermediate values fit into `u32`
+        let negative = (self.num < 0) != (self.den < 0) && self.raw != 0 && self.num != 0;
+        let numerator = u32::from(self.raw) * u32::from(self.num.unsigned_abs());
+        let denominator = u32::from(self.den.unsigned_abs());
+        let mut integer = numerator / denominator;
+        let mut remainder = numerator % denominator;
+
+        // long division for the fractional digits, i.e., `fraction / 10^decimals`
+        let decimals = self.decimals.min(MAX_SCALED_DECIMALS);
+        let mut fraction = 0u32;
+        for _ in 0..decimals {
+            remainder *= 10;
+            fraction = fraction * 10 + remainder / denominator;
+            remainder %= denominator;
+        }
+
+        // round half away from zero, carrying into the integer part
+        if 2 * remainder >= denominator {
+            fraction += 1;
+            if fraction == 10u32.pow(decimals.into()) {
+                fraction = 0;
+                integer += 1;
+            }
+        }
+
+        Serial::write_decimal(negative, integer, fraction, decimals);
+    }
+}
+
+impl Serial {
+    /// Writes the fixed-point number `value / 10^decimals` as decimal with exactly `decimals`
+    /// fractional digits (at most `MAX_SCALED_DECIMALS`), e.g., a length of -1234µm as `"-1.234"`
+    /// mm with `Serial::write_fixed(-1234, 3)`.
+    pub fn write_fixed(value: i32, decimals: u8) {
+        let decimals = decimals.min(MAX_SCALED_DECIMALS);
+        let magnitude = value.unsigned_abs();
+        let place = 10u32.pow(decimals.into());
+        Self::write_decimal(value < 0, magnitude / place, magnitude % place, decimals);
+    }
+
+    /// Writes `integer.fraction`, where `fraction` has `decimals` digits including leading zeros,
+    /// with a minus sign if `negative` and the value is not zero.
+    fn write_decimal(negative: bool, integer: u32, fraction: u32, decimals: u8) {
+        if negative && (integer != 0 || fraction != 0) {
+            Self::write('-');
+        }
+        Self::write_dec_u32(integer);
+        if decimals > 0 {
+            Self::write('.');
+            // pad the fractional digits with leading zeros
+            let mut place = 10u32.pow(u32::from(decimals) - 1);
+            while place > 1 && fraction < place {
+                Self::write('0');
+                place /= 10;
+            }
+            Self::write_dec_u32(fraction);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Scaled;
+    use crate::{avr::mock, Serial};
+
+    fn written(scaled: Scaled) -> std::string::String {
+        let output = mock::capture_serial(|| Serial::write(scaled));
+        std::string::String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn volts_with_decimals() {
+        assert_eq!(written(Scaled::new(512, 5, 1024).with_decimals(3)), "2.500");
+    }
+
+    #[test]
+    fn millivolts_without_decimals() {
+        assert_eq!(written(Scaled::new(512, 5000, 1024)), "2500");
+    }
+
+    #[test]
+    fn zero_denominator() {
+        assert_eq!(written(Scaled::new(512, 5, 0)), "NaN");
+    }
+}
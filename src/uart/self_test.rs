@@ -0,0 +1,46 @@
+use super::Serial;
+use crate::delay_us;
+
+/// Byte pattern transmitted by `Serial::self_test`: alternating bits as well as all-zero and
+/// all-one bytes.
+const SELF_TEST_PATTERN: [u8; 6] = [0x55, 0xAA, 0x00, 0xFF, 0x0F, 0xF0];
+
+/// Time in µs to wait for each byte to be looped back before the self-test fails. A byte takes
+/// about 260µs to be transmitted at `BAUD_LOW`.
+const SELF_TEST_TIMEOUT_US: u16 = 5000;
+
+impl Serial {
+    /// Loopback self-test of the `Serial` connection: transmits a known byte pattern and verifies
+    /// that exactly this pattern is received again. Returns `true` if the test succeeded.
+    ///
+    /// As the atmega32 has no internal loopback mode, this test requires an external loopback,
+    /// i.e., a jumper connecting `TX` (pin `d1`) to `RX` (pin `d0`), e.g., on the robot's
+    /// programming connector with the USB interface unplugged. Bytes received before the test are
+    /// discarded. To avoid hanging if no loopback is present, the test fails if a byte is not
+    /// received within `SELF_TEST_TIMEOUT_US` (5ms). Requires the `USART_RXC` interrupt to be
+    /// enabled, which is the default after `Serial::init`.
+    pub fn self_test() -> bool {
+        Self::drain();
+
+        SELF_TEST_PATTERN.iter().all(|&expected| {
+            Self::write_raw(expected);
+            Self::read_with_timeout(SELF_TEST_TIMEOUT_US) == Some(expected)
+        })
+    }
+
+    /// Reads the oldest byte from the receive ringbuffer, waiting up to `timeout_us` µs for a
+    /// byte to arrive.
+    fn read_with_timeout(timeout_us: u16) -> Option<u8> {
+        let mut waited_us = 0;
+        loop {
+            if let Some(byte) = Self::read() {
+                return Some(byte);
+            }
+            if waited_us >= timeout_us {
+                return None;
+            }
+            delay_us(10);
+            waited_us += 10;
+        }
+    }
+}
@@ -48,12 +48,94 @@ impl SerialWritable for &str {
     }
 }
 
+impl<T: SerialWritable> SerialWritable for Option<T> {
+    /// Write `Some(...)` with the contained value or `None` to the `Serial` connection, e.g., to
+    /// dump the result of a lookup.
+    fn write_to_serial(&self) {
+        match self {
+            Some(value) => {
+                Serial::write("Some(");
+                value.write_to_serial();
+                Serial::write(')');
+            }
+            None => Serial::write("None"),
+        }
+    }
+}
+
+impl<T: SerialWritable, E: SerialWritable> SerialWritable for Result<T, E> {
+    /// Write `Ok(...)` or `Err(...)` with the contained value or error to the `Serial` connection,
+    /// e.g., to dump the result of a fallible operation in a single call. Nested values are
+    /// written recursively, e.g., `Ok(Some(42))`.
+    fn write_to_serial(&self) {
+        match self {
+            Ok(value) => {
+                Serial::write("Ok(");
+                value.write_to_serial();
+            }
+            Err(error) => {
+                Serial::write("Err(");
+                error.write_to_serial();
+            }
+        }
+        Serial::write(')');
+    }
+}
+
+impl SerialWritable for () {
+    /// Write `()` to the `Serial` connection, e.g., as the value of a `Result<(), E>`.
+    fn write_to_serial(&self) {
+        Serial::write("()");
+    }
+}
+
+/// Implement `SerialWritable` for tuples of the given element types, written as `(a, b, ...)`.
+macro_rules! impl_serial_writable_tuple {
+    ($first: ident $(, $rest: ident)+) => {
+        impl<$first: SerialWritable $(, $rest: SerialWritable)+> SerialWritable
+            for ($first, $($rest),+)
+        {
+            /// Write the elements separated by commas and enclosed in parentheses to the `Serial`
+            /// connection, e.g., `(1234, 1201)` for a pair of encoder counts. Nested values are
+            /// written recursively, e.g., `((1, 2), Some(3))`.
+            #[allow(non_snake_case)]
+            fn write_to_serial(&self) {
+                let ($first, $($rest),+) = self;
+                Serial::write('(');
+                $first.write_to_serial();
+                $(
+                    Serial::write(", ");
+                    $rest.write_to_serial();
+                )+
+                Serial::write(')');
+            }
+        }
+    };
+}
+
+impl_serial_writable_tuple!(A, B);
+impl_serial_writable_tuple!(A, B, C);
+impl_serial_writable_tuple!(A, B, C, D);
+
+/// Marker written after formatted output that was truncated because it exceeded its buffer.
+pub const TRUNCATION_MARKER: &str = "...";
+
 /// Trait to allow instantiation and passing as `&str` for a type.
 pub trait StringType: uWrite {
     /// Instantiate the `StringType`.
     fn new() -> Self;
     /// Allow passing the `StringType` as `&str`.
     fn as_str(&self) -> &str;
+
+    /// Write the formatted contents of the buffer to the `Serial` connection. If formatting failed
+    /// (`result` is an `Err`), e.g., because the buffer is too small, the written part is followed
+    /// by `TRUNCATION_MARKER` instead of panicking.
+    fn write_formatted<E>(&self, result: Result<(), E>) {
+        Serial::write(self.as_str());
+        if result.is_err() {
+            Serial::write(TRUNCATION_MARKER);
+        }
+    }
 }
 
 impl<const N: usize> StringType for String<N> {
@@ -73,8 +155,8 @@ pub trait SerialWritableDecimal: uDisplay {
     /// Format the given number as decimal and write it to the `Serial` connection.
     fn write_to_serial_as_dec(&self) {
         let mut buffer = Self::DecimalString::new();
-        let _ = uwrite!(&mut buffer, "{}", *self);
-        Serial::write(StringType::as_str(&buffer));
+        let result = uwrite!(&mut buffer, "{}", *self);
+        buffer.write_formatted(result);
     }
 }
 
@@ -86,19 +168,28 @@ impl<T: SerialWritableDecimal> SerialWritable for T {
     }
 }
 
-/*
-/// Trait to allow implementing specific `Serial::write_bin` behavior for types.
-pub trait SerialWritableBinary: Binary {
-    type BinaryString: StringType;
+/// Trait to allow implementing specific `Serial::write_bin` behavior for types. As `ufmt` does not
+/// support binary formatting, the digits are written directly bit by bit.
+pub trait SerialWritableBinary {
+    /// Number of bits of the type.
+    const BITS: u32;
 
-    /// Format the given number as binary and write it to the `Serial` connection.
+    /// Check if the bit at `index` is set, using the two's complement for signed types.
+    fn is_bit_set(&self, index: u32) -> bool;
+
+    /// Format the given number as binary (without leading zeros) and write it to the `Serial`
+    /// connection.
     fn write_to_serial_as_bin(&self) {
-        let mut buffer = Self::BinaryString::new();
-        let _ = write!(&mut buffer, "{:b}", self);
-        Serial::write(StringType::as_str(&buffer));
+        let mut started = false;
+        for index in (0..Self::BITS).rev() {
+            let set = self.is_bit_set(index);
+            started |= set || index == 0;
+            if started {
+                Serial::write(if set { '1' } else { '0' });
+            }
+        }
     }
 }
-*/
 
 /*
 /// Trait to allow implementing specific `Serial::write_exp` behavior for types.
@@ -108,8 +199,8 @@ pub trait SerialWritableExponential: LowerExp {
     /// Format the given number as decimal and write it to the `Serial` connection.
     fn write_to_serial_as_exp(&self) {
         let mut buffer = Self::ExponentialString::new();
-        let _ = write!(&mut buffer, "{:e}", self);
-        Serial::write(StringType::as_str(&buffer));
+        let result = write!(&mut buffer, "{:e}", self);
+        buffer.write_formatted(result);
     }
 }
 */
@@ -121,8 +212,8 @@ pub trait SerialWritableHexadecimal: uDisplayHex {
     /// Format the given number as hexadecimal and write it to the `Serial` connection.
     fn write_to_serial_as_hex(&self) {
         let mut buffer = Self::HexadecimalString::new();
-        let _ = uwrite!(&mut buffer, "{:x}", *self);
-        Serial::write(StringType::as_str(&buffer));
+        let result = uwrite!(&mut buffer, "{:x}", *self);
+        buffer.write_formatted(result);
     }
 }
 
@@ -134,8 +225,8 @@ pub trait SerialWritableOctal: Octal {
     /// Format the given number as octal and write it to the `Serial` connection.
     fn write_to_serial_as_oct(&self) {
         let mut buffer = Self::OctalString::new();
-        let _ = write!(&mut buffer, "{:o}", self);
-        Serial::write(StringType::as_str(&buffer));
+        let result = write!(&mut buffer, "{:o}", self);
+        buffer.write_formatted(result);
     }
 }
 */
@@ -152,11 +243,21 @@ macro_rules! impl_serial_writable_num {
     };
     // implement traits for Binary, Decimal, Hexadecimal and Octal
     ($type: ty, $size_dec: expr, $size_oct: expr $(,)?) => {
-        //impl_serial_writable_num!(@impl $type, Binary, 8 * ::core::mem::size_of::<$type>());
+        impl_serial_writable_num!(@binary $type);
         impl_serial_writable_num!(@impl $type, Decimal, $size_dec);
         impl_serial_writable_num!(@impl $type, Hexadecimal, 2 * ::core::mem::size_of::<$type>());
         //impl_serial_writable_num!(@impl $type, Octal, $size_oct);
     };
+    // implement the trait `SerialWritableBinary` for `$type`.
+    (@binary $type: ty) => {
+        impl SerialWritableBinary for $type {
+            const BITS: u32 = <$type>::BITS;
+
+            fn is_bit_set(&self, index: u32) -> bool {
+                (*self >> index) & 1 != 0
+            }
+        }
+    };
     // implement the trait `SerialWritable{$base_ident}` for `$type`.
     (@impl $type: ty, $base_name: ident, $size: expr) => {
         paste::paste! {
@@ -192,3 +293,90 @@ macro_rules! impl_serial_writable_float {
 impl_serial_writable_float!(f32, 100);
 impl_serial_writable_float!(f64, 100);
 */
+
+#[cfg(test)]
+mod tests {
+    use super::{uwrite, String, StringType, TRUNCATION_MARKER};
+    use crate::{avr::mock, Serial};
+
+    #[test]
+    fn write_formatted_without_truncation() {
+        let output = mock::capture_serial(|| {
+            let mut buffer = String::<4>::new();
+            let result = uwrite!(&mut buffer, "{}", 1234u16);
+            buffer.write_formatted(result);
+        });
+        assert_eq!(output, b"1234");
+    }
+
+    #[test]
+    fn write_formatted_marks_undersized_buffer() {
+        let output = mock::capture_serial(|| {
+            let mut buffer = String::<4>::new();
+            let result = uwrite!(&mut buffer, "{}", 12345u16);
+            buffer.write_formatted(result);
+        });
+        assert_eq!(output, TRUNCATION_MARKER.as_bytes());
+    }
+
+    #[test]
+    fn write_formatted_keeps_the_written_part() {
+        let output = mock::capture_serial(|| {
+            let mut buffer = String::<8>::new();
+            let result = uwrite!(&mut buffer, "x = {} mm", 1000u16);
+            buffer.write_formatted(result);
+        });
+        assert_eq!(output, b"x = 1000...");
+    }
+
+    #[test]
+    fn write_formatted_with_empty_buffer() {
+        let output = mock::capture_serial(|| {
+            let mut buffer = String::<0>::new();
+            let result = uwrite!(&mut buffer, "{}", 0u8);
+            buffer.write_formatted(result);
+        });
+        assert_eq!(output, TRUNCATION_MARKER.as_bytes());
+    }
+
+    #[test]
+    fn nested_option_and_result() {
+        let output = mock::capture_serial(|| {
+            Serial::write(Some(Ok::<i16, &str>(-1234)));
+            Serial::write(' ');
+            Serial::write(Some(Err::<i16, &str>("timeout")));
+            Serial::write(' ');
+            Serial::write(None::<Result<i16, &str>>);
+        });
+        assert_eq!(output, b"Some(Ok(-1234)) Some(Err(timeout)) None");
+    }
+
+    #[test]
+    fn result_of_nested_values() {
+        let output = mock::capture_serial(|| {
+            Serial::write(Ok::<Option<u8>, ()>(Some(42)));
+            Serial::write(' ');
+            Serial::write(Err::<Option<u8>, ()>(()));
+            Serial::write(' ');
+            Serial::write(Ok::<(u8, Option<i32>), ()>((1, Some(i32::MIN))));
+        });
+        assert_eq!(output, b"Ok(Some(42)) Err(()) Ok((1, Some(-2147483648)))");
+    }
+
+    #[test]
+    fn decimal_buffers_fit_the_extreme_values() {
+        let output = mock::capture_serial(|| {
+            Serial::write(i8::MIN);
+            Serial::write(' ');
+            Serial::write(u8::MAX);
+            Serial::write(' ');
+            Serial::write(i32::MIN);
+            Serial::write(' ');
+            Serial::write(i128::MIN);
+        });
+        assert_eq!(
+            output,
+            b"-128 255 -2147483648 -170141183460469231731687303715884105728"
+        );
+    }
+}
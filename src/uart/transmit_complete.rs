@@ -0,0 +1,71 @@
+use super::Serial;
+use crate::{
+    avr::{
+        bitmasks::{MPCM, TXC, TXCIE, U2X},
+        registers::{UCSRA, UCSRB},
+    },
+    interrupt::{self, mutex::Mutex},
+    Register,
+};
+use avr_macros::interrupt;
+
+/// Hook invoked once a transmission has completed, see `Serial::on_transmit_complete`.
+static TRANSMIT_COMPLETE_HOOK: Mutex<Option<fn()>> = Mutex::new(None);
+
+/// Invokes the hook set via `Serial::on_transmit_complete` once the last byte has left the shift
+/// register. Executing the handler clears the `TXC` flag.
+#[interrupt]
+fn USART_TXC() {
+    let hook = interrupt::without_interrupts(|cs| TRANSMIT_COMPLETE_HOOK.lock(cs).get());
+    // more bytes are queued if the transmit ringbuffer was refilled too late to keep the
+    // transmitter busy, so the transmission is not complete yet
+    if Serial::tx_pending() == 0 {
+        if let Some(hook) = hook {
+            hook();
+        }
+    }
+}
+
+impl Serial {
+    /// Set a `hook` that is invoked by the `USART_TXC` interrupt once a transmission has truly
+    /// completed, i.e., the stop bit of the last byte has left the shift register, and enable the
+    /// interrupt. Replaces a previously set hook.
+    ///
+    /// This is the difference to `UDRE`, which only signals that the data register can take the
+    /// next byte while the previous one is still being shifted out for another frame time, i.e.,
+    /// about 260µs at `BAUD_LOW`. The typical use case is a half-duplex RS-485 bus, where the
+    /// driver of the transceiver has to be disabled right after the transmission to release the
+    /// bus for the answer of another node, but not a single bit earlier, see `Rs485`:
+    /// ```rust
+    /// fn release_bus() {
+    ///     port::ADC0::set_low(); // driver enable of the transceiver
+    /// }
+    ///
+    /// Serial::on_transmit_complete(release_bus);
+    /// port::ADC0::set_high();
+    /// Serial::write_nonblocking(&frame);
+    /// ```
+    ///
+    /// The hardware signals completion whenever both the data register and the shift register are
+    /// empty, i.e., also during a gap between two blocking writes. Hence, the hook is not invoked
+    /// while bytes are queued by `Serial::write_nonblocking`, but bytes written one at a time by
+    /// blocking writes may each complete a transmission if they are written too slowly. The hook is
+    /// invoked from an interrupt handler, so it should return quickly and must not block. A stale
+    /// completion of an earlier transmission is discarded when setting the hook.
+    pub fn on_transmit_complete(hook: fn()) {
+        interrupt::without_interrupts(|cs| {
+            TRANSMIT_COMPLETE_HOOK.lock(cs).set(Some(hook));
+            // clear the flag by writing a logical one, keeping the writable configuration bits
+            UCSRA::write((UCSRA::read() & (U2X | MPCM)) | TXC);
+            UCSRB::set_mask_raw(TXCIE);
+        });
+    }
+
+    /// Disable the `USART_TXC` interrupt and remove the hook set via `Serial::on_transmit_complete`.
+    pub fn clear_transmit_complete_hook() {
+        interrupt::without_interrupts(|cs| {
+            UCSRB::unset_mask_raw(TXCIE);
+            TRANSMIT_COMPLETE_HOOK.lock(cs).set(None);
+        });
+    }
+}
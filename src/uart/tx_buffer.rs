@@ -0,0 +1,91 @@
+use super::Serial;
+use crate::{
+    avr::{
+        bitmasks::{UDRE, UDRIE},
+        registers::{UCSRA, UCSRB, UDR},
+    },
+    collections::RingBuffer,
+    interrupt::{self, mutex::DynamicMutex},
+    Register, RobotBase,
+};
+use avr_macros::interrupt;
+
+/// Size of the ringbuffer storing bytes to be sent via the `Serial` connection by
+/// `Serial::write_nonblocking`. One slot always stays unused, i.e., the ringbuffer holds up to
+/// `TX_BUFFER_SIZE - 1` bytes.
+pub const TX_BUFFER_SIZE: usize = 64;
+
+/// Ringbuffer storing the bytes queued by `Serial::write_nonblocking` until the `USART_UDRE`
+/// interrupt sends them.
+static TX_BUFFER: DynamicMutex<RingBuffer<u8, TX_BUFFER_SIZE>> =
+    DynamicMutex::new(RingBuffer::new());
+
+/// Sends the next byte of the transmit ringbuffer whenever `UDR` is empty. The interrupt is
+/// disabled once the ringbuffer is empty, or while the other side pauses via flow control, in which
+/// case the next call to `Serial::write_nonblocking` or `Serial::flush` resumes the transmission.
+#[interrupt]
+fn USART_UDRE() {
+    interrupt::without_interrupts(|cs| {
+        let mut buffer = TX_BUFFER.lock(cs).borrow_mut();
+        if Serial::is_clear_to_send(cs) {
+            if let Some(byte) = buffer.pop() {
+                UDR::write(byte);
+            }
+        }
+        if buffer.is_empty() || !Serial::is_clear_to_send(cs) {
+            UCSRB::unset_mask_raw(UDRIE);
+        }
+    });
+}
+
+impl Serial {
+    /// Queues as many bytes of `data` as fit into the transmit ringbuffer without blocking, and
+    /// returns their number, so that the caller can retry the remainder later, e.g., in the next
+    /// iteration of a cooperative main loop. The queued bytes are sent in the background by the
+    /// `USART_UDRE` interrupt. As the free space is determined inside a critical section, the
+    /// count is exact even while the interrupt sends bytes at the same time.
+    ///
+    /// Blocking writes, e.g., `Serial::write`, first wait until all queued bytes have been sent, so
+    /// that the order of the bytes is preserved. Must not be called from an interrupt handler.
+    pub fn write_nonblocking(data: &[u8]) -> usize {
+        interrupt::without_interrupts(|cs| {
+            let mut buffer = TX_BUFFER.lock(cs).borrow_mut();
+            let accepted = data.iter().take_while(|&&byte| buffer.push(byte)).count();
+            if !buffer.is_empty() {
+                UCSRB::set_mask_raw(UDRIE);
+            }
+            accepted
+        })
+    }
+
+    /// Number of bytes queued by `Serial::write_nonblocking` that have not been sent yet.
+    pub fn tx_pending() -> usize {
+        interrupt::without_interrupts(|cs| TX_BUFFER.lock(cs).borrow().len())
+    }
+
+    /// Blocks until all bytes queued by `Serial::write_nonblocking` have been handed to the
+    /// hardware, invoking the idle hook meanwhile, see `RobotBase::set_idle_hook`. The bytes are
+    /// sent by polling, so this also works while interrupts are disabled, e.g., in a panic handler.
+    pub fn flush() {
+        loop {
+            let flushed = interrupt::without_interrupts(|cs| {
+                let mut buffer = TX_BUFFER.lock(cs).borrow_mut();
+                if UCSRA::is_mask_set_raw(UDRE) && Self::is_clear_to_send(cs) {
+                    if let Some(byte) = buffer.pop() {
+                        UDR::write(byte);
+                    }
+                }
+                if buffer.is_empty() {
+                    UCSRB::unset_mask_raw(UDRIE);
+                    true
+                } else {
+                    false
+                }
+            });
+            if flushed {
+                return;
+            }
+            RobotBase::idle();
+        }
+    }
+}
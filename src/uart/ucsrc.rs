@@ -0,0 +1,98 @@
+use super::Serial;
+#[cfg(not(target_arch = "avr"))]
+use crate::avr::registers::UBRRH;
+use crate::{
+    avr::{bitmasks::URSEL, registers::UCSRC},
+    interrupt, Register,
+};
+#[cfg(target_arch = "avr")]
+use core::{arch::asm, mem::transmute};
+
+impl Serial {
+    /// Reads the frame format register `UCSRC`. On the atmega32, `UCSRC` shares its I/O address
+    /// with `UBRRH`: a single read returns `UBRRH`, while `UCSRC` is only returned by a read in
+    /// the clock cycle directly following a read of that address. Thus, both reads are executed
+    /// by two consecutive `IN` instructions, with interrupts disabled so that no interrupt
+    /// handler is executed in between.
+    #[inline(always)]
+    pub fn read_ucsrc() -> u8 {
+        interrupt::without_interrupts(|_| {
+            #[cfg(target_arch = "avr")]
+            {
+                let ucsrc: u8;
+                unsafe {
+                    asm!(
+                        "in {ubrrh}, {address}",
+                        "in {ucsrc}, {address}",
+                        // I/O address of `UCSRC`, i.e., its memory address minus 0x20
+                        address = const { transmute::<*mut u8, usize>(UCSRC::ADDRESS) - 0x20 },
+                        ubrrh = out(reg) _,
+                        ucsrc = out(reg) ucsrc,
+                        options(nostack, preserves_flags),
+                    )
+                };
+                ucsrc
+            }
+            // the mock models the shared address, see `rp6::avr::mock::read`
+            #[cfg(not(target_arch = "avr"))]
+            {
+                let _ = UBRRH::read();
+                UCSRC::read()
+            }
+        })
+    }
+
+    /// Modifies the frame format register `UCSRC` (character size, parity and stop bits) by
+    /// writing the value returned by `f`, which is given the current value of `UCSRC`.
+    ///
+    /// On the atmega32, `UCSRC` shares its I/O address with the high byte of the baudrate
+    /// register `UBRRH`, and a write goes to `UCSRC` only if the bit `URSEL` is set. Otherwise,
+    /// the write silently corrupts the configured baudrate. This function always sets `URSEL`,
+    /// so `f` does not need to take care of it.
+    ///
+    /// Example:
+    /// ```rust
+    /// // use two stop bits after `Serial::init`
+    /// Serial::modify_ucsrc(|ucsrc| ucsrc | USBS);
+    /// ```
+    pub fn modify_ucsrc(f: impl FnOnce(u8) -> u8) {
+        interrupt::without_interrupts(|_| {
+            let ucsrc = Self::read_ucsrc();
+            UCSRC::write(f(ucsrc) | URSEL);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        avr::{
+            bitmasks::{URSEL, USBS},
+            mock,
+            registers::{UBRRH, UCSRC},
+        },
+        Register, Serial,
+    };
+
+    #[test]
+    fn read_ucsrc_returns_ucsrc_instead_of_ubrrh() {
+        mock::reset();
+        UBRRH::write(0x01);
+        UCSRC::write(URSEL | 0x06);
+        assert_eq!(Serial::read_ucsrc(), URSEL | 0x06);
+        // a single read returns `UBRRH`
+        assert_eq!(UBRRH::read(), 0x01);
+    }
+
+    #[test]
+    fn modify_ucsrc_keeps_the_baudrate() {
+        mock::reset();
+        UBRRH::write(0x01);
+        UCSRC::write(URSEL | 0x06);
+        mock::take_writes::<UCSRC>();
+        Serial::modify_ucsrc(|ucsrc| ucsrc | USBS);
+        assert_eq!(mock::take_writes::<UCSRC>(), [URSEL | USBS | 0x06]);
+        assert_eq!(Serial::read_ucsrc(), URSEL | USBS | 0x06);
+        assert_eq!(UBRRH::read(), 0x01);
+    }
+}